@@ -0,0 +1,130 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Compares the allocating and allocation-light ways of validating a Digest response.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use guardhaus::digest::{Digest, Username};
+use guardhaus::types::{HashAlgorithm, NonceCount, Qop};
+use http::Method;
+use std::hint::black_box;
+
+const PASSWORD: &str = "Circle Of Life";
+
+struct Case {
+    name: &'static str,
+    digest: Digest,
+    simple_hashed_a1: String,
+    entity_body: Vec<u8>,
+}
+
+fn case(name: &'static str, algorithm: HashAlgorithm, qop: Qop, entity_body: Vec<u8>) -> Case {
+    let mut digest = Digest {
+        username: Username::Plain("Mufasa".to_owned()),
+        realm: "testrealm@host.com".to_owned(),
+        nonce: "dcd98b7102dd2f0e8b11d0f600bfb0c093".to_owned(),
+        nonce_count: Some(NonceCount(1)),
+        response: String::new(),
+        request_uri: "/dir/index.html".to_owned(),
+        algorithm,
+        qop: Some(qop),
+        client_nonce: Some("0a4f113b".to_owned()),
+        opaque: Some("5ccc069c403ebaf9f0171e9517f40e41".to_owned()),
+        charset: None,
+        userhash: false,
+    };
+    let simple_hashed_a1 = Digest::simple_hashed_a1(
+        &digest.algorithm,
+        digest.username.clone(),
+        digest.realm.clone(),
+        PASSWORD.to_owned(),
+    );
+    let hashed_a1 = digest
+        .session_hashed_a1(&simple_hashed_a1)
+        .expect("Could not hash the session A1 value");
+    digest.response = digest
+        .using_hashed_a1(Method::POST, &entity_body, hashed_a1)
+        .expect("Could not generate the response");
+    Case {
+        name,
+        digest,
+        simple_hashed_a1,
+        entity_body,
+    }
+}
+
+fn validate(c: &mut Criterion) {
+    let cases = [
+        case("MD5/auth", HashAlgorithm::Md5, Qop::Auth, Vec::new()),
+        case(
+            "SHA-256-sess/auth",
+            HashAlgorithm::Sha256Session,
+            Qop::Auth,
+            Vec::new(),
+        ),
+        case(
+            "SHA-512-256/auth",
+            HashAlgorithm::Sha512256,
+            Qop::Auth,
+            Vec::new(),
+        ),
+        case(
+            "SHA-256/auth-int",
+            HashAlgorithm::Sha256,
+            Qop::AuthInt,
+            vec![b'x'; 4096],
+        ),
+    ];
+    let mut group = c.benchmark_group("validate");
+    for case in &cases {
+        group.bench_with_input(
+            BenchmarkId::new("allocating", case.name),
+            case,
+            |b, case| {
+                b.iter(|| {
+                    let hashed_a1 = case
+                        .digest
+                        .session_hashed_a1(&case.simple_hashed_a1)
+                        .expect("Could not hash the session A1 value");
+                    let valid = case.digest.validate_using_hashed_a1(
+                        Method::POST,
+                        black_box(&case.entity_body),
+                        hashed_a1,
+                    );
+                    assert!(black_box(valid));
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("streaming", case.name), case, |b, case| {
+            b.iter(|| {
+                let valid = case.digest.validate_response_using_simple_hashed_a1(
+                    &Method::POST,
+                    black_box(&case.entity_body),
+                    &case.simple_hashed_a1,
+                );
+                assert!(black_box(valid));
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, validate);
+criterion_main!(benches);
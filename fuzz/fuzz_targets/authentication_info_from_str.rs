@@ -0,0 +1,39 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Parsing any string as `Authentication-Info` parameters must not panic, and anything that parses
+//! from a valid header value must survive an encode/decode round trip unchanged.
+
+#![no_main]
+
+use guardhaus::authentication_info::AuthenticationInfo;
+use headers::{Header, HeaderValue};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &str| {
+    if let Ok(info) = data.parse::<AuthenticationInfo>()
+        && HeaderValue::from_str(data).is_ok_and(|value| value.to_str().is_ok())
+    {
+        let mut values: Vec<HeaderValue> = vec![];
+        info.encode(&mut values);
+        let decoded = AuthenticationInfo::decode(&mut values.iter());
+        assert_eq!(Some(info), decoded.ok(), "{:?}", values);
+    }
+});
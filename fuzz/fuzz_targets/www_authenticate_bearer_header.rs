@@ -0,0 +1,43 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Decoding any `WWW-Authenticate` header value into a `Bearer` challenge must not panic, and
+//! anything decoded must survive an encode/decode round trip.
+
+#![no_main]
+
+use guardhaus::bearer::BearerChallenge;
+use guardhaus::challenge::WwwAuthenticate;
+use headers::{Header, HeaderValue};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(value) = HeaderValue::from_bytes(data)
+        && let Ok(header) = WwwAuthenticate::<BearerChallenge>::decode(&mut std::iter::once(&value))
+    {
+        let mut values: Vec<HeaderValue> = vec![];
+        header.encode(&mut values);
+        if data.iter().all(|b| (0x20..0x7f).contains(b)) {
+            let decoded = WwwAuthenticate::<BearerChallenge>::decode(&mut values.iter())
+                .expect("Encoded challenge should decode");
+            assert_eq!(header, decoded);
+        }
+    }
+});
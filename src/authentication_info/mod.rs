@@ -1,4 +1,4 @@
-// Copyright (c) 2016, 2017, 2020, 2025 Mark Lee
+// Copyright (c) 2016, 2017, 2020, 2025, 2026 Mark Lee
 //
 // Permission is hereby granted, free of charge, to any person obtaining a copy
 // of this software and associated documentation files (the "Software"), to deal
@@ -20,42 +20,91 @@
 
 //! An implementation of the `Authentication-Info` header.
 
-use crate::parsing::{DigestParameters, parse_parameters, unraveled_map_value};
+use crate::digest::Digest;
+use crate::parsing::{DigestParameters, parse_parameters, to_header_value, unraveled_map_value};
 use crate::types::{NonceCount, Qop};
+use http::{HeaderMap, Method};
 use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
+use subtle::ConstantTimeEq;
 use unicase::UniCase;
 
 mod test;
 
 type HeadersResult<T> = Result<T, headers::Error>;
 
+/// The digest carried by an `Authentication-Info` header. Which variant is used determines the
+/// parameter name that it is serialized with.
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ResponseDigest {
+    /// Parameter name `rspauth` (RFC 2617 and RFC 7616), which proves that the server also knows
+    /// the user's secret.
+    #[cfg_attr(feature = "serde", serde(rename = "rspauth"))]
+    ResponseAuth(String),
+    /// Parameter name `digest` (RFC 2069), a digest of the entity headers and body. See
+    /// [`AuthenticationInfo::rfc2069_entity_digest`].
+    #[cfg_attr(feature = "serde", serde(rename = "digest"))]
+    EntityDigest(String),
+}
+
+impl ResponseDigest {
+    /// The hexadecimal digest, regardless of which parameter it came from.
+    pub fn value(&self) -> &str {
+        match *self {
+            ResponseDigest::ResponseAuth(ref value) | ResponseDigest::EntityDigest(ref value) => {
+                value
+            }
+        }
+    }
+}
+
 /// Parameters for the `Authentication-Info` header.
+///
+/// With the `serde` feature, the fields are serialized using their parameter names and values.
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AuthenticationInfo {
-    /// The digest of the entity body, parameter name `digest` in RFC 2069, `rspauth` otherwise
-    pub digest: Option<String>,
+    /// The response digest, parameter name `digest` in RFC 2069, `rspauth` otherwise
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub digest: Option<ResponseDigest>,
     /// `nextnonce` - per RFC 7616, "the nonce the server wishes the client to use for a future
     /// authentication response."
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "nextnonce", default, skip_serializing_if = "Option::is_none")
+    )]
     pub next_nonce: Option<String>,
     /// Quality of protection
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub qop: Option<Qop>,
     /// Cryptographic nonce from the client
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "cnonce", default, skip_serializing_if = "Option::is_none")
+    )]
     pub client_nonce: Option<String>,
     /// Nonce count, parameter name `nc`
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "nc", default, skip_serializing_if = "Option::is_none")
+    )]
     pub nonce_count: Option<NonceCount>,
 }
 
-fn parse_digest(map: &HashMap<UniCase<String>, String>) -> HeadersResult<Option<String>> {
+fn parse_digest(map: &HashMap<UniCase<String>, String>) -> HeadersResult<Option<ResponseDigest>> {
     if let Some(rspauth) = unraveled_map_value(map, "rspauth") {
         if unraveled_map_value(map, "digest").is_some() {
             Err(headers::Error::invalid())
         } else {
-            Ok(Some(rspauth))
+            Ok(Some(ResponseDigest::ResponseAuth(rspauth)))
         }
     } else if let Some(digest) = unraveled_map_value(map, "digest") {
-        Ok(Some(digest))
+        Ok(Some(ResponseDigest::EntityDigest(digest)))
     } else {
         Ok(None)
     }
@@ -65,7 +114,7 @@ impl FromStr for AuthenticationInfo {
     type Err = headers::Error;
 
     fn from_str(s: &str) -> HeadersResult<AuthenticationInfo> {
-        let parameters = parse_parameters(s);
+        let parameters = parse_parameters(s)?;
         let digest = parse_digest(&parameters)?;
         let qop = match Qop::from_parameters(&parameters) {
             Ok(val) => val,
@@ -102,20 +151,14 @@ impl headers::Header for AuthenticationInfo {
         I: Iterator<Item = &'i headers::HeaderValue>,
     {
         let value = values.next().ok_or_else(headers::Error::invalid)?;
-        Self::from_str(
-            value
-                .to_str()
-                .expect("Could not serialize Authentication-Info header value"),
-        )
+        Self::from_str(value.to_str().map_err(|_| headers::Error::invalid())?)
     }
 
     fn encode<E>(&self, values: &mut E)
     where
         E: Extend<headers::HeaderValue>,
     {
-        let value = headers::HeaderValue::from_str(&self.to_string())
-            .expect("Could not generate HeaderValue for Authentication-Info");
-        values.extend(std::iter::once(value));
+        values.extend(std::iter::once(to_header_value(&self.to_string())));
     }
 }
 
@@ -123,8 +166,14 @@ impl fmt::Display for AuthenticationInfo {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut parameters = DigestParameters::new();
 
-        if let Some(ref digest) = self.digest {
-            parameters.append("rspauth", digest, true);
+        match self.digest {
+            Some(ResponseDigest::ResponseAuth(ref digest)) => {
+                parameters.append("rspauth", digest, true)
+            }
+            Some(ResponseDigest::EntityDigest(ref digest)) => {
+                parameters.append("digest", digest, true)
+            }
+            None => {}
         }
 
         if let Some(ref next_nonce) = self.next_nonce {
@@ -146,3 +195,73 @@ impl fmt::Display for AuthenticationInfo {
         write!(f, "{}", parameters)
     }
 }
+
+fn header_str(headers: &HeaderMap, name: http::HeaderName) -> &str {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("")
+}
+
+impl AuthenticationInfo {
+    /// Generates an RFC 2069 entity digest, given the client's `Digest`, the hexadecimal digest of
+    /// its A1 value, and the response that is being sent back.
+    ///
+    /// As defined in [RFC 2069, section 2.1.3](https://tools.ietf.org/html/rfc2069#section-2.1.3),
+    /// the entity digest covers the `Date` header, the `Content-Type`, `Content-Length`,
+    /// `Content-Encoding`, `Last-Modified`, and `Expires` entity headers (any of which are
+    /// treated as empty when missing), and the `entity_body`.
+    pub fn rfc2069_entity_digest(
+        digest: &Digest,
+        method: Method,
+        a1: String,
+        headers: &HeaderMap,
+        entity_body: &[u8],
+    ) -> String {
+        let entity_info = format!(
+            "{}:{}:{}:{}:{}:{}",
+            digest.request_uri,
+            header_str(headers, http::header::CONTENT_TYPE),
+            header_str(headers, http::header::CONTENT_LENGTH),
+            header_str(headers, http::header::CONTENT_ENCODING),
+            header_str(headers, http::header::LAST_MODIFIED),
+            header_str(headers, http::header::EXPIRES),
+        );
+        let data = format!(
+            "{}:{}:{}:{}:{}",
+            digest.nonce,
+            method,
+            header_str(headers, http::header::DATE),
+            digest.algorithm.hex_digest(entity_info.as_bytes()),
+            digest.algorithm.hex_digest(entity_body),
+        );
+        Digest::kd(&digest.algorithm, a1, data)
+    }
+
+    /// Validates an RFC 2069 entity digest (see
+    /// [`rfc2069_entity_digest`](AuthenticationInfo::rfc2069_entity_digest)).
+    ///
+    /// Returns `false` if the header does not carry an RFC 2069 `digest` parameter.
+    pub fn validate_rfc2069_entity_digest(
+        &self,
+        digest: &Digest,
+        method: Method,
+        a1: String,
+        headers: &HeaderMap,
+        entity_body: &[u8],
+    ) -> bool {
+        match self.digest {
+            Some(ResponseDigest::EntityDigest(ref value)) => {
+                let expected = AuthenticationInfo::rfc2069_entity_digest(
+                    digest,
+                    method,
+                    a1,
+                    headers,
+                    entity_body,
+                );
+                bool::from(value.as_bytes().ct_eq(expected.as_bytes()))
+            }
+            _ => false,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-// Copyright (c) 2016, 2025 Mark Lee
+// Copyright (c) 2016, 2025, 2026 Mark Lee
 //
 // Permission is hereby granted, free of charge, to any person obtaining a copy
 // of this software and associated documentation files (the "Software"), to deal
@@ -22,9 +22,12 @@
 
 use std::str::FromStr;
 
-use super::AuthenticationInfo;
+use super::{AuthenticationInfo, ResponseDigest};
+use crate::digest::Digest;
 use crate::parsing::test_helper;
 use crate::types::{NonceCount, Qop};
+use headers::Header;
+use http::{HeaderMap, HeaderValue, Method};
 
 pub fn assert_header_parsing_error(data: &str) {
     test_helper::assert_header_parsing_error(data)
@@ -53,7 +56,7 @@ fn assert_serialized_header_equal(expected: AuthenticationInfo, actual_header: &
 #[test]
 fn test_parse_authentication_info_with_digest_and_nextnonce() {
     let expected = AuthenticationInfo {
-        digest: Some("abcdef".to_owned()),
+        digest: Some(ResponseDigest::ResponseAuth("abcdef".to_owned())),
         next_nonce: Some("fedcba".to_owned()),
         qop: None,
         client_nonce: None,
@@ -65,7 +68,7 @@ fn test_parse_authentication_info_with_digest_and_nextnonce() {
 #[test]
 fn test_parse_authentication_info_with_digest() {
     let expected = AuthenticationInfo {
-        digest: Some("abcdef".to_owned()),
+        digest: Some(ResponseDigest::ResponseAuth("abcdef".to_owned())),
         next_nonce: None,
         qop: None,
         client_nonce: None,
@@ -82,7 +85,7 @@ fn test_parse_authentication_info_with_digest_and_rspauth() {
 #[test]
 fn test_parse_authentication_info_with_qop() {
     let expected = AuthenticationInfo {
-        digest: Some("abcdef".to_owned()),
+        digest: Some(ResponseDigest::ResponseAuth("abcdef".to_owned())),
         next_nonce: None,
         qop: Some(Qop::Auth),
         client_nonce: Some("1234".to_owned()),
@@ -125,7 +128,7 @@ fn test_parse_authentication_info_with_bad_nonce_count() {
 #[test]
 fn test_parse_authentication_info_with_digest_qop_cnonce_and_nc() {
     let expected = AuthenticationInfo {
-        digest: Some("abcdef".to_owned()),
+        digest: Some(ResponseDigest::ResponseAuth("abcdef".to_owned())),
         next_nonce: None,
         qop: Some(Qop::Auth),
         client_nonce: Some("client nonce".to_owned()),
@@ -141,7 +144,7 @@ fn test_parse_authentication_info_with_digest_qop_cnonce_and_nc() {
 #[test]
 fn test_fmt_authentication_info_with_digest_and_nextnonce() {
     let header = AuthenticationInfo {
-        digest: Some("abcdef".to_owned()),
+        digest: Some(ResponseDigest::ResponseAuth("abcdef".to_owned())),
         next_nonce: Some("fedcba".to_owned()),
         qop: None,
         client_nonce: None,
@@ -156,7 +159,7 @@ fn test_fmt_authentication_info_with_digest_and_nextnonce() {
 #[test]
 fn test_fmt_authentication_info_with_digest() {
     let header = AuthenticationInfo {
-        digest: Some("abcdef".to_owned()),
+        digest: Some(ResponseDigest::ResponseAuth("abcdef".to_owned())),
         next_nonce: None,
         qop: None,
         client_nonce: None,
@@ -212,3 +215,151 @@ fn test_fmt_authentication_info_with_nonce_count() {
     };
     assert_serialized_header_equal(header, "Authentication-Info: nc=000000ff");
 }
+
+#[test]
+fn test_parse_authentication_info_with_rfc2069_digest() {
+    let expected = AuthenticationInfo {
+        digest: Some(ResponseDigest::EntityDigest("abcdef".to_owned())),
+        next_nonce: None,
+        qop: None,
+        client_nonce: None,
+        nonce_count: None,
+    };
+    assert_parsed_header_equal(expected, "digest=\"abcdef\"");
+}
+
+#[test]
+fn test_fmt_authentication_info_with_rfc2069_digest() {
+    let header = AuthenticationInfo {
+        digest: Some(ResponseDigest::EntityDigest("abcdef".to_owned())),
+        next_nonce: Some("fedcba".to_owned()),
+        qop: None,
+        client_nonce: None,
+        nonce_count: None,
+    };
+    assert_serialized_header_equal(
+        header,
+        "Authentication-Info: digest=\"abcdef\", nextnonce=\"fedcba\"",
+    );
+}
+
+#[test]
+fn test_rfc2069_entity_digest() {
+    let digest: Digest = "username=\"Mufasa\", realm=\"testrealm@host.com\", \
+                          nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", uri=\"/dir/index.html\", \
+                          response=\"1949323746fe6a43ef61f9606e7febea\""
+        .parse()
+        .expect("Could not parse Digest");
+    let hashed_a1 = "939e7578ed9e3c518a452acee763bce9".to_owned();
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        http::header::DATE,
+        HeaderValue::from_static("Sun, 06 Nov 1994 08:49:37 GMT"),
+    );
+    headers.insert(
+        http::header::CONTENT_TYPE,
+        HeaderValue::from_static("text/html"),
+    );
+    headers.insert(http::header::CONTENT_LENGTH, HeaderValue::from_static("5"));
+
+    let entity_digest = AuthenticationInfo::rfc2069_entity_digest(
+        &digest,
+        Method::GET,
+        hashed_a1.clone(),
+        &headers,
+        b"hello",
+    );
+    assert_eq!("45b1dc410204156305b52882434cdd8e", entity_digest);
+
+    let header = AuthenticationInfo {
+        digest: Some(ResponseDigest::EntityDigest(entity_digest.clone())),
+        next_nonce: None,
+        qop: None,
+        client_nonce: None,
+        nonce_count: None,
+    };
+    assert!(header.validate_rfc2069_entity_digest(
+        &digest,
+        Method::GET,
+        hashed_a1.clone(),
+        &headers,
+        b"hello",
+    ));
+    assert!(!header.validate_rfc2069_entity_digest(
+        &digest,
+        Method::GET,
+        hashed_a1.clone(),
+        &headers,
+        b"goodbye",
+    ));
+
+    let rspauth = AuthenticationInfo {
+        digest: Some(ResponseDigest::ResponseAuth(entity_digest)),
+        ..header
+    };
+    assert!(!rspauth.validate_rfc2069_entity_digest(
+        &digest,
+        Method::GET,
+        hashed_a1,
+        &headers,
+        b"hello",
+    ));
+}
+
+#[test]
+fn test_decode_authentication_info_with_opaque_bytes() {
+    let value = HeaderValue::from_bytes(b"nextnonce=\"\xff\"").expect("Invalid header");
+    assert!(AuthenticationInfo::decode(&mut std::iter::once(&value)).is_err());
+}
+
+#[test]
+fn test_decode_authentication_info_with_parameter_without_value() {
+    let value = HeaderValue::from_static("nextnonce");
+    assert!(AuthenticationInfo::decode(&mut std::iter::once(&value)).is_err());
+}
+
+#[test]
+fn test_decode_authentication_info_with_short_nonce_count() {
+    let value = HeaderValue::from_static("nc=00");
+    assert!(AuthenticationInfo::decode(&mut std::iter::once(&value)).is_err());
+}
+
+#[test]
+fn test_encode_authentication_info_with_control_characters() {
+    let header = AuthenticationInfo {
+        digest: None,
+        next_nonce: Some("fed\ncba".to_owned()),
+        qop: None,
+        client_nonce: None,
+        nonce_count: None,
+    };
+    let mut values = vec![];
+    header.encode(&mut values);
+    assert_eq!(
+        vec![HeaderValue::from_static("nextnonce=\"fed%0Acba\"")],
+        values
+    );
+    let decoded = AuthenticationInfo::decode(&mut values.iter()).expect("Could not decode");
+    assert_eq!(header, decoded);
+}
+
+#[test]
+fn test_encode_and_decode_authentication_info_with_quoted_pairs() {
+    let header = AuthenticationInfo {
+        digest: Some(ResponseDigest::ResponseAuth("abc\"def".to_owned())),
+        next_nonce: Some("fed,\\cba".to_owned()),
+        qop: None,
+        client_nonce: None,
+        nonce_count: None,
+    };
+    let mut values = vec![];
+    header.encode(&mut values);
+    assert_eq!(
+        vec![HeaderValue::from_static(
+            "rspauth=\"abc\\\"def\", nextnonce=\"fed,\\\\cba\""
+        )],
+        values
+    );
+    let decoded = AuthenticationInfo::decode(&mut values.iter()).expect("Could not decode");
+    assert_eq!(header, decoded);
+}
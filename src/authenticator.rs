@@ -0,0 +1,271 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Scheme-agnostic server-side authentication.
+//!
+//! An [`Authenticator`] issues the challenges for one authentication scheme and verifies the
+//! credentials that clients send back. A [`MultiSchemeAuthenticator`] combines several of them,
+//! so that a server can offer every scheme it supports in a single `401 Unauthorized` response
+//! and let the client pick one, as described in
+//! [RFC 7235, section 4.1](https://tools.ietf.org/html/rfc7235#section-4.1).
+
+use http::header::{AUTHORIZATION, HOST, WWW_AUTHENTICATE};
+use http::{HeaderMap, HeaderValue, Method, StatusCode, Uri};
+use thiserror::Error;
+
+mod test;
+
+/// The parts of an HTTP request that credentials can be bound to.
+#[derive(Clone, Copy, Debug)]
+pub struct AuthRequest<'a> {
+    /// The request method.
+    pub method: &'a Method,
+    /// The request target.
+    pub uri: &'a Uri,
+    /// The value of the `Host` header, which `Digest` credentials whose `uri` is in
+    /// absolute-form are checked against when the request target is in origin-form.
+    pub host: Option<&'a str>,
+    /// The entity body, as defined in
+    /// [RFC 2616, section 7.2](https://tools.ietf.org/html/rfc2616#section-7.2). Only needed for
+    /// schemes that protect the body, e.g. `Digest` with `qop=auth-int`.
+    pub body: &'a [u8],
+}
+
+impl<'a> AuthRequest<'a> {
+    /// Describes a request without a body.
+    pub fn new(method: &'a Method, uri: &'a Uri) -> Self {
+        AuthRequest {
+            method,
+            uri,
+            host: None,
+            body: &[],
+        }
+    }
+
+    /// Sets the value of the `Host` header.
+    pub fn with_host(mut self, host: &'a str) -> Self {
+        self.host = Some(host);
+        self
+    }
+
+    /// Sets the entity body.
+    pub fn with_body(mut self, body: &'a [u8]) -> Self {
+        self.body = body;
+        self
+    }
+}
+
+/// Reasons that a request could not be authenticated.
+#[derive(Debug, Error, PartialEq)]
+pub enum AuthenticationError {
+    /// The request did not include an `Authorization` header.
+    #[error("Missing credentials")]
+    MissingCredentials,
+    /// The request used an authentication scheme that the server does not support.
+    #[error("Unsupported authentication scheme: {0}")]
+    UnsupportedScheme(String),
+    /// The credentials could not be parsed.
+    #[error("Malformed credentials")]
+    MalformedCredentials,
+    /// The credentials were valid, but were bound to a server nonce that has expired. The client
+    /// can retry without prompting the user.
+    #[error("Stale nonce")]
+    StaleNonce,
+    /// The credentials were well-formed, but not valid.
+    #[error("Invalid credentials")]
+    InvalidCredentials,
+    /// The credentials were rejected with scheme-specific challenges, e.g. a `Bearer` challenge
+    /// with an `error` parameter.
+    #[error("Credentials rejected with status {status}")]
+    Rejected {
+        /// The status code to respond with.
+        status: StatusCode,
+        /// The challenges to send back to the client.
+        challenges: Vec<HeaderValue>,
+    },
+}
+
+impl AuthenticationError {
+    /// The HTTP status code that the server should respond with.
+    pub fn status_code(&self) -> StatusCode {
+        match *self {
+            AuthenticationError::MalformedCredentials => StatusCode::BAD_REQUEST,
+            AuthenticationError::Rejected { status, .. } => status,
+            _ => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+/// Server-side support for a single authentication scheme.
+pub trait Authenticator {
+    /// Whoever the credentials authenticate, e.g. a username.
+    type Principal;
+
+    /// The authentication scheme, e.g. `Digest`. Schemes are matched case-insensitively.
+    fn scheme(&self) -> &str;
+
+    /// Generates the challenges to send in the `WWW-Authenticate` header. `error` is the reason
+    /// that the previous credentials for this scheme were rejected, if any.
+    fn challenges(&self, error: Option<&AuthenticationError>) -> Vec<HeaderValue>;
+
+    /// Verifies the value of an `Authorization` header that uses this scheme.
+    fn authenticate(
+        &self,
+        value: &HeaderValue,
+        request: &AuthRequest,
+    ) -> Result<Self::Principal, AuthenticationError>;
+}
+
+/// The authentication scheme that the value of an `Authorization` header uses.
+fn scheme_of(value: &HeaderValue) -> Option<&str> {
+    let value = value.to_str().ok()?.trim_start();
+    let scheme = value.split(' ').next()?;
+    (!scheme.is_empty()).then_some(scheme)
+}
+
+/// Why a [`MultiSchemeAuthenticator`] rejected a request, along with the challenges to respond
+/// with.
+#[derive(Debug, PartialEq)]
+pub struct Rejection {
+    /// The reason that the request was rejected.
+    pub error: AuthenticationError,
+    /// The `WWW-Authenticate` header values to respond with.
+    pub challenges: Vec<HeaderValue>,
+}
+
+impl Rejection {
+    /// The HTTP status code that the server should respond with.
+    pub fn status_code(&self) -> StatusCode {
+        self.error.status_code()
+    }
+
+    /// The response headers, i.e. one `WWW-Authenticate` header per challenge.
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for challenge in &self.challenges {
+            headers.append(WWW_AUTHENTICATE, challenge.clone());
+        }
+        headers
+    }
+}
+
+type BoxedAuthenticator<P> = Box<dyn Authenticator<Principal = P> + Send + Sync>;
+
+/// Offers several authentication schemes at once, and dispatches incoming credentials to the
+/// [`Authenticator`] for their scheme.
+///
+/// Challenges are emitted in the order that the authenticators were added, which should be
+/// the server's order of preference.
+pub struct MultiSchemeAuthenticator<P> {
+    authenticators: Vec<BoxedAuthenticator<P>>,
+}
+
+impl<P> MultiSchemeAuthenticator<P> {
+    /// Creates an instance that does not support any schemes.
+    pub fn new() -> Self {
+        MultiSchemeAuthenticator {
+            authenticators: vec![],
+        }
+    }
+
+    /// Adds support for a scheme.
+    pub fn with<A>(mut self, authenticator: A) -> Self
+    where
+        A: Authenticator<Principal = P> + Send + Sync + 'static,
+    {
+        self.authenticators.push(Box::new(authenticator));
+        self
+    }
+
+    /// The supported schemes, in order of preference.
+    pub fn schemes(&self) -> impl Iterator<Item = &str> {
+        self.authenticators
+            .iter()
+            .map(|authenticator| authenticator.scheme())
+    }
+
+    /// The challenges for every supported scheme, e.g. for requests without credentials.
+    pub fn challenges(&self) -> Vec<HeaderValue> {
+        self.authenticators
+            .iter()
+            .flat_map(|authenticator| authenticator.challenges(None))
+            .collect()
+    }
+
+    fn reject(&self, failed: Option<usize>, error: AuthenticationError) -> Rejection {
+        let challenges = self
+            .authenticators
+            .iter()
+            .enumerate()
+            .flat_map(|(index, authenticator)| {
+                let error = (Some(index) == failed).then_some(&error);
+                authenticator.challenges(error)
+            })
+            .collect();
+        Rejection { error, challenges }
+    }
+
+    /// Authenticates a request, given its headers. The credentials are passed to the
+    /// authenticator whose scheme matches (case-insensitively) the one in the `Authorization`
+    /// header.
+    ///
+    /// On failure, every scheme is challenged again; only the scheme that the credentials used
+    /// is told why they were rejected.
+    ///
+    /// If `request` does not have a host, the `Host` header is used.
+    pub fn authenticate(&self, headers: &HeaderMap, request: &AuthRequest) -> Result<P, Rejection> {
+        let Some(value) = headers.get(AUTHORIZATION) else {
+            return Err(self.reject(None, AuthenticationError::MissingCredentials));
+        };
+        let Some(scheme) = scheme_of(value) else {
+            return Err(self.reject(None, AuthenticationError::MalformedCredentials));
+        };
+        let Some(index) = self
+            .authenticators
+            .iter()
+            .position(|authenticator| authenticator.scheme().eq_ignore_ascii_case(scheme))
+        else {
+            let error = AuthenticationError::UnsupportedScheme(scheme.to_owned());
+            return Err(self.reject(None, error));
+        };
+
+        let request = AuthRequest {
+            host: request
+                .host
+                .or_else(|| headers.get(HOST).and_then(|host| host.to_str().ok())),
+            ..*request
+        };
+        self.authenticators[index]
+            .authenticate(value, &request)
+            .map_err(|error| self.reject(Some(index), error))
+    }
+}
+
+impl<P> Default for MultiSchemeAuthenticator<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P> std::fmt::Debug for MultiSchemeAuthenticator<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.schemes()).finish()
+    }
+}
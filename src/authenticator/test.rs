@@ -0,0 +1,194 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::{AuthRequest, AuthenticationError, MultiSchemeAuthenticator};
+use crate::bearer::{Bearer, BearerAuthenticator, BearerChallenge, BearerErrorCode};
+use crate::digest::test_helper::respond_to_challenge;
+use crate::digest::{DigestAuthenticator, DigestChallenge};
+use crate::store::htdigest::{Htdigest, HtdigestRecord};
+use crate::types::HashAlgorithm;
+use headers::authorization::Credentials;
+use http::header::{AUTHORIZATION, HOST, WWW_AUTHENTICATE};
+use http::{HeaderMap, HeaderValue, Method, StatusCode, Uri};
+
+fn validate(bearer: &Bearer) -> Result<String, BearerChallenge> {
+    match bearer.token() {
+        "mF_9.B5f-4.1JqM" => Ok("Mufasa".to_owned()),
+        "read-only" => Err(BearerChallenge {
+            scope: Some("write".to_owned()),
+            ..BearerChallenge::from_error(BearerErrorCode::InsufficientScope)
+        }),
+        _ => Err(BearerChallenge::from_error(BearerErrorCode::InvalidToken)),
+    }
+}
+
+fn authenticator() -> MultiSchemeAuthenticator<String> {
+    let mut store = Htdigest::new();
+    store.insert(HtdigestRecord::new(
+        "Mufasa",
+        "example",
+        "Circle Of Life".to_owned(),
+        &HashAlgorithm::Sha256,
+    ));
+    MultiSchemeAuthenticator::new()
+        .with(
+            DigestAuthenticator::new("example", store)
+                .with_algorithms([HashAlgorithm::Sha256, HashAlgorithm::Sha256Session]),
+        )
+        .with(BearerAuthenticator::new("example", validate))
+}
+
+fn headers(authorization: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        AUTHORIZATION,
+        HeaderValue::from_str(authorization).expect("Invalid header value"),
+    );
+    headers
+}
+
+fn authenticate(
+    authenticator: &MultiSchemeAuthenticator<String>,
+    headers: &HeaderMap,
+) -> Result<String, super::Rejection> {
+    let uri = Uri::from_static("/");
+    authenticator.authenticate(headers, &AuthRequest::new(&Method::GET, &uri))
+}
+
+#[test]
+fn test_challenges() {
+    let authenticator = authenticator();
+    assert_eq!(
+        vec!["Digest", "Bearer"],
+        authenticator.schemes().collect::<Vec<_>>()
+    );
+    let rejection = authenticate(&authenticator, &HeaderMap::new())
+        .expect_err("Missing credentials should be rejected");
+    assert_eq!(AuthenticationError::MissingCredentials, rejection.error);
+    assert_eq!(StatusCode::UNAUTHORIZED, rejection.status_code());
+
+    let headers = rejection.headers();
+    let challenges: Vec<&str> = headers
+        .get_all(WWW_AUTHENTICATE)
+        .iter()
+        .map(|value| value.to_str().expect("Invalid header value"))
+        .collect();
+    assert_eq!(3, challenges.len());
+    assert!(challenges[0].starts_with("Digest ") && challenges[0].contains("SHA-256,"));
+    assert!(challenges[1].contains("algorithm=SHA-256-sess"));
+    assert_eq!("Bearer realm=\"example\"", challenges[2]);
+}
+
+#[test]
+fn test_dispatch_is_case_insensitive() {
+    let authenticator = authenticator();
+    assert_eq!(
+        Ok("Mufasa".to_owned()),
+        authenticate(&authenticator, &headers("bEaReR mF_9.B5f-4.1JqM"))
+    );
+
+    let value = authenticator.challenges().remove(0);
+    let challenge: DigestChallenge =
+        crate::challenge::Challenge::decode(value.to_str().expect("Invalid header value"))
+            .expect("Could not decode challenge");
+    let digest = respond_to_challenge(&challenge, "Mufasa", "Circle Of Life", Method::GET, "/");
+    let value = digest.encode();
+    let value = value.to_str().expect("Invalid header value");
+    let value = format!("DIGEST{}", &value["Digest".len()..]);
+    assert_eq!(
+        Ok("Mufasa".to_owned()),
+        authenticate(&authenticator, &headers(&value))
+    );
+}
+
+#[test]
+fn test_absolute_digest_uri_is_checked_against_host() {
+    let authenticator = authenticator();
+    let value = authenticator.challenges().remove(0);
+    let challenge: DigestChallenge =
+        crate::challenge::Challenge::decode(value.to_str().expect("Invalid header value"))
+            .expect("Could not decode challenge");
+    let digest = respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle Of Life",
+        Method::GET,
+        "http://example.com/",
+    );
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, digest.encode());
+
+    let rejection = authenticate(&authenticator, &headers)
+        .expect_err("Credentials without a Host header should be rejected");
+    assert_eq!(StatusCode::BAD_REQUEST, rejection.status_code());
+
+    headers.insert(HOST, HeaderValue::from_static("evil.example"));
+    let rejection = authenticate(&authenticator, &headers)
+        .expect_err("Credentials for another host should be rejected");
+    assert_eq!(StatusCode::BAD_REQUEST, rejection.status_code());
+
+    headers.insert(HOST, HeaderValue::from_static("Example.com:80"));
+    assert_eq!(
+        Ok("Mufasa".to_owned()),
+        authenticate(&authenticator, &headers)
+    );
+}
+
+#[test]
+fn test_rejection_only_annotates_failed_scheme() {
+    let authenticator = authenticator();
+    let rejection = authenticate(&authenticator, &headers("Bearer expired"))
+        .expect_err("Invalid token should be rejected");
+    assert_eq!(StatusCode::UNAUTHORIZED, rejection.status_code());
+    assert!(!rejection.challenges[0].to_str().unwrap().contains("error"));
+    assert_eq!(
+        "Bearer error=\"invalid_token\"",
+        rejection.challenges[2].to_str().unwrap()
+    );
+
+    let rejection = authenticate(&authenticator, &headers("Bearer read-only"))
+        .expect_err("Token without scope should be rejected");
+    assert_eq!(StatusCode::FORBIDDEN, rejection.status_code());
+
+    let rejection = authenticate(&authenticator, &headers("Bearer not a token"))
+        .expect_err("Malformed token should be rejected");
+    assert_eq!(StatusCode::BAD_REQUEST, rejection.status_code());
+    assert_eq!(
+        "Bearer realm=\"example\", error=\"invalid_request\"",
+        rejection.challenges[2].to_str().unwrap()
+    );
+}
+
+#[test]
+fn test_unsupported_scheme() {
+    let rejection = authenticate(&authenticator(), &headers("Basic TXVmYXNhOg=="))
+        .expect_err("Basic should not be supported");
+    assert_eq!(
+        AuthenticationError::UnsupportedScheme("Basic".to_owned()),
+        rejection.error
+    );
+    assert_eq!(3, rejection.challenges.len());
+
+    let rejection =
+        authenticate(&authenticator(), &headers("")).expect_err("Empty header is malformed");
+    assert_eq!(AuthenticationError::MalformedCredentials, rejection.error);
+}
@@ -0,0 +1,114 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Server-side support for the `Basic` authentication scheme, as defined in
+//! [RFC 7617](https://tools.ietf.org/html/rfc7617).
+//!
+//! The credentials themselves are parsed by [`headers::authorization::Basic`].
+
+use crate::authenticator::{AuthRequest, AuthenticationError, Authenticator};
+use crate::parsing::{DigestParameters, strip_scheme, to_header_value};
+use crate::store::{CredentialStore, PasswordRecord};
+use headers::authorization::{Basic, Credentials};
+use http::HeaderValue;
+
+mod test;
+
+/// An [`Authenticator`] for the `Basic` scheme, which verifies passwords against a credential
+/// store, e.g. an `htpasswd` file (see the `htpasswd` feature).
+///
+/// Challenges advertise `charset="UTF-8"`. The principal is the name of the authenticated user.
+#[derive(Debug)]
+pub struct BasicAuthenticator<S> {
+    realm: String,
+    store: S,
+}
+
+impl<S> BasicAuthenticator<S>
+where
+    S: CredentialStore,
+    S::Record: PasswordRecord,
+{
+    /// Creates an authenticator for the given realm, whose users are held by `store`.
+    pub fn new(realm: &str, store: S) -> Self {
+        BasicAuthenticator {
+            realm: realm.to_owned(),
+            store,
+        }
+    }
+
+    /// The credential store.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// The credential store, e.g. to reload it.
+    pub fn store_mut(&mut self) -> &mut S {
+        &mut self.store
+    }
+
+    /// Verifies `Basic` credentials, returning the name of the user.
+    pub fn verify(&self, credentials: &Basic) -> Result<String, AuthenticationError> {
+        match self.store.get(credentials.username(), &self.realm) {
+            Some(record) if record.verify_password(credentials.password()) => {
+                Ok(credentials.username().to_owned())
+            }
+            _ => Err(AuthenticationError::InvalidCredentials),
+        }
+    }
+}
+
+impl<S> Authenticator for BasicAuthenticator<S>
+where
+    S: CredentialStore,
+    S::Record: PasswordRecord,
+{
+    type Principal = String;
+
+    fn scheme(&self) -> &str {
+        Basic::SCHEME
+    }
+
+    fn challenges(&self, _error: Option<&AuthenticationError>) -> Vec<HeaderValue> {
+        let mut parameters = DigestParameters::new();
+        parameters.append("realm", &self.realm, true);
+        parameters.append("charset", "UTF-8", true);
+        vec![to_header_value(&format!(
+            "{} {}",
+            Basic::SCHEME,
+            parameters
+        ))]
+    }
+
+    fn authenticate(
+        &self,
+        value: &HeaderValue,
+        _request: &AuthRequest,
+    ) -> Result<String, AuthenticationError> {
+        // `Basic::decode` assumes that the scheme has already been checked.
+        let serialized = value
+            .to_str()
+            .map_err(|_| AuthenticationError::MalformedCredentials)?;
+        strip_scheme(serialized, Basic::SCHEME)
+            .and_then(|_| Basic::decode(value))
+            .ok_or(AuthenticationError::MalformedCredentials)
+            .and_then(|credentials| self.verify(&credentials))
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(all(test, feature = "htpasswd"))]
+
+use super::BasicAuthenticator;
+use crate::authenticator::{AuthRequest, AuthenticationError, Authenticator};
+use crate::store::htpasswd::{Htpasswd, HtpasswdRecord, PasswordScheme};
+use headers::Authorization;
+use headers::authorization::Credentials;
+use http::{HeaderValue, Method, Uri};
+
+fn authenticator() -> BasicAuthenticator<Htpasswd> {
+    let mut store = Htpasswd::new();
+    store.insert(
+        HtpasswdRecord::new("Mufasa", "Circle Of Life", &PasswordScheme::Apr1)
+            .expect("Could not hash password"),
+    );
+    BasicAuthenticator::new("WallyWorld", store)
+}
+
+fn authenticate(value: &HeaderValue) -> Result<String, AuthenticationError> {
+    let uri = Uri::from_static("/");
+    authenticator().authenticate(value, &AuthRequest::new(&Method::GET, &uri))
+}
+
+#[test]
+fn test_challenge() {
+    assert_eq!(
+        vec![HeaderValue::from_static(
+            "Basic realm=\"WallyWorld\", charset=\"UTF-8\""
+        )],
+        authenticator().challenges(None)
+    );
+}
+
+#[test]
+fn test_authenticate() {
+    let value = Authorization::basic("Mufasa", "Circle Of Life").0.encode();
+    assert_eq!(Ok("Mufasa".to_owned()), authenticate(&value));
+
+    let value = Authorization::basic("Mufasa", "Circle of Life").0.encode();
+    assert_eq!(
+        Err(AuthenticationError::InvalidCredentials),
+        authenticate(&value)
+    );
+
+    let value = Authorization::basic("Scar", "Circle Of Life").0.encode();
+    assert_eq!(
+        Err(AuthenticationError::InvalidCredentials),
+        authenticate(&value)
+    );
+}
+
+#[test]
+fn test_authenticate_malformed() {
+    for value in ["Basic", "Basic !!!", "Bas", "Basic TXVmYXNh"] {
+        assert_eq!(
+            Err(AuthenticationError::MalformedCredentials),
+            authenticate(&HeaderValue::from_static(value)),
+            "{}",
+            value
+        );
+    }
+}
@@ -0,0 +1,340 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! An implementation of the `Bearer` authentication scheme, as defined in
+//! [RFC 6750](https://tools.ietf.org/html/rfc6750).
+
+use crate::authenticator::{AuthRequest, AuthenticationError, Authenticator};
+use crate::challenge::Challenge;
+use crate::parsing::{
+    DigestParameters, parse_parameters, raw_map_value, strip_scheme, to_header_value,
+    unraveled_map_value,
+};
+use headers::authorization::Credentials;
+use http::{HeaderValue, StatusCode};
+use std::fmt;
+use std::str::FromStr;
+
+mod test;
+
+fn is_token68(s: &str) -> bool {
+    let token = s.trim_end_matches('=');
+    !token.is_empty()
+        && token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-._~+/".contains(c))
+}
+
+/// Bearer credentials, sent by the client in the `Authorization` header.
+///
+/// ## ABNF
+///
+/// ```text
+/// b64token    = 1*( ALPHA / DIGIT / "-" / "." / "_" / "~" / "+" / "/" ) *"="
+/// credentials = "Bearer" 1*SP b64token
+/// ```
+#[derive(Clone, PartialEq)]
+pub struct Bearer {
+    token: String,
+}
+
+impl Bearer {
+    /// The bearer token.
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+}
+
+/// The token is omitted.
+impl fmt::Debug for Bearer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bearer").finish_non_exhaustive()
+    }
+}
+
+impl FromStr for Bearer {
+    type Err = headers::Error;
+
+    /// Parses a bearer token, which must match the `b64token` grammar.
+    fn from_str(s: &str) -> Result<Bearer, headers::Error> {
+        if is_token68(s) {
+            Ok(Bearer {
+                token: s.to_owned(),
+            })
+        } else {
+            Err(headers::Error::invalid())
+        }
+    }
+}
+
+impl Credentials for Bearer {
+    const SCHEME: &'static str = "Bearer";
+
+    fn decode(value: &HeaderValue) -> Option<Self> {
+        let token = strip_scheme(value.to_str().ok()?, Self::SCHEME)?;
+        token.trim_end_matches(' ').parse().ok()
+    }
+
+    fn encode(&self) -> HeaderValue {
+        to_header_value(&format!("{} {}", Self::SCHEME, self.token))
+    }
+}
+
+/// Error codes that a resource server can return in a [`BearerChallenge`], as defined in
+/// [RFC 6750, section 3.1](https://tools.ietf.org/html/rfc6750#section-3.1), or registered
+/// later (see [RFC 6749, section 11.4](https://tools.ietf.org/html/rfc6749#section-11.4)).
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum BearerErrorCode {
+    /// `invalid_request` - the request is missing a parameter, or is otherwise malformed.
+    InvalidRequest,
+    /// `invalid_token` - the token is expired, revoked, malformed, or invalid for other reasons.
+    InvalidToken,
+    /// `insufficient_scope` - the request requires higher privileges than the token provides.
+    InsufficientScope,
+    /// Any other error code, e.g. `insufficient_user_authentication` from
+    /// [RFC 9470](https://tools.ietf.org/html/rfc9470).
+    Other(String),
+}
+
+impl BearerErrorCode {
+    /// The HTTP status code that the resource server should respond with. Other error codes
+    /// default to `401 Unauthorized`.
+    pub fn status_code(&self) -> StatusCode {
+        match *self {
+            BearerErrorCode::InvalidRequest => StatusCode::BAD_REQUEST,
+            BearerErrorCode::InvalidToken | BearerErrorCode::Other(_) => StatusCode::UNAUTHORIZED,
+            BearerErrorCode::InsufficientScope => StatusCode::FORBIDDEN,
+        }
+    }
+}
+
+impl FromStr for BearerErrorCode {
+    type Err = headers::Error;
+
+    /// Parses an error code, which must be made up of the characters allowed by RFC 6750
+    /// (printable ASCII other than `"` and `\`).
+    fn from_str(s: &str) -> Result<BearerErrorCode, headers::Error> {
+        match s {
+            "invalid_request" => Ok(BearerErrorCode::InvalidRequest),
+            "invalid_token" => Ok(BearerErrorCode::InvalidToken),
+            "insufficient_scope" => Ok(BearerErrorCode::InsufficientScope),
+            _ if !s.is_empty()
+                && s.chars()
+                    .all(|c| (' '..='~').contains(&c) && c != '"' && c != '\\') =>
+            {
+                Ok(BearerErrorCode::Other(s.to_owned()))
+            }
+            _ => Err(headers::Error::invalid()),
+        }
+    }
+}
+
+impl fmt::Display for BearerErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BearerErrorCode::InvalidRequest => write!(f, "invalid_request"),
+            BearerErrorCode::InvalidToken => write!(f, "invalid_token"),
+            BearerErrorCode::InsufficientScope => write!(f, "insufficient_scope"),
+            BearerErrorCode::Other(ref code) => write!(f, "{}", code),
+        }
+    }
+}
+
+/// A `Bearer` challenge, sent by the server in the `WWW-Authenticate` header. See
+/// [RFC 6750, section 3](https://tools.ietf.org/html/rfc6750#section-3).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BearerChallenge {
+    /// The protection space
+    pub realm: Option<String>,
+    /// Space-delimited list of scopes required to access the resource
+    pub scope: Option<String>,
+    /// Why the request was rejected, if the client sent a token
+    pub error: Option<BearerErrorCode>,
+    /// Human-readable explanation of the error, meant for the developer
+    pub error_description: Option<String>,
+    /// URI of a human-readable web page explaining the error
+    pub error_uri: Option<String>,
+}
+
+impl BearerChallenge {
+    /// Creates a challenge for the given error code, with no other parameters.
+    pub fn from_error(error: BearerErrorCode) -> BearerChallenge {
+        BearerChallenge {
+            error: Some(error),
+            ..Default::default()
+        }
+    }
+
+    /// The individual scopes in the `scope` parameter.
+    pub fn scopes(&self) -> impl Iterator<Item = &str> {
+        self.scope
+            .iter()
+            .flat_map(|scope| scope.split(' ').filter(|s| !s.is_empty()))
+    }
+
+    /// The HTTP status code that the resource server should respond with: the one associated
+    /// with the error code, or `401 Unauthorized` if there is none.
+    pub fn status_code(&self) -> StatusCode {
+        self.error
+            .as_ref()
+            .map_or(StatusCode::UNAUTHORIZED, BearerErrorCode::status_code)
+    }
+}
+
+impl Challenge for BearerChallenge {
+    const SCHEME: &'static str = "Bearer";
+
+    fn decode(value: &str) -> Option<Self> {
+        let parameters = parse_parameters(strip_scheme(value, Self::SCHEME)?).ok()?;
+        let error = match unraveled_map_value(&parameters, "error") {
+            Some(error) => Some(error.parse().ok()?),
+            None => None,
+        };
+
+        Some(BearerChallenge {
+            realm: unraveled_map_value(&parameters, "realm"),
+            scope: unraveled_map_value(&parameters, "scope"),
+            error,
+            error_description: unraveled_map_value(&parameters, "error_description"),
+            error_uri: raw_map_value(&parameters, "error_uri"),
+        })
+    }
+
+    fn encode(&self) -> HeaderValue {
+        let mut parameters = DigestParameters::new();
+        if let Some(ref realm) = self.realm {
+            parameters.append("realm", realm, true);
+        }
+        if let Some(ref scope) = self.scope {
+            parameters.append("scope", scope, true);
+        }
+        if let Some(ref error) = self.error {
+            parameters.append("error", &error.to_string(), true);
+        }
+        if let Some(ref error_description) = self.error_description {
+            parameters.append("error_description", error_description, true);
+        }
+        if let Some(ref error_uri) = self.error_uri {
+            parameters.append_raw("error_uri", error_uri, true);
+        }
+        let parameters = parameters.to_string();
+        if parameters.is_empty() {
+            to_header_value(Self::SCHEME)
+        } else {
+            to_header_value(&format!("{} {}", Self::SCHEME, parameters))
+        }
+    }
+}
+
+/// Validates bearer tokens, e.g. by looking them up in a database or by verifying a signed token.
+///
+/// Closures of the form `Fn(&Bearer) -> Result<P, BearerChallenge>` implement this trait.
+pub trait TokenValidator {
+    /// Whatever the token grants access as, e.g. a user or a set of scopes.
+    type Principal;
+
+    /// Validates the token. On failure, returns the challenge to send back to the client, which
+    /// should carry an error code (usually [`BearerErrorCode::InvalidToken`]).
+    fn validate(&self, credentials: &Bearer) -> Result<Self::Principal, BearerChallenge>;
+}
+
+impl<F, P> TokenValidator for F
+where
+    F: Fn(&Bearer) -> Result<P, BearerChallenge>,
+{
+    type Principal = P;
+
+    fn validate(&self, credentials: &Bearer) -> Result<P, BearerChallenge> {
+        self(credentials)
+    }
+}
+
+/// An [`Authenticator`] for the `Bearer` scheme, which delegates to a [`TokenValidator`].
+///
+/// Challenges returned by the validator are sent back to the client as-is.
+#[derive(Debug)]
+pub struct BearerAuthenticator<V> {
+    challenge: BearerChallenge,
+    validator: V,
+}
+
+impl<V: TokenValidator> BearerAuthenticator<V> {
+    /// Creates an authenticator for the given realm.
+    pub fn new(realm: &str, validator: V) -> Self {
+        BearerAuthenticator {
+            challenge: BearerChallenge {
+                realm: Some(realm.to_owned()),
+                ..Default::default()
+            },
+            validator,
+        }
+    }
+
+    /// Sets the scopes that are listed in challenges.
+    pub fn with_scope(mut self, scope: &str) -> Self {
+        self.challenge.scope = Some(scope.to_owned());
+        self
+    }
+
+    fn challenge_with_error(&self, error: BearerErrorCode) -> BearerChallenge {
+        BearerChallenge {
+            error: Some(error),
+            ..self.challenge.clone()
+        }
+    }
+}
+
+impl<V: TokenValidator> Authenticator for BearerAuthenticator<V> {
+    type Principal = V::Principal;
+
+    fn scheme(&self) -> &str {
+        Bearer::SCHEME
+    }
+
+    fn challenges(&self, error: Option<&AuthenticationError>) -> Vec<HeaderValue> {
+        let challenge = match error {
+            Some(AuthenticationError::Rejected { challenges, .. }) => {
+                return challenges.clone();
+            }
+            Some(AuthenticationError::MalformedCredentials) => {
+                self.challenge_with_error(BearerErrorCode::InvalidRequest)
+            }
+            Some(AuthenticationError::InvalidCredentials) => {
+                self.challenge_with_error(BearerErrorCode::InvalidToken)
+            }
+            _ => self.challenge.clone(),
+        };
+        vec![challenge.encode()]
+    }
+
+    fn authenticate(
+        &self,
+        value: &HeaderValue,
+        _request: &AuthRequest,
+    ) -> Result<V::Principal, AuthenticationError> {
+        let bearer = Bearer::decode(value).ok_or(AuthenticationError::MalformedCredentials)?;
+        self.validator
+            .validate(&bearer)
+            .map_err(|challenge| AuthenticationError::Rejected {
+                status: challenge.status_code(),
+                challenges: vec![challenge.encode()],
+            })
+    }
+}
@@ -0,0 +1,255 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::{Bearer, BearerChallenge, BearerErrorCode, TokenValidator};
+use crate::challenge::WwwAuthenticate;
+use headers::authorization::Credentials;
+use headers::{Authorization, Header, HeaderMapExt, HeaderValue};
+use http::{HeaderMap, StatusCode};
+
+fn decode_challenge(value: &str) -> Result<BearerChallenge, headers::Error> {
+    let value = HeaderValue::from_str(value).expect("Could not create header value");
+    WwwAuthenticate::<BearerChallenge>::decode(&mut std::iter::once(&value)).map(|header| header.0)
+}
+
+fn encode_challenge(challenge: BearerChallenge) -> String {
+    let mut headers = HeaderMap::new();
+    headers.typed_insert(WwwAuthenticate(challenge));
+    headers[http::header::WWW_AUTHENTICATE]
+        .to_str()
+        .expect("Could not serialize header")
+        .to_owned()
+}
+
+#[test]
+fn test_decode_credentials() {
+    // From: RFC 6750, Section 2.1
+    let value = HeaderValue::from_static("Bearer mF_9.B5f-4.1JqM");
+    let bearer = Bearer::decode(&value).expect("Could not decode credentials");
+    assert_eq!("mF_9.B5f-4.1JqM", bearer.token())
+}
+
+#[test]
+fn test_decode_credentials_with_padding_and_lowercase_scheme() {
+    let value = HeaderValue::from_static("bearer  abc+/def==");
+    let bearer = Bearer::decode(&value).expect("Could not decode credentials");
+    assert_eq!("abc+/def==", bearer.token())
+}
+
+#[test]
+fn test_decode_invalid_credentials() {
+    for value in [
+        "Bearer",
+        "Bearer ",
+        "Bearer ==",
+        "Bearer abc def",
+        "Bearer a=b",
+        "Bearer \"abc\"",
+        "Bearerabc",
+        "Basic abc",
+    ] {
+        assert_eq!(
+            None,
+            Bearer::decode(&HeaderValue::from_static(value)),
+            "{value}"
+        );
+    }
+}
+
+#[test]
+fn test_debug_omits_token() {
+    let bearer: Bearer = "mF_9.B5f-4.1JqM".parse().expect("Could not parse token");
+    assert!(!format!("{:?}", bearer).contains("mF_9.B5f-4.1JqM"));
+}
+
+#[test]
+fn test_encode_credentials() {
+    let bearer: Bearer = "mF_9.B5f-4.1JqM".parse().expect("Could not parse token");
+    let mut headers = HeaderMap::new();
+    headers.typed_insert(Authorization(bearer.clone()));
+    assert_eq!(
+        "Bearer mF_9.B5f-4.1JqM",
+        headers[http::header::AUTHORIZATION]
+    );
+    assert_eq!(
+        Some(Authorization(bearer)),
+        headers.typed_get::<Authorization<Bearer>>()
+    )
+}
+
+#[test]
+fn test_decode_challenge() {
+    // From: RFC 6750, Section 3
+    let challenge = decode_challenge(
+        "Bearer realm=\"example\", error=\"invalid_token\", error_description=\"The access token \
+         expired\"",
+    )
+    .expect("Could not decode challenge");
+    assert_eq!(
+        BearerChallenge {
+            realm: Some("example".to_owned()),
+            scope: None,
+            error: Some(BearerErrorCode::InvalidToken),
+            error_description: Some("The access token expired".to_owned()),
+            error_uri: None,
+        },
+        challenge
+    );
+    assert_eq!(StatusCode::UNAUTHORIZED, challenge.status_code())
+}
+
+#[test]
+fn test_decode_challenge_without_parameters() {
+    assert_eq!(
+        BearerChallenge::default(),
+        decode_challenge("Bearer").expect("Could not decode challenge")
+    )
+}
+
+#[test]
+fn test_decode_challenge_among_others() {
+    let challenge = decode_challenge(
+        "Digest realm=\"digest\", nonce=\"abc\", Bearer realm=\"bearer\", scope=\"read write\"",
+    )
+    .expect("Could not decode challenge");
+    assert_eq!(Some("bearer".to_owned()), challenge.realm);
+    assert_eq!(
+        vec!["read", "write"],
+        challenge.scopes().collect::<Vec<_>>()
+    )
+}
+
+#[test]
+fn test_decode_challenge_from_second_header_value() {
+    let first = HeaderValue::from_static("Basic realm=\"basic\"");
+    let second = HeaderValue::from_static("Bearer realm=\"bearer\"");
+    let header = WwwAuthenticate::<BearerChallenge>::decode(&mut [&first, &second].into_iter())
+        .expect("Could not decode challenge");
+    assert_eq!(Some("bearer".to_owned()), header.0.realm)
+}
+
+#[test]
+fn test_decode_challenge_with_other_error_code() {
+    let challenge = decode_challenge(
+        "Bearer error=\"insufficient_user_authentication\", \
+         error_description=\"A different authentication level is required\"",
+    )
+    .expect("Could not decode challenge");
+    assert_eq!(
+        Some(BearerErrorCode::Other(
+            "insufficient_user_authentication".to_owned()
+        )),
+        challenge.error
+    );
+    assert_eq!(StatusCode::UNAUTHORIZED, challenge.status_code());
+    assert!(
+        encode_challenge(challenge)
+            .starts_with("Bearer error=\"insufficient_user_authentication\"")
+    );
+}
+
+#[test]
+fn test_decode_invalid_challenges() {
+    assert!(decode_challenge("Basic realm=\"basic\"").is_err());
+    assert!(decode_challenge("Bearer error=\"\"").is_err());
+    assert!(decode_challenge("Bearer realm=\"a\", realm=\"b\"").is_err());
+    assert!(decode_challenge("Bearer realm=\"a").is_err());
+}
+
+#[test]
+fn test_encode_challenge() {
+    let challenge = BearerChallenge {
+        realm: Some("example".to_owned()),
+        scope: Some("read write".to_owned()),
+        error: Some(BearerErrorCode::InsufficientScope),
+        error_description: Some("Needs \"write\"".to_owned()),
+        error_uri: Some("https://example.com/errors".to_owned()),
+    };
+    let encoded = encode_challenge(challenge.clone());
+    assert_eq!(
+        "Bearer realm=\"example\", scope=\"read write\", error=\"insufficient_scope\", \
+         error_description=\"Needs \\\"write\\\"\", error_uri=\"https://example.com/errors\"",
+        encoded
+    );
+    assert_eq!(
+        challenge,
+        decode_challenge(&encoded).expect("Could not decode challenge")
+    )
+}
+
+#[test]
+fn test_encode_challenge_with_percent_signs_and_control_characters() {
+    let challenge = BearerChallenge {
+        error_description: Some("100%\ninvalid".to_owned()),
+        error_uri: Some("https://example.com/errors%2F1".to_owned()),
+        ..BearerChallenge::from_error(BearerErrorCode::InvalidToken)
+    };
+    let encoded = encode_challenge(challenge.clone());
+    assert_eq!(
+        "Bearer error=\"invalid_token\", error_description=\"100%25%0Ainvalid\", \
+         error_uri=\"https://example.com/errors%2F1\"",
+        encoded
+    );
+    assert_eq!(
+        challenge,
+        decode_challenge(&encoded).expect("Could not decode challenge")
+    )
+}
+
+#[test]
+fn test_encode_challenge_without_parameters() {
+    assert_eq!("Bearer", encode_challenge(BearerChallenge::default()))
+}
+
+#[test]
+fn test_error_code_status_codes() {
+    assert_eq!(
+        StatusCode::BAD_REQUEST,
+        BearerChallenge::from_error(BearerErrorCode::InvalidRequest).status_code()
+    );
+    assert_eq!(
+        StatusCode::FORBIDDEN,
+        BearerChallenge::from_error(BearerErrorCode::InsufficientScope).status_code()
+    );
+    assert_eq!(
+        StatusCode::UNAUTHORIZED,
+        BearerChallenge::default().status_code()
+    )
+}
+
+#[test]
+fn test_closure_token_validator() {
+    let validator = |bearer: &Bearer| {
+        if bearer.token() == "letmein" {
+            Ok("admin")
+        } else {
+            Err(BearerChallenge::from_error(BearerErrorCode::InvalidToken))
+        }
+    };
+    let valid: Bearer = "letmein".parse().expect("Could not parse token");
+    let invalid: Bearer = "nope".parse().expect("Could not parse token");
+    assert_eq!(Ok("admin"), validator.validate(&valid));
+    assert_eq!(
+        Err(BearerChallenge::from_error(BearerErrorCode::InvalidToken)),
+        validator.validate(&invalid)
+    )
+}
@@ -0,0 +1,389 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Decodes `Digest` authentication headers and shows how the response is computed, to help
+//! find out why a client fails to authenticate.
+
+use getopts::{Matches, Options};
+use guardhaus::authentication_info::{AuthenticationInfo, ResponseDigest};
+use guardhaus::challenge::WwwAuthenticate;
+use guardhaus::digest::{Digest, DigestChallenge, ResponseTrace, Username};
+use guardhaus::types::Qop;
+use headers::authorization::Credentials;
+use http::{HeaderValue, Method};
+use std::fmt::Display;
+use std::io::{self, BufRead};
+use std::process::ExitCode;
+use thiserror::Error;
+
+const DETAILS: &str = "
+Each HEADER is a complete header line, e.g.
+    'Authorization: Digest username=\"Mufasa\", realm=\"testrealm@host.com\", ...'
+Authorization, WWW-Authenticate and Authentication-Info headers (and their Proxy- variants) are
+supported. If HEADER is -, header lines are read from standard input.
+
+If a password or HA1 is given, every intermediate value of the response computation is shown,
+along with the rspauth value that the server should send back.
+
+Exit codes:
+    0    Success (and, if a secret was given, the response and any rspauth matched)
+    1    The response or rspauth did not match
+    2    Invalid command-line arguments or headers
+";
+
+#[derive(Debug, Error)]
+enum DebugError {
+    #[error("{0}")]
+    Usage(String),
+    #[error("Invalid {0} header")]
+    InvalidHeader(String),
+    #[error("The response does not match")]
+    Mismatch,
+}
+
+enum Secret {
+    Password(String),
+    HashedA1(String),
+}
+
+fn field<T: Display>(label: &str, value: T) {
+    println!("  {:<12} {}", format!("{}:", label), value);
+}
+
+fn optional_field<T: Display>(label: &str, value: &Option<T>) {
+    if let Some(value) = value {
+        field(label, value);
+    }
+}
+
+fn print_username(username: &Username) {
+    match *username {
+        Username::Plain(ref username) => field("username", username),
+        Username::Encoded(ref encoded) => {
+            field("username*", encoded);
+            field(
+                "(decoded)",
+                String::from_utf8_lossy(&encoded.value).into_owned(),
+            );
+        }
+    }
+}
+
+fn print_authorization(digest: &Digest) {
+    println!("Authorization: Digest");
+    print_username(&digest.username);
+    field("userhash", digest.userhash);
+    field("realm", &digest.realm);
+    field("nonce", &digest.nonce);
+    field("uri", &digest.request_uri);
+    field("algorithm", &digest.algorithm);
+    optional_field("qop", &digest.qop);
+    optional_field("nc", &digest.nonce_count);
+    optional_field("cnonce", &digest.client_nonce);
+    optional_field("opaque", &digest.opaque);
+    optional_field("charset", &digest.charset);
+    field("response", &digest.response);
+}
+
+fn print_challenge(challenge: &DigestChallenge) {
+    println!("WWW-Authenticate: Digest");
+    field("realm", &challenge.realm);
+    if !challenge.domain.is_empty() {
+        field("domain", challenge.domain.join(" "));
+    }
+    field("nonce", &challenge.nonce);
+    optional_field("opaque", &challenge.opaque);
+    field("stale", challenge.stale);
+    field("algorithm", &challenge.algorithm);
+    if !challenge.qop.is_empty() {
+        let qop: Vec<String> = challenge.qop.iter().map(ToString::to_string).collect();
+        field("qop", qop.join(", "));
+    }
+    optional_field("charset", &challenge.charset);
+    field("userhash", challenge.userhash);
+}
+
+fn print_authentication_info(info: &AuthenticationInfo) {
+    println!("Authentication-Info");
+    match info.digest {
+        Some(ResponseDigest::ResponseAuth(ref value)) => field("rspauth", value),
+        Some(ResponseDigest::EntityDigest(ref value)) => field("digest", value),
+        None => {}
+    }
+    optional_field("nextnonce", &info.next_nonce);
+    optional_field("qop", &info.qop);
+    optional_field("cnonce", &info.client_nonce);
+    optional_field("nc", &info.nonce_count);
+}
+
+fn print_trace(trace: &ResponseTrace, digest: &Digest) {
+    println!("Response computation ({})", digest.algorithm);
+    optional_field("A1", &trace.a1);
+    field("HA1", &trace.hashed_a1);
+    field("A2", &trace.a2);
+    field("HA2", &trace.hashed_a2);
+    field("KD input", &trace.kd_input);
+    field("expected", &trace.response);
+    field("actual", &digest.response);
+    field(
+        "result",
+        if trace.matches(digest) {
+            "match"
+        } else {
+            "MISMATCH"
+        },
+    );
+}
+
+/// Checks an `Authorization` header against a secret, returning whether the response matched,
+/// and the `rspauth` value that the server should respond with.
+fn check_authorization(
+    digest: &Digest,
+    matches: &Matches,
+    secret: &Secret,
+    method: Method,
+    body: &[u8],
+) -> Result<(bool, Option<String>), DebugError> {
+    let mut digest = digest.clone();
+    if digest.userhash {
+        let username = matches
+            .opt_str("u")
+            .ok_or_else(|| DebugError::Usage("userhash=true requires --username".to_owned()))?;
+        let userhash = Digest::userhash(
+            &digest.algorithm,
+            username.clone().into_bytes(),
+            digest.realm.clone(),
+        );
+        println!("Userhash");
+        field("expected", &userhash);
+        field(
+            "result",
+            if digest.validate_userhash(Username::Plain(username.clone())) {
+                "match"
+            } else {
+                "MISMATCH"
+            },
+        );
+        digest.username = Username::Plain(username);
+    }
+
+    let trace = match *secret {
+        Secret::Password(ref password) => {
+            digest.trace_using_password(method, body, password.clone())
+        }
+        Secret::HashedA1(ref hashed_a1) => {
+            digest.trace_using_simple_hashed_a1(method, body, hashed_a1)
+        }
+    }
+    .map_err(|_| {
+        DebugError::Usage(
+            "The response cannot be computed: qop requires nc and cnonce, and -sess requires \
+             cnonce"
+                .to_owned(),
+        )
+    })?;
+    print_trace(&trace, &digest);
+    let response_auth = digest
+        .response_auth_using_hashed_a1(b"", trace.hashed_a1.clone())
+        .ok();
+    if let Some(ref response_auth) = response_auth {
+        field("rspauth", response_auth);
+        if digest.qop == Some(Qop::AuthInt) {
+            println!("  (rspauth assumes an empty response body)");
+        }
+    }
+    Ok((trace.matches(&digest), response_auth))
+}
+
+fn parse_header(line: &str) -> Result<(String, &str), DebugError> {
+    let (name, value) = line
+        .split_once(':')
+        .ok_or_else(|| DebugError::Usage(format!("Not a header line: {}", line)))?;
+    Ok((name.trim().to_ascii_lowercase(), value.trim()))
+}
+
+fn run(matches: &Matches) -> Result<(), DebugError> {
+    let mut lines = vec![];
+    for header in &matches.free {
+        if header == "-" {
+            for line in io::stdin().lock().lines() {
+                let line = line.map_err(|error| DebugError::Usage(error.to_string()))?;
+                if !line.trim().is_empty() {
+                    lines.push(line);
+                }
+            }
+        } else {
+            lines.push(header.clone());
+        }
+    }
+    if lines.is_empty() {
+        return Err(DebugError::Usage("No headers given".to_owned()));
+    }
+
+    let secret = match (matches.opt_str("p"), matches.opt_str("ha1")) {
+        (Some(password), None) => Some(Secret::Password(password)),
+        (None, Some(hashed_a1)) => Some(Secret::HashedA1(hashed_a1.to_ascii_lowercase())),
+        (None, None) => None,
+        (Some(_), Some(_)) => {
+            return Err(DebugError::Usage(
+                "Only one of --password and --ha1 can be given".to_owned(),
+            ));
+        }
+    };
+    let method = match matches.opt_str("m") {
+        Some(method) => Method::from_bytes(method.as_bytes())
+            .map_err(|_| DebugError::Usage(format!("Invalid method: {}", method)))?,
+        None => Method::GET,
+    };
+    let body = match matches.opt_str("b") {
+        Some(path) => std::fs::read(&path)
+            .map_err(|error| DebugError::Usage(format!("Could not read {}: {}", path, error)))?,
+        None => vec![],
+    };
+
+    let mut matched = true;
+    let mut expected_response_auth = None;
+    let mut received_response_auth = vec![];
+    let mut first = true;
+    for line in &lines {
+        if !first {
+            println!();
+        }
+        first = false;
+
+        let (name, value) = parse_header(line)?;
+        let header_value =
+            HeaderValue::from_str(value).map_err(|_| DebugError::InvalidHeader(name.clone()))?;
+        match name.as_str() {
+            "authorization" | "proxy-authorization" => {
+                let digest = Digest::decode(&header_value)
+                    .ok_or_else(|| DebugError::InvalidHeader(name.clone()))?;
+                print_authorization(&digest);
+                if let Some(ref secret) = secret {
+                    println!();
+                    let (response_matched, response_auth) =
+                        check_authorization(&digest, matches, secret, method.clone(), &body)?;
+                    matched &= response_matched;
+                    expected_response_auth = response_auth;
+                }
+            }
+            "www-authenticate" | "proxy-authenticate" => {
+                let challenges = WwwAuthenticate::<DigestChallenge>::decode_all([&header_value])
+                    .map_err(|_| DebugError::InvalidHeader(name.clone()))?;
+                if challenges.is_empty() {
+                    return Err(DebugError::InvalidHeader(name));
+                }
+                for (index, challenge) in challenges.iter().enumerate() {
+                    if index > 0 {
+                        println!();
+                    }
+                    print_challenge(challenge);
+                }
+            }
+            "authentication-info" | "proxy-authentication-info" => {
+                let info: AuthenticationInfo = value
+                    .parse()
+                    .map_err(|_| DebugError::InvalidHeader(name.clone()))?;
+                print_authentication_info(&info);
+                if let Some(ResponseDigest::ResponseAuth(value)) = info.digest {
+                    received_response_auth.push(value);
+                }
+            }
+            _ => return Err(DebugError::Usage(format!("Unsupported header: {}", name))),
+        }
+    }
+
+    if let Some(expected) = expected_response_auth {
+        for actual in received_response_auth {
+            println!();
+            println!("Response authentication");
+            field("expected", &expected);
+            field("actual", &actual);
+            field(
+                "result",
+                if expected == actual {
+                    "match"
+                } else {
+                    "MISMATCH"
+                },
+            );
+            matched &= expected == actual;
+        }
+    }
+
+    if matched {
+        Ok(())
+    } else {
+        Err(DebugError::Mismatch)
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    let program = args.first().map_or("guardhaus-debug", String::as_str);
+
+    let mut opts = Options::new();
+    opts.optflag("h", "help", "print this help menu");
+    opts.optopt("p", "password", "the user's password", "PASSWORD");
+    opts.optopt(
+        "",
+        "ha1",
+        "the digest of the user's (non-session) A1 value, e.g. from an htdigest file",
+        "HA1",
+    );
+    opts.optopt(
+        "u",
+        "username",
+        "the actual username, when the header uses a userhash",
+        "USERNAME",
+    );
+    opts.optopt("m", "method", "the request method (default: GET)", "METHOD");
+    opts.optopt(
+        "b",
+        "body",
+        "a file containing the request body, for qop=auth-int",
+        "FILE",
+    );
+    let brief = format!("Usage: {} [options] HEADER...", program);
+
+    let matches = match opts.parse(&args[1..]) {
+        Ok(matches) => matches,
+        Err(error) => {
+            eprintln!("{}\n\n{}{}", error, opts.usage(&brief), DETAILS);
+            return ExitCode::from(2);
+        }
+    };
+    if matches.opt_present("h") {
+        print!("{}{}", opts.usage(&brief), DETAILS);
+        return ExitCode::SUCCESS;
+    }
+
+    match run(&matches) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(DebugError::Mismatch) => ExitCode::from(1),
+        Err(error) => {
+            eprintln!("{}: {}", program, error);
+            if let DebugError::Usage(_) = error {
+                eprint!("\n{}{}", opts.usage(&brief), DETAILS);
+            }
+            ExitCode::from(2)
+        }
+    }
+}
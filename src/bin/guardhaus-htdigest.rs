@@ -0,0 +1,399 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Manages Apache `htdigest`-style password files.
+
+use getopts::{Matches, Options};
+use guardhaus::digest::{Digest, Username};
+use guardhaus::store::htdigest::{Htdigest, HtdigestRecord};
+use guardhaus::store::multi_digest::MultiDigest;
+use guardhaus::store::{CredentialStore, StoreError};
+use guardhaus::types::HashAlgorithm;
+use rpassword::prompt_password;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use subtle::ConstantTimeEq;
+use thiserror::Error;
+
+const COMMANDS: &str = "
+Commands:
+    create passwdfile realm username    Create a new file containing a single user
+    add    passwdfile realm username    Add a user
+    update passwdfile realm username    Change the password of an existing user
+    delete passwdfile realm username    Remove a user
+    verify passwdfile realm username    Check the password of a user
+    list   passwdfile [realm]           List users, optionally only those in a realm
+    migrate passwdfile newfile          Convert to the multi-algorithm format, where the digests
+                                        in passwdfile were hashed with the given algorithm
+
+Exit codes:
+    0    Success
+    1    The password file could not be read or written
+    2    Invalid command-line arguments
+    3    The passwords did not match, or the password could not be verified
+    6    The username or realm contains invalid characters
+    7    The password file is malformed
+    8    The user already exists (create/add) or does not exist (update/delete/verify)
+";
+
+#[derive(Debug, Error)]
+enum CliError {
+    #[error("{0}")]
+    Usage(String),
+    #[error("{0}")]
+    File(String),
+    #[error("{0}")]
+    Mismatch(&'static str),
+    #[error("{0}")]
+    InvalidCharacters(&'static str),
+    #[error("Malformed password file: {0}")]
+    InvalidFile(StoreError),
+    #[error("{0}")]
+    User(String),
+}
+
+impl CliError {
+    fn exit_code(&self) -> u8 {
+        match *self {
+            CliError::File(_) => 1,
+            CliError::Usage(_) => 2,
+            CliError::Mismatch(_) => 3,
+            CliError::InvalidCharacters(_) => 6,
+            CliError::InvalidFile(_) => 7,
+            CliError::User(_) => 8,
+        }
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(error: io::Error) -> Self {
+        CliError::File(error.to_string())
+    }
+}
+
+impl From<StoreError> for CliError {
+    fn from(error: StoreError) -> Self {
+        match error {
+            StoreError::Io(error) => error.into(),
+            _ => CliError::InvalidFile(error),
+        }
+    }
+}
+
+/// An exclusive lock on a password file, held by creating `<passwdfile>.lock`. The new contents
+/// are written to the lock file, which then atomically replaces the password file. The lock file
+/// is given the password file's permissions, so that replacing it does not change who can read
+/// it.
+struct Lock {
+    path: PathBuf,
+    lock_path: PathBuf,
+    file: Option<File>,
+    committed: bool,
+}
+
+impl Lock {
+    fn acquire(path: &Path) -> Result<Lock, CliError> {
+        let mut lock_path = path.as_os_str().to_owned();
+        lock_path.push(".lock");
+        let lock_path = PathBuf::from(lock_path);
+        let file = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&lock_path)
+            .map_err(|error| match error.kind() {
+                io::ErrorKind::AlreadyExists => CliError::File(format!(
+                    "{} exists; is another process editing the password file?",
+                    lock_path.display()
+                )),
+                _ => error.into(),
+            })?;
+        let lock = Lock {
+            path: path.to_owned(),
+            lock_path,
+            file: Some(file),
+            committed: false,
+        };
+        match fs::metadata(path) {
+            Ok(metadata) => fs::set_permissions(&lock.lock_path, metadata.permissions())?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => return Err(error.into()),
+        }
+        Ok(lock)
+    }
+
+    fn commit<F>(mut self, write: F) -> Result<(), CliError>
+    where
+        F: FnOnce(&mut File) -> io::Result<()>,
+    {
+        // The file has to be closed before it can be renamed on Windows.
+        if let Some(mut file) = self.file.take() {
+            write(&mut file)?;
+            file.sync_all()?;
+        }
+        fs::rename(&self.lock_path, &self.path)?;
+        self.committed = true;
+        Ok(())
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.file = None;
+            let _ = fs::remove_file(&self.lock_path);
+        }
+    }
+}
+
+fn validate_field(value: &str, name: &'static str) -> Result<(), CliError> {
+    if value.contains(':') || value.chars().any(char::is_control) {
+        Err(CliError::InvalidCharacters(name))
+    } else {
+        Ok(())
+    }
+}
+
+fn parse_algorithm(matches: &Matches) -> Result<Option<HashAlgorithm>, CliError> {
+    let Some(name) = matches.opt_str("a") else {
+        return Ok(None);
+    };
+    match name.parse() {
+        Ok(algorithm @ (HashAlgorithm::Md5 | HashAlgorithm::Sha256 | HashAlgorithm::Sha512256)) => {
+            Ok(Some(algorithm))
+        }
+        _ => Err(CliError::Usage(format!("Unsupported algorithm: {}", name))),
+    }
+}
+
+fn read_password(from_stdin: bool, confirm: bool) -> Result<String, CliError> {
+    if from_stdin {
+        let mut password = String::new();
+        io::stdin().lock().read_line(&mut password)?;
+        let password = password.strip_suffix('\n').unwrap_or(&password);
+        return Ok(password.strip_suffix('\r').unwrap_or(password).to_owned());
+    }
+
+    let password = prompt_password("Enter password: ")?;
+    if confirm && password != prompt_password("Re-enter password: ")? {
+        return Err(CliError::Mismatch("Passwords do not match"));
+    }
+    Ok(password)
+}
+
+fn candidate_algorithms(hashed_a1: &str, algorithm: Option<HashAlgorithm>) -> Vec<HashAlgorithm> {
+    match algorithm {
+        Some(algorithm) => vec![algorithm],
+        None => [
+            HashAlgorithm::Md5,
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Sha512256,
+        ]
+        .into_iter()
+        .filter(|algorithm| algorithm.hex_digest_len() == hashed_a1.len())
+        .collect(),
+    }
+}
+
+fn run(matches: &Matches) -> Result<(), CliError> {
+    let (command, path) = match &matches.free[..] {
+        [command, path, ..] => (command.as_str(), Path::new(path)),
+        _ => return Err(CliError::Usage("Missing command or passwdfile".to_owned())),
+    };
+    let algorithm = parse_algorithm(matches)?;
+    let from_stdin = matches.opt_present("i");
+
+    if command == "migrate" {
+        let new_path = match &matches.free[2..] {
+            [new_path] => Path::new(new_path),
+            _ => return Err(CliError::Usage("Expected a new file".to_owned())),
+        };
+        let algorithm = algorithm.unwrap_or(HashAlgorithm::Md5);
+        let htdigest = Htdigest::open(path)?;
+        let store = MultiDigest::migrate(&htdigest, &algorithm, matches.opt_present("u"))?;
+        let lock = Lock::acquire(new_path)?;
+        if new_path.exists() {
+            return Err(CliError::File(format!(
+                "{} already exists",
+                new_path.display()
+            )));
+        }
+        return lock.commit(|file| store.write(file));
+    }
+
+    if command == "list" {
+        let realm = match &matches.free[2..] {
+            [] => None,
+            [realm] => Some(realm.as_str()),
+            _ => return Err(CliError::Usage("Too many arguments".to_owned())),
+        };
+        let store = Htdigest::open(path)?;
+        let mut stdout = io::stdout().lock();
+        for record in store.records() {
+            if realm.is_none_or(|realm| realm == record.realm) {
+                writeln!(stdout, "{}:{}", record.username, record.realm)?;
+            }
+        }
+        return Ok(());
+    }
+
+    let (realm, username) = match &matches.free[2..] {
+        [realm, username] => (realm.as_str(), username.as_str()),
+        _ => {
+            return Err(CliError::Usage(
+                "Expected a realm and a username".to_owned(),
+            ));
+        }
+    };
+    validate_field(realm, "The realm contains invalid characters")?;
+    validate_field(username, "The username contains invalid characters")?;
+    if username.is_empty() {
+        return Err(CliError::InvalidCharacters("The username cannot be empty"));
+    }
+    let new_record = |password| {
+        HtdigestRecord::new(
+            username,
+            realm,
+            password,
+            algorithm.as_ref().unwrap_or(&HashAlgorithm::Md5),
+        )
+    };
+
+    match command {
+        "create" => {
+            let lock = Lock::acquire(path)?;
+            if path.exists() {
+                return Err(CliError::File(format!("{} already exists", path.display())));
+            }
+            let mut store = Htdigest::new();
+            store.insert(new_record(read_password(from_stdin, true)?));
+            lock.commit(|file| store.write(file))
+        }
+        "add" | "update" => {
+            let lock = Lock::acquire(path)?;
+            let mut store = Htdigest::open(path)?;
+            let exists = store.get(username, realm).is_some();
+            if command == "add" && exists {
+                return Err(CliError::User(format!(
+                    "User {} already exists in realm {}",
+                    username, realm
+                )));
+            } else if command == "update" && !exists {
+                return Err(CliError::User(format!(
+                    "User {} does not exist in realm {}",
+                    username, realm
+                )));
+            }
+            store.insert(new_record(read_password(from_stdin, true)?));
+            lock.commit(|file| store.write(file))
+        }
+        "delete" => {
+            let lock = Lock::acquire(path)?;
+            let mut store = Htdigest::open(path)?;
+            if store.remove(username, realm).is_none() {
+                return Err(CliError::User(format!(
+                    "User {} does not exist in realm {}",
+                    username, realm
+                )));
+            }
+            lock.commit(|file| store.write(file))
+        }
+        "verify" => {
+            let store = Htdigest::open(path)?;
+            let record = store.get(username, realm).ok_or_else(|| {
+                CliError::User(format!(
+                    "User {} does not exist in realm {}",
+                    username, realm
+                ))
+            })?;
+            let password = read_password(from_stdin, false)?;
+            let verified = candidate_algorithms(&record.hashed_a1, algorithm)
+                .iter()
+                .any(|algorithm| {
+                    let hashed_a1 = Digest::simple_hashed_a1(
+                        algorithm,
+                        Username::Plain(username.to_owned()),
+                        realm.to_owned(),
+                        password.clone(),
+                    );
+                    bool::from(hashed_a1.as_bytes().ct_eq(record.hashed_a1.as_bytes()))
+                });
+            if verified {
+                println!("Password for user {} correct.", username);
+                Ok(())
+            } else {
+                Err(CliError::Mismatch("Password verification failed"))
+            }
+        }
+        _ => Err(CliError::Usage(format!("Unknown command: {}", command))),
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    let program = args.first().map_or("guardhaus-htdigest", String::as_str);
+
+    let mut opts = Options::new();
+    opts.optflag("h", "help", "print this help menu");
+    opts.optopt(
+        "a",
+        "algorithm",
+        "the algorithm to hash passwords with: MD5 (default), SHA-256 or SHA-512-256",
+        "ALGORITHM",
+    );
+    opts.optflag(
+        "i",
+        "stdin",
+        "read the password from standard input, without prompting or confirmation",
+    );
+    opts.optflag(
+        "u",
+        "userhash",
+        "store userhashes in the migrated file (migrate only)",
+    );
+    let brief = format!(
+        "Usage: {} [options] command passwdfile [realm [username]]",
+        program
+    );
+
+    let matches = match opts.parse(&args[1..]) {
+        Ok(matches) => matches,
+        Err(error) => {
+            eprintln!("{}\n\n{}{}", error, opts.usage(&brief), COMMANDS);
+            return ExitCode::from(2);
+        }
+    };
+    if matches.opt_present("h") {
+        print!("{}{}", opts.usage(&brief), COMMANDS);
+        return ExitCode::SUCCESS;
+    }
+
+    match run(&matches) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{}: {}", program, error);
+            if let CliError::Usage(_) = error {
+                eprint!("\n{}{}", opts.usage(&brief), COMMANDS);
+            }
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
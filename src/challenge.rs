@@ -0,0 +1,98 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Authentication challenges, as sent by a server in the `WWW-Authenticate` header.
+
+use crate::parsing::{split_challenges, strip_scheme};
+use http::HeaderValue;
+
+/// A challenge for a single authentication scheme.
+pub trait Challenge: Sized {
+    /// The authentication scheme, e.g. `Bearer`.
+    const SCHEME: &'static str;
+
+    /// Decodes a single challenge, which starts with the authentication scheme.
+    fn decode(value: &str) -> Option<Self>;
+
+    /// Encodes the challenge, including the leading authentication scheme.
+    fn encode(&self) -> HeaderValue;
+}
+
+/// The `WWW-Authenticate` header, as defined in
+/// [RFC 7235, section 4.1](https://tools.ietf.org/html/rfc7235#section-4.1).
+///
+/// A server may offer several challenges, either in one header value or spread across several
+/// values. Decoding picks the first challenge whose scheme matches `C::SCHEME`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WwwAuthenticate<C: Challenge>(pub C);
+
+impl<C: Challenge> WwwAuthenticate<C> {
+    /// Decodes every challenge whose scheme matches `C::SCHEME`, e.g. the `Digest` challenges for
+    /// each algorithm that a server supports, in the order that they were sent. Matching
+    /// challenges that cannot be decoded are skipped.
+    pub fn decode_all<'i, I>(values: I) -> Result<Vec<C>, headers::Error>
+    where
+        I: IntoIterator<Item = &'i HeaderValue>,
+    {
+        let mut challenges = vec![];
+        for value in values {
+            let value = value.to_str().map_err(|_| headers::Error::invalid())?;
+            for challenge in split_challenges(value)? {
+                if strip_scheme(challenge, C::SCHEME).is_some()
+                    && let Some(challenge) = C::decode(challenge)
+                {
+                    challenges.push(challenge);
+                }
+            }
+        }
+        Ok(challenges)
+    }
+}
+
+impl<C: Challenge> headers::Header for WwwAuthenticate<C> {
+    fn name() -> &'static http::HeaderName {
+        &http::header::WWW_AUTHENTICATE
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        for value in values {
+            let value = value.to_str().map_err(|_| headers::Error::invalid())?;
+            for challenge in split_challenges(value)? {
+                if strip_scheme(challenge, C::SCHEME).is_some() {
+                    return C::decode(challenge)
+                        .map(WwwAuthenticate)
+                        .ok_or_else(headers::Error::invalid);
+                }
+            }
+        }
+
+        Err(headers::Error::invalid())
+    }
+
+    fn encode<E>(&self, values: &mut E)
+    where
+        E: Extend<HeaderValue>,
+    {
+        values.extend(std::iter::once(self.0.encode()));
+    }
+}
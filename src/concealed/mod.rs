@@ -0,0 +1,319 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! An implementation of the `Concealed` authentication scheme, as defined in
+//! [RFC 9729](https://tools.ietf.org/html/rfc9729).
+//!
+//! The client signs keying material exported from the TLS connection, so the server never has to
+//! send a challenge; clients without a valid key cannot tell that a resource is protected. The
+//! TLS layer is abstracted by [`KeyExporter`], and which keys are allowed by
+//! [`ConcealedVerifier`].
+//!
+//! Only the Ed25519 signature scheme is supported. This module requires the `concealed` feature.
+
+use crate::parsing::{
+    DigestParameters, parse_parameters, strip_scheme, to_header_value, unraveled_map_value,
+};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64URL;
+use ed25519_dalek::{Signer, SigningKey, Verifier, VerifyingKey};
+use headers::authorization::Credentials;
+use http::HeaderValue;
+use std::fmt;
+use subtle::ConstantTimeEq;
+use thiserror::Error;
+
+mod test;
+
+/// The authentication scheme name.
+pub const SCHEME: &str = "Concealed";
+
+/// The label used when exporting keying material from the TLS connection.
+pub const EXPORTER_LABEL: &[u8] = b"EXPORTER-HTTP-Concealed-Authentication";
+
+/// The number of octets exported from the TLS connection: 32 for the signature input, followed by
+/// 16 for the verification parameter.
+pub const EXPORTER_LENGTH: usize = 48;
+
+/// The context string included in the signature input.
+const SIGNATURE_CONTEXT: &[u8] = b"HTTP Concealed Authentication";
+
+/// Errors relating to `Concealed` authentication.
+#[derive(Debug, Error, PartialEq)]
+pub enum ConcealedError {
+    /// The signature scheme is not supported.
+    #[error("Unsupported signature scheme: {0}")]
+    UnsupportedSignatureScheme(SignatureScheme),
+    /// The key is not allowed by the [`ConcealedVerifier`].
+    #[error("Unknown key")]
+    UnknownKey,
+    /// The TLS layer could not export keying material.
+    #[error("Could not export keying material")]
+    ExporterUnavailable,
+    /// The verification parameter does not match the exported keying material, e.g. because the
+    /// credentials were replayed on another connection.
+    #[error("Verification parameter does not match")]
+    VerificationMismatch,
+    /// The public key or signature is malformed, or the signature is invalid.
+    #[error("Invalid signature")]
+    InvalidSignature,
+}
+
+/// A TLS `SignatureScheme` code point, the `s` parameter.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SignatureScheme(pub u16);
+
+impl SignatureScheme {
+    /// `ed25519` (0x0807)
+    pub const ED25519: SignatureScheme = SignatureScheme(0x0807);
+}
+
+impl fmt::Display for SignatureScheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Exports keying material from the TLS connection that a request was received (or sent) on, as
+/// defined in [RFC 8446, section 7.5](https://tools.ietf.org/html/rfc8446#section-7.5).
+pub trait KeyExporter {
+    /// Exports [`EXPORTER_LENGTH`] octets with the given label and context. Returns `None` if the
+    /// connection does not support exporters.
+    fn export_keying_material(&self, label: &[u8], context: &[u8])
+    -> Option<[u8; EXPORTER_LENGTH]>;
+}
+
+/// Decides which keys may authenticate.
+///
+/// Closures of the form `Fn(&[u8], &[u8], SignatureScheme) -> Option<P>` implement this trait.
+pub trait ConcealedVerifier {
+    /// Whatever the key grants access as, e.g. a user.
+    type Principal;
+
+    /// Returns the principal for a key, or `None` if the key is not allowed. The signature has
+    /// not been verified at this point.
+    fn authorize(
+        &self,
+        key_id: &[u8],
+        public_key: &[u8],
+        signature_scheme: SignatureScheme,
+    ) -> Option<Self::Principal>;
+}
+
+impl<F, P> ConcealedVerifier for F
+where
+    F: Fn(&[u8], &[u8], SignatureScheme) -> Option<P>,
+{
+    type Principal = P;
+
+    fn authorize(
+        &self,
+        key_id: &[u8],
+        public_key: &[u8],
+        signature_scheme: SignatureScheme,
+    ) -> Option<P> {
+        self(key_id, public_key, signature_scheme)
+    }
+}
+
+/// The origin and realm that the credentials are bound to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConcealedTarget {
+    /// The URI scheme, e.g. `https`
+    pub scheme: String,
+    /// The host
+    pub host: String,
+    /// The port
+    pub port: u16,
+    /// The protection space, or an empty string
+    pub realm: String,
+}
+
+/// Encodes a variable-length integer, as defined in
+/// [RFC 9000, section 16](https://tools.ietf.org/html/rfc9000#section-16).
+fn push_varint(buffer: &mut Vec<u8>, value: usize) {
+    let value = value as u64;
+    if value < 1 << 6 {
+        buffer.push(value as u8);
+    } else if value < 1 << 14 {
+        buffer.extend_from_slice(&(value as u16 | 0x4000).to_be_bytes());
+    } else if value < 1 << 30 {
+        buffer.extend_from_slice(&(value as u32 | 0x8000_0000).to_be_bytes());
+    } else {
+        buffer.extend_from_slice(&(value | 0xc000_0000_0000_0000).to_be_bytes());
+    }
+}
+
+fn push_with_length(buffer: &mut Vec<u8>, value: &[u8]) {
+    push_varint(buffer, value.len());
+    buffer.extend_from_slice(value);
+}
+
+/// Builds the key exporter context.
+///
+/// ```text
+/// Signature Algorithm (16),
+/// Key ID Length (i), Key ID (..),
+/// Public Key Length (i), Public Key (..),
+/// Scheme Length (i), Scheme (..),
+/// Host Length (i), Host (..),
+/// Port (16),
+/// Realm Length (i), Realm (..),
+/// ```
+pub fn key_exporter_context(
+    signature_scheme: SignatureScheme,
+    key_id: &[u8],
+    public_key: &[u8],
+    target: &ConcealedTarget,
+) -> Vec<u8> {
+    let mut context = signature_scheme.0.to_be_bytes().to_vec();
+    push_with_length(&mut context, key_id);
+    push_with_length(&mut context, public_key);
+    push_with_length(&mut context, target.scheme.as_bytes());
+    push_with_length(&mut context, target.host.as_bytes());
+    context.extend_from_slice(&target.port.to_be_bytes());
+    push_with_length(&mut context, target.realm.as_bytes());
+    context
+}
+
+/// Builds the signature input from the first 32 octets of the exported keying material: 64
+/// spaces, the context string `HTTP Concealed Authentication`, a zero octet, and the keying
+/// material.
+pub fn signature_input(exporter_output: &[u8; EXPORTER_LENGTH]) -> Vec<u8> {
+    let mut input = vec![0x20; 64];
+    input.extend_from_slice(SIGNATURE_CONTEXT);
+    input.push(0);
+    input.extend_from_slice(&exporter_output[..32]);
+    input
+}
+
+/// `Concealed` credentials, sent by the client in the `Authorization` header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Concealed {
+    /// `k` - the key identifier
+    pub key_id: Vec<u8>,
+    /// `a` - the public key
+    pub public_key: Vec<u8>,
+    /// `p` - the signature over the signature input
+    pub proof: Vec<u8>,
+    /// `s` - the signature scheme
+    pub signature_scheme: SignatureScheme,
+    /// `v` - the last 16 octets of the exported keying material
+    pub verification: Vec<u8>,
+}
+
+impl Concealed {
+    /// Signs the exported keying material with an Ed25519 key.
+    pub fn sign<E: KeyExporter>(
+        signing_key: &SigningKey,
+        key_id: &[u8],
+        target: &ConcealedTarget,
+        exporter: &E,
+    ) -> Result<Concealed, ConcealedError> {
+        let public_key = signing_key.verifying_key().to_bytes().to_vec();
+        let context = key_exporter_context(SignatureScheme::ED25519, key_id, &public_key, target);
+        let output = exporter
+            .export_keying_material(EXPORTER_LABEL, &context)
+            .ok_or(ConcealedError::ExporterUnavailable)?;
+        Ok(Concealed {
+            key_id: key_id.to_vec(),
+            public_key,
+            proof: signing_key
+                .sign(&signature_input(&output))
+                .to_bytes()
+                .to_vec(),
+            signature_scheme: SignatureScheme::ED25519,
+            verification: output[32..].to_vec(),
+        })
+    }
+
+    /// Verifies the credentials against the keying material exported from the connection that
+    /// they were received on, returning the principal from the verifier.
+    pub fn verify<E: KeyExporter, V: ConcealedVerifier>(
+        &self,
+        target: &ConcealedTarget,
+        exporter: &E,
+        verifier: &V,
+    ) -> Result<V::Principal, ConcealedError> {
+        if self.signature_scheme != SignatureScheme::ED25519 {
+            return Err(ConcealedError::UnsupportedSignatureScheme(
+                self.signature_scheme,
+            ));
+        }
+        let principal = verifier
+            .authorize(&self.key_id, &self.public_key, self.signature_scheme)
+            .ok_or(ConcealedError::UnknownKey)?;
+
+        let context = key_exporter_context(
+            self.signature_scheme,
+            &self.key_id,
+            &self.public_key,
+            target,
+        );
+        let output = exporter
+            .export_keying_material(EXPORTER_LABEL, &context)
+            .ok_or(ConcealedError::ExporterUnavailable)?;
+        if !bool::from(output[32..].ct_eq(self.verification.as_slice())) {
+            return Err(ConcealedError::VerificationMismatch);
+        }
+
+        let public_key = <[u8; 32]>::try_from(self.public_key.as_slice())
+            .ok()
+            .and_then(|key| VerifyingKey::from_bytes(&key).ok())
+            .ok_or(ConcealedError::InvalidSignature)?;
+        let signature = ed25519_dalek::Signature::from_slice(&self.proof)
+            .map_err(|_| ConcealedError::InvalidSignature)?;
+        public_key
+            .verify(&signature_input(&output), &signature)
+            .map_err(|_| ConcealedError::InvalidSignature)?;
+        Ok(principal)
+    }
+}
+
+impl Credentials for Concealed {
+    const SCHEME: &'static str = SCHEME;
+
+    fn decode(value: &HeaderValue) -> Option<Self> {
+        let map = parse_parameters(strip_scheme(value.to_str().ok()?, SCHEME)?).ok()?;
+        let decode = |key: &str| BASE64URL.decode(unraveled_map_value(&map, key)?).ok();
+        let signature_scheme = unraveled_map_value(&map, "s")?;
+        if !signature_scheme.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        Some(Concealed {
+            key_id: decode("k")?,
+            public_key: decode("a")?,
+            proof: decode("p")?,
+            signature_scheme: SignatureScheme(signature_scheme.parse().ok()?),
+            verification: decode("v")?,
+        })
+    }
+
+    fn encode(&self) -> HeaderValue {
+        let mut parameters = DigestParameters::new();
+        parameters.append("k", &BASE64URL.encode(&self.key_id), false);
+        parameters.append("a", &BASE64URL.encode(&self.public_key), false);
+        parameters.append("p", &BASE64URL.encode(&self.proof), false);
+        parameters.append("s", &self.signature_scheme.to_string(), false);
+        parameters.append("v", &BASE64URL.encode(&self.verification), false);
+        to_header_value(&format!("{} {}", SCHEME, parameters))
+    }
+}
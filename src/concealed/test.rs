@@ -0,0 +1,226 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::{
+    Concealed, ConcealedError, ConcealedTarget, EXPORTER_LABEL, EXPORTER_LENGTH, KeyExporter,
+    SignatureScheme, key_exporter_context, signature_input,
+};
+use ed25519_dalek::SigningKey;
+use headers::authorization::Credentials;
+use headers::{Authorization, HeaderMapExt};
+use http::{HeaderMap, HeaderValue};
+
+const KEY_ID: &[u8] = b"basement";
+
+// Computed independently, from an Ed25519 key with the seed 0x00..0x1f, and keying material
+// 0x64..0x93.
+const CONTEXT: &str = "080708626173656d656e742003a107bff3ce10be1d70dd18e74bc09967e4d6309ba50d5f1d\
+                       dc8664125531b80568747470730b6578616d706c652e636f6d01bb00";
+const HEADER: &str = "Concealed k=YmFzZW1lbnQ, a=A6EHv_POEL4dcN0Y50vAmWfk1jCbpQ1fHdyGZBJVMbg, \
+                      p=Nk9XhlXglXO5cMUkN3KctSYqR9d5tdo_edwNWlmJOPcxBM8rgdOpTUK6-mZuCKGXCWLoqkGW-\
+                      M7RPCr4-MCPDA, s=2055, v=hIWGh4iJiouMjY6PkJGSkw";
+
+/// Returns fixed keying material for the expected context, and different keying material for
+/// any other context (as a TLS exporter would).
+struct FixedExporter {
+    context: Vec<u8>,
+    output: [u8; EXPORTER_LENGTH],
+}
+
+impl KeyExporter for FixedExporter {
+    fn export_keying_material(
+        &self,
+        label: &[u8],
+        context: &[u8],
+    ) -> Option<[u8; EXPORTER_LENGTH]> {
+        if label == EXPORTER_LABEL && context == self.context {
+            Some(self.output)
+        } else {
+            Some([0; EXPORTER_LENGTH])
+        }
+    }
+}
+
+struct NoExporter;
+
+impl KeyExporter for NoExporter {
+    fn export_keying_material(&self, _: &[u8], _: &[u8]) -> Option<[u8; EXPORTER_LENGTH]> {
+        None
+    }
+}
+
+fn signing_key() -> SigningKey {
+    let seed: [u8; 32] = std::array::from_fn(|i| i as u8);
+    SigningKey::from_bytes(&seed)
+}
+
+fn target() -> ConcealedTarget {
+    ConcealedTarget {
+        scheme: "https".to_owned(),
+        host: "example.com".to_owned(),
+        port: 443,
+        realm: String::new(),
+    }
+}
+
+fn exporter() -> FixedExporter {
+    FixedExporter {
+        context: base16ct::mixed::decode_vec(CONTEXT).expect("Could not decode context"),
+        output: std::array::from_fn(|i| 100 + i as u8),
+    }
+}
+
+fn allow_basement(key_id: &[u8], _: &[u8], _: SignatureScheme) -> Option<&'static str> {
+    (key_id == KEY_ID).then_some("basement")
+}
+
+#[test]
+fn test_key_exporter_context() {
+    let public_key = signing_key().verifying_key().to_bytes();
+    assert_eq!(
+        CONTEXT,
+        base16ct::lower::encode_string(&key_exporter_context(
+            SignatureScheme::ED25519,
+            KEY_ID,
+            &public_key,
+            &target()
+        ))
+    )
+}
+
+#[test]
+fn test_key_exporter_context_with_long_values() {
+    let mut target = target();
+    target.realm = "r".repeat(64);
+    let context = key_exporter_context(SignatureScheme(1), &[7; 300], &[], &target);
+    assert_eq!([0x00, 0x01, 0x41, 0x2c], context[..4]);
+    assert_eq!(
+        [0x40, 0x40],
+        context[context.len() - 66..context.len() - 64]
+    )
+}
+
+#[test]
+fn test_signature_input() {
+    let input = signature_input(&[0xff; EXPORTER_LENGTH]);
+    assert_eq!(64 + 29 + 1 + 32, input.len());
+    assert_eq!(b"HTTP Concealed Authentication\0", &input[64..94]);
+    assert!(input[..64].iter().all(|b| *b == b' '));
+    assert!(input[94..].iter().all(|b| *b == 0xff))
+}
+
+#[test]
+fn test_sign() {
+    let credentials =
+        Concealed::sign(&signing_key(), KEY_ID, &target(), &exporter()).expect("Could not sign");
+    let mut headers = HeaderMap::new();
+    headers.typed_insert(Authorization(credentials.clone()));
+    assert_eq!(HEADER, headers[http::header::AUTHORIZATION]);
+    assert_eq!(
+        Some(Authorization(credentials)),
+        headers.typed_get::<Authorization<Concealed>>()
+    )
+}
+
+#[test]
+fn test_verify() {
+    let credentials =
+        Concealed::decode(&HeaderValue::from_static(HEADER)).expect("Could not decode credentials");
+    assert_eq!(
+        Ok("basement"),
+        credentials.verify(&target(), &exporter(), &allow_basement)
+    )
+}
+
+#[test]
+fn test_verify_with_unknown_key() {
+    let credentials =
+        Concealed::decode(&HeaderValue::from_static(HEADER)).expect("Could not decode credentials");
+    let deny = |_: &[u8], _: &[u8], _: SignatureScheme| None::<()>;
+    assert_eq!(
+        Err(ConcealedError::UnknownKey),
+        credentials.verify(&target(), &exporter(), &deny)
+    )
+}
+
+#[test]
+fn test_verify_on_another_origin_or_connection() {
+    let credentials =
+        Concealed::decode(&HeaderValue::from_static(HEADER)).expect("Could not decode credentials");
+    let mut other_target = target();
+    other_target.realm = "other".to_owned();
+    assert_eq!(
+        Err(ConcealedError::VerificationMismatch),
+        credentials.verify(&other_target, &exporter(), &allow_basement)
+    );
+    assert_eq!(
+        Err(ConcealedError::ExporterUnavailable),
+        credentials.verify(&target(), &NoExporter, &allow_basement)
+    )
+}
+
+#[test]
+fn test_verify_with_invalid_signature() {
+    let mut credentials =
+        Concealed::decode(&HeaderValue::from_static(HEADER)).expect("Could not decode credentials");
+    credentials.proof[0] ^= 1;
+    assert_eq!(
+        Err(ConcealedError::InvalidSignature),
+        credentials.verify(&target(), &exporter(), &allow_basement)
+    );
+    credentials.proof.truncate(10);
+    assert_eq!(
+        Err(ConcealedError::InvalidSignature),
+        credentials.verify(&target(), &exporter(), &allow_basement)
+    )
+}
+
+#[test]
+fn test_verify_with_unsupported_signature_scheme() {
+    let mut credentials =
+        Concealed::decode(&HeaderValue::from_static(HEADER)).expect("Could not decode credentials");
+    credentials.signature_scheme = SignatureScheme(0x0804);
+    assert_eq!(
+        Err(ConcealedError::UnsupportedSignatureScheme(SignatureScheme(
+            0x0804
+        ))),
+        credentials.verify(&target(), &exporter(), &allow_basement)
+    )
+}
+
+#[test]
+fn test_decode_invalid_credentials() {
+    for value in [
+        "Concealed a=YQ, p=cA, s=2055, v=dg",
+        "Concealed k=YQ, a=YQ, p=cA, s=-1, v=dg",
+        "Concealed k=YQ, a=YQ, p=cA, s=65536, v=dg",
+        "Concealed k=YQ==, a=YQ, p=cA, s=2055, v=dg",
+        "Concealed k=Y+Q, a=YQ, p=cA, s=2055, v=dg",
+        "Bearer k=YQ, a=YQ, p=cA, s=2055, v=dg",
+    ] {
+        assert_eq!(
+            None,
+            Concealed::decode(&HeaderValue::from_static(value)),
+            "{value}"
+        );
+    }
+}
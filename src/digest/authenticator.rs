@@ -0,0 +1,454 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Server-side `Digest` authentication against a credential store.
+
+use super::nonce::{NonceCountTracker, NonceGenerator};
+use super::session::SessionCache;
+use super::{Digest, DigestChallenge, PolicyViolation, UserhashIndex, Username, Verification};
+use crate::authentication_info::{AuthenticationInfo, ResponseDigest};
+use crate::authenticator::{AuthRequest, AuthenticationError, Authenticator};
+use crate::challenge::Challenge;
+use crate::parsing::fromheaders::Charset;
+use crate::store::{CredentialRecord, CredentialStore, DigestRecord};
+use crate::types::{HashAlgorithm, Qop};
+use headers::authorization::Credentials;
+use http::{HeaderValue, StatusCode};
+use std::sync::{RwLock, RwLockReadGuard};
+
+mod test;
+
+/// A user in the store, as indexed by userhash.
+#[derive(Clone, Debug)]
+struct IndexedUser {
+    username: String,
+    realm: String,
+}
+
+impl CredentialRecord for IndexedUser {
+    fn username(&self) -> &str {
+        &self.username
+    }
+
+    fn realm(&self) -> &str {
+        &self.realm
+    }
+}
+
+/// An [`Authenticator`] for the `Digest` scheme, as defined in
+/// [RFC 7616](https://tools.ietf.org/html/rfc7616).
+///
+/// By default, one challenge is issued using `MD5` (the only algorithm that `htdigest` files
+/// support) with `qop=auth`. The principal is the name of the authenticated user. Each nonce
+/// count can only be used once per nonce.
+///
+/// When userhashes are allowed, the users in the realm are indexed by userhash, so that they can
+/// be resolved without hashing every username on each request.
+#[derive(Debug)]
+pub struct DigestAuthenticator<S> {
+    realm: String,
+    domain: Vec<String>,
+    store: S,
+    algorithms: Vec<HashAlgorithm>,
+    qop: Vec<Qop>,
+    nonces: NonceCountTracker,
+    sessions: Option<SessionCache>,
+    opaque: Option<String>,
+    userhash: bool,
+    userhashes: RwLock<Option<UserhashIndex<IndexedUser>>>,
+}
+
+impl<S> DigestAuthenticator<S>
+where
+    S: CredentialStore,
+    S::Record: DigestRecord,
+{
+    /// Creates an authenticator for the given realm, whose users are held by `store`.
+    pub fn new(realm: &str, store: S) -> Self {
+        DigestAuthenticator {
+            realm: realm.to_owned(),
+            domain: vec![],
+            store,
+            algorithms: vec![HashAlgorithm::Md5],
+            qop: vec![Qop::Auth],
+            nonces: NonceCountTracker::default(),
+            sessions: None,
+            opaque: None,
+            userhash: false,
+            userhashes: RwLock::new(None),
+        }
+    }
+
+    /// Sets the algorithms to offer, in order of preference. One challenge is issued per
+    /// algorithm.
+    pub fn with_algorithms<I: IntoIterator<Item = HashAlgorithm>>(mut self, algorithms: I) -> Self {
+        self.algorithms = algorithms.into_iter().collect();
+        self.index_userhashes();
+        self
+    }
+
+    /// Sets the qualities of protection to offer. If empty, RFC 2069-style credentials (without
+    /// `qop`, `nc` or `cnonce`) are expected instead.
+    pub fn with_qop<I: IntoIterator<Item = Qop>>(mut self, qop: I) -> Self {
+        self.qop = qop.into_iter().collect();
+        self
+    }
+
+    /// Sets the generator used to create and validate nonces.
+    pub fn with_nonces(mut self, nonces: NonceGenerator) -> Self {
+        self.nonces = NonceCountTracker::new(nonces);
+        self
+    }
+
+    /// Sets the tracker used to create and validate nonces, e.g. to issue a `nextnonce` (see
+    /// [`DigestAuthenticator::authentication_info`]).
+    pub fn with_nonce_tracker(mut self, nonces: NonceCountTracker) -> Self {
+        self.nonces = nonces;
+        self
+    }
+
+    /// Sets the cache used to remember session A1 values for the `-sess` algorithms, so that later
+    /// requests in the same session are validated without looking up the user in the store.
+    pub fn with_session_cache(mut self, sessions: SessionCache) -> Self {
+        self.sessions = Some(sessions);
+        self
+    }
+
+    /// The cache of session A1 values, if any.
+    pub fn session_cache(&self) -> Option<&SessionCache> {
+        self.sessions.as_ref()
+    }
+
+    /// Sets the URIs that share the protection space, which are sent in the `domain` parameter
+    /// of challenges. See [`ProtectionSpaces`](super::ProtectionSpaces) for serving several
+    /// protection spaces.
+    pub fn with_domain<I: IntoIterator<Item = String>>(mut self, domain: I) -> Self {
+        self.domain = domain.into_iter().collect();
+        self
+    }
+
+    /// The URIs that share the protection space.
+    pub fn domain(&self) -> &[String] {
+        &self.domain
+    }
+
+    /// Sets the `opaque` value that clients must send back unchanged.
+    pub fn with_opaque(mut self, opaque: &str) -> Self {
+        self.opaque = Some(opaque.to_owned());
+        self
+    }
+
+    /// Sets whether clients may send a userhash instead of the username.
+    pub fn with_userhash(mut self, userhash: bool) -> Self {
+        self.userhash = userhash;
+        self.index_userhashes();
+        self
+    }
+
+    /// The credential store.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// The credential store, e.g. to reload it. This forgets every cached session, so that
+    /// changed passwords take effect immediately, and the userhash index, which is rebuilt when
+    /// it is next needed.
+    pub fn store_mut(&mut self) -> &mut S {
+        if let Some(ref sessions) = self.sessions {
+            sessions.clear();
+        }
+        *self
+            .userhashes
+            .get_mut()
+            .unwrap_or_else(|error| error.into_inner()) = None;
+        &mut self.store
+    }
+
+    /// Indexes the users in the realm by the userhash of each offered algorithm, preferring the
+    /// userhashes that the store has already computed.
+    fn build_userhash_index(&self) -> UserhashIndex<IndexedUser> {
+        let mut index = UserhashIndex::new(self.algorithms.iter().cloned());
+        for record in self.store.records() {
+            if record.realm() != self.realm {
+                continue;
+            }
+            let user = IndexedUser {
+                username: record.username().to_owned(),
+                realm: self.realm.clone(),
+            };
+            for algorithm in &self.algorithms {
+                let algorithm = algorithm.base();
+                let userhash = record.userhash(&algorithm).map_or_else(
+                    || {
+                        Digest::userhash(
+                            &algorithm,
+                            user.username.as_bytes().to_vec(),
+                            self.realm.clone(),
+                        )
+                    },
+                    str::to_owned,
+                );
+                index.insert_userhash(&algorithm, &userhash, user.clone());
+            }
+        }
+        index
+    }
+
+    fn index_userhashes(&mut self) {
+        let index = self.userhash.then(|| self.build_userhash_index());
+        *self
+            .userhashes
+            .get_mut()
+            .unwrap_or_else(|error| error.into_inner()) = index;
+    }
+
+    /// The userhash index, which is built if the store has changed since it was last needed.
+    fn userhash_index(&self) -> RwLockReadGuard<'_, Option<UserhashIndex<IndexedUser>>> {
+        let index = self
+            .userhashes
+            .read()
+            .unwrap_or_else(|error| error.into_inner());
+        if index.is_some() {
+            return index;
+        }
+        drop(index);
+
+        let mut index = self
+            .userhashes
+            .write()
+            .unwrap_or_else(|error| error.into_inner());
+        if index.is_none() {
+            *index = Some(self.build_userhash_index());
+        }
+        drop(index);
+        self.userhashes
+            .read()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// Generates a challenge for each configured algorithm.
+    pub fn digest_challenges(&self, stale: bool) -> Vec<DigestChallenge> {
+        let nonce = self.nonces.generate();
+        self.algorithms
+            .iter()
+            .map(|algorithm| DigestChallenge {
+                realm: self.realm.clone(),
+                domain: self.domain.clone(),
+                nonce: nonce.clone(),
+                opaque: self.opaque.clone(),
+                stale,
+                algorithm: algorithm.clone(),
+                qop: self.qop.clone(),
+                charset: Some(Charset::UTF_8),
+                userhash: self.userhash,
+            })
+            .collect()
+    }
+
+    fn find_record(&self, digest: &Digest) -> Option<&S::Record> {
+        match digest.username {
+            Username::Plain(ref userhash) if digest.userhash => {
+                let username = self
+                    .userhash_index()
+                    .as_ref()?
+                    .get(&digest.algorithm, &self.realm, userhash)?
+                    .username
+                    .clone();
+                self.store.get(&username, &self.realm)
+            }
+            Username::Plain(ref username) => self.store.get(username, &self.realm),
+            Username::Encoded(ref encoded) => {
+                let username = std::str::from_utf8(&encoded.value).ok()?;
+                self.store.get(username, &self.realm)
+            }
+        }
+    }
+
+    /// Generates the `Authentication-Info` header for a response to a request whose credentials
+    /// were verified, as defined in
+    /// [RFC 7616, section 3.5](https://tools.ietf.org/html/rfc7616#section-3.5). It includes
+    /// `rspauth` and, if the nonce tracker's policy says so, a `nextnonce`.
+    ///
+    /// `response_body` is only used for `qop=auth-int`. Returns `None` if the user cannot be
+    /// found.
+    pub fn authentication_info(
+        &self,
+        digest: &Digest,
+        response_body: &[u8],
+    ) -> Option<AuthenticationInfo> {
+        let cached = self
+            .sessions
+            .as_ref()
+            .and_then(|sessions| sessions.get(digest));
+        let hashed_a1 = match cached {
+            Some(session) => session.hashed_a1,
+            None => {
+                let hashed_a1 = self.find_record(digest)?.hashed_a1(&digest.algorithm)?;
+                digest.session_hashed_a1(hashed_a1).ok()?
+            }
+        };
+        let rspauth = digest
+            .response_auth_using_hashed_a1(response_body, hashed_a1)
+            .ok()?;
+        Some(AuthenticationInfo {
+            digest: Some(ResponseDigest::ResponseAuth(rspauth)),
+            next_nonce: self.nonces.next_nonce(&digest.nonce),
+            qop: digest.qop.clone(),
+            client_nonce: digest.client_nonce.clone(),
+            nonce_count: digest.nonce_count.clone(),
+        })
+    }
+
+    /// Verifies `Digest` credentials against the challenges issued by this authenticator. The
+    /// principal is the name of the user, even if a userhash was sent.
+    pub fn verification(&self, digest: &Digest, request: &AuthRequest) -> Verification {
+        let qop_allowed = match digest.qop {
+            Some(ref qop) => self.qop.contains(qop),
+            None => self.qop.is_empty(),
+        };
+        let violation = if digest.realm != self.realm {
+            Some(PolicyViolation::RealmMismatch)
+        } else if digest.opaque != self.opaque {
+            Some(PolicyViolation::OpaqueMismatch)
+        } else if !self.algorithms.contains(&digest.algorithm) {
+            Some(PolicyViolation::DisallowedAlgorithm)
+        } else if !qop_allowed {
+            Some(PolicyViolation::DisallowedQop)
+        } else if digest.userhash && !self.userhash {
+            Some(PolicyViolation::DisallowedUserhash)
+        } else if !digest.validate_request_uri(request.method, request.uri, request.host) {
+            Some(PolicyViolation::RequestUriMismatch)
+        } else {
+            None
+        };
+        if let Some(violation) = violation {
+            return violation.into();
+        }
+
+        let cached = self
+            .sessions
+            .as_ref()
+            .and_then(|sessions| sessions.get(digest));
+        if let Some(session) = cached {
+            return match digest.verify_using_hashed_a1(
+                request.method.clone(),
+                request.body,
+                session.hashed_a1,
+                &self.nonces,
+            ) {
+                Verification::Ok(_) => Verification::Ok(Username::Plain(session.username)),
+                verification => verification,
+            };
+        }
+
+        let Some(record) = self.find_record(digest) else {
+            return Verification::UnknownUser;
+        };
+        let Some(hashed_a1) = record.hashed_a1(&digest.algorithm) else {
+            return Verification::UnknownUser;
+        };
+        match digest.verify_using_simple_hashed_a1(
+            request.method,
+            request.body,
+            hashed_a1,
+            &self.nonces,
+        ) {
+            Verification::Ok(_) => {
+                self.remember_session(digest, hashed_a1, record.username());
+                Verification::Ok(Username::Plain(record.username().to_owned()))
+            }
+            verification => verification,
+        }
+    }
+
+    /// Caches the session A1 value of a `-sess` algorithm until the nonce expires.
+    fn remember_session(&self, digest: &Digest, hashed_a1: &str, username: &str) {
+        let Some(ref sessions) = self.sessions else {
+            return;
+        };
+        if digest.algorithm == digest.algorithm.base() {
+            return;
+        }
+        let generator = self.nonces.generator();
+        let Some(age) = generator.age(&digest.nonce) else {
+            return;
+        };
+        if let Ok(hashed_a1) = digest.session_hashed_a1(hashed_a1) {
+            let expires_in = generator.lifetime().saturating_sub(age);
+            sessions.insert(digest, hashed_a1, username.to_owned(), expires_in);
+        }
+    }
+
+    /// Verifies `Digest` credentials, returning the name of the user.
+    ///
+    /// See [`DigestAuthenticator::verification`] for a more detailed outcome. Policy violations
+    /// that are answered with `403 Forbidden` are returned as [`AuthenticationError::Rejected`].
+    pub fn verify(
+        &self,
+        digest: &Digest,
+        request: &AuthRequest,
+    ) -> Result<String, AuthenticationError> {
+        match self.verification(digest, request) {
+            Verification::Ok(username) => Ok(username.to_string()),
+            Verification::StaleNonce => Err(AuthenticationError::StaleNonce),
+            Verification::PolicyViolation(violation) => match violation.status_code() {
+                StatusCode::BAD_REQUEST => Err(AuthenticationError::MalformedCredentials),
+                StatusCode::FORBIDDEN => Err(AuthenticationError::Rejected {
+                    status: StatusCode::FORBIDDEN,
+                    challenges: self.challenges(None),
+                }),
+                _ => Err(AuthenticationError::InvalidCredentials),
+            },
+            _ => Err(AuthenticationError::InvalidCredentials),
+        }
+    }
+}
+
+impl<S> Authenticator for DigestAuthenticator<S>
+where
+    S: CredentialStore,
+    S::Record: DigestRecord,
+{
+    type Principal = String;
+
+    fn scheme(&self) -> &str {
+        Digest::SCHEME
+    }
+
+    fn challenges(&self, error: Option<&AuthenticationError>) -> Vec<HeaderValue> {
+        if let Some(AuthenticationError::Rejected { challenges, .. }) = error {
+            return challenges.clone();
+        }
+        let stale = error == Some(&AuthenticationError::StaleNonce);
+        self.digest_challenges(stale)
+            .iter()
+            .map(DigestChallenge::encode)
+            .collect()
+    }
+
+    fn authenticate(
+        &self,
+        value: &HeaderValue,
+        request: &AuthRequest,
+    ) -> Result<String, AuthenticationError> {
+        let digest = Digest::decode(value).ok_or(AuthenticationError::MalformedCredentials)?;
+        self.verify(&digest, request)
+    }
+}
@@ -0,0 +1,529 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::DigestAuthenticator;
+use crate::authentication_info::ResponseDigest;
+use crate::authenticator::{AuthRequest, AuthenticationError, Authenticator};
+use crate::challenge::Challenge;
+use crate::digest::SessionCache;
+use crate::digest::nonce::NonceGenerator;
+use crate::digest::test_helper::respond_to_challenge;
+use crate::digest::{Digest, DigestChallenge, PolicyViolation, Username, Verification};
+use crate::store::CredentialStore;
+use crate::store::htdigest::{Htdigest, HtdigestRecord};
+use crate::store::multi_digest::{MultiDigest, MultiDigestRecord};
+use crate::types::{HashAlgorithm, NonceCount, Qop};
+use headers::authorization::Credentials;
+use http::{Method, StatusCode, Uri};
+use std::cell::Cell;
+use std::time::Duration;
+
+const REALM: &str = "testrealm@host.com";
+
+fn authenticator(algorithm: HashAlgorithm) -> DigestAuthenticator<Htdigest> {
+    let mut store = Htdigest::new();
+    store.insert(HtdigestRecord::new(
+        "Mufasa",
+        REALM,
+        "Circle Of Life".to_owned(),
+        &algorithm.base(),
+    ));
+    DigestAuthenticator::new(REALM, store)
+        .with_algorithms([algorithm])
+        .with_nonces(NonceGenerator::with_key([7; 32]))
+}
+
+fn challenge(authenticator: &DigestAuthenticator<Htdigest>) -> DigestChallenge {
+    let value = authenticator.challenges(None).remove(0);
+    DigestChallenge::decode(value.to_str().expect("Invalid header value"))
+        .expect("Could not decode challenge")
+}
+
+fn verify(
+    authenticator: &DigestAuthenticator<Htdigest>,
+    digest: &Digest,
+) -> Result<String, AuthenticationError> {
+    let uri = Uri::from_static("/dir/index.html");
+    authenticator.authenticate(&digest.encode(), &AuthRequest::new(&Method::GET, &uri))
+}
+
+#[test]
+fn test_authenticate() {
+    for algorithm in [HashAlgorithm::Md5, HashAlgorithm::Sha256Session] {
+        let authenticator = authenticator(algorithm);
+        let challenge = challenge(&authenticator);
+        let digest = respond_to_challenge(
+            &challenge,
+            "Mufasa",
+            "Circle Of Life",
+            Method::GET,
+            "/dir/index.html",
+        );
+        assert_eq!(Ok("Mufasa".to_owned()), verify(&authenticator, &digest));
+    }
+}
+
+#[test]
+fn test_authenticate_with_wrong_password_or_user() {
+    let authenticator = authenticator(HashAlgorithm::Md5);
+    let challenge = challenge(&authenticator);
+    for (username, password) in [("Mufasa", "Circle of Death"), ("Scar", "Circle Of Life")] {
+        let digest = respond_to_challenge(
+            &challenge,
+            username,
+            password,
+            Method::GET,
+            "/dir/index.html",
+        );
+        assert_eq!(
+            Err(AuthenticationError::InvalidCredentials),
+            verify(&authenticator, &digest)
+        );
+    }
+}
+
+#[test]
+fn test_authenticate_with_unoffered_parameters() {
+    let authenticator = authenticator(HashAlgorithm::Md5)
+        .with_algorithms([HashAlgorithm::Md5, HashAlgorithm::Sha256]);
+    let mut challenge = challenge(&authenticator);
+    challenge.qop = vec![Qop::AuthInt];
+    let digest = respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle Of Life",
+        Method::GET,
+        "/dir/index.html",
+    );
+    let uri = Uri::from_static("/dir/index.html");
+    let request = AuthRequest::new(&Method::GET, &uri);
+    assert_eq!(
+        Verification::PolicyViolation(PolicyViolation::DisallowedQop),
+        authenticator.verification(&digest, &request)
+    );
+    match verify(&authenticator, &digest) {
+        Err(AuthenticationError::Rejected { status, challenges }) => {
+            assert_eq!(StatusCode::FORBIDDEN, status);
+            assert_eq!(2, challenges.len());
+        }
+        result => panic!("Unexpected result: {:?}", result),
+    }
+
+    let mut challenge = self::challenge(&authenticator);
+    challenge.realm = "otherrealm@host.com".to_owned();
+    let digest = respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle Of Life",
+        Method::GET,
+        "/dir/index.html",
+    );
+    assert_eq!(
+        Err(AuthenticationError::InvalidCredentials),
+        verify(&authenticator, &digest)
+    );
+}
+
+#[test]
+fn test_authenticate_with_forged_or_replayed_nonce() {
+    let authenticator = authenticator(HashAlgorithm::Md5);
+    let mut challenge = challenge(&authenticator);
+    challenge.nonce = "forged".to_owned();
+    let digest = respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle Of Life",
+        Method::GET,
+        "/dir/index.html",
+    );
+    assert_eq!(
+        Err(AuthenticationError::StaleNonce),
+        verify(&authenticator, &digest)
+    );
+
+    let challenge = self::challenge(&authenticator);
+    let digest = respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle Of Life",
+        Method::GET,
+        "/dir/index.html",
+    );
+    let uri = Uri::from_static("/dir/index.html");
+    let request = AuthRequest::new(&Method::GET, &uri);
+    assert_eq!(
+        Verification::Ok(Username::Plain("Mufasa".to_owned())),
+        authenticator.verification(&digest, &request)
+    );
+    assert_eq!(
+        Verification::ReplayedNonceCount,
+        authenticator.verification(&digest, &request)
+    );
+    assert_eq!(
+        Err(AuthenticationError::InvalidCredentials),
+        verify(&authenticator, &digest)
+    );
+}
+
+#[test]
+fn test_authentication_info() {
+    let authenticator = authenticator(HashAlgorithm::Sha256Session);
+    let challenge = challenge(&authenticator);
+    let digest = respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle Of Life",
+        Method::GET,
+        "/dir/index.html",
+    );
+    let info = authenticator
+        .authentication_info(&digest, b"")
+        .expect("No Authentication-Info");
+    let simple_hashed_a1 = Digest::simple_hashed_a1(
+        &HashAlgorithm::Sha256,
+        Username::Plain("Mufasa".to_owned()),
+        REALM.to_owned(),
+        "Circle Of Life".to_owned(),
+    );
+    let hashed_a1 = digest
+        .session_hashed_a1(&simple_hashed_a1)
+        .expect("Could not compute A1");
+    let rspauth = digest
+        .response_auth_using_hashed_a1(b"", hashed_a1)
+        .expect("Could not compute rspauth");
+    assert_eq!(Some(ResponseDigest::ResponseAuth(rspauth)), info.digest);
+    assert_eq!(None, info.next_nonce);
+    assert_eq!(digest.client_nonce, info.client_nonce);
+    assert_eq!(digest.nonce_count, info.nonce_count);
+
+    let mut unknown = digest.clone();
+    unknown.username = Username::Plain("Scar".to_owned());
+    assert_eq!(None, authenticator.authentication_info(&unknown, b""));
+}
+
+#[derive(Debug)]
+struct CountingStore {
+    store: Htdigest,
+    lookups: Cell<usize>,
+    scans: Cell<usize>,
+}
+
+impl CountingStore {
+    fn new(store: Htdigest) -> Self {
+        CountingStore {
+            store,
+            lookups: Cell::new(0),
+            scans: Cell::new(0),
+        }
+    }
+}
+
+impl CredentialStore for CountingStore {
+    type Record = HtdigestRecord;
+
+    fn get(&self, username: &str, realm: &str) -> Option<&HtdigestRecord> {
+        self.lookups.set(self.lookups.get() + 1);
+        self.store.get(username, realm)
+    }
+
+    fn records(&self) -> impl Iterator<Item = &HtdigestRecord> {
+        self.scans.set(self.scans.get() + 1);
+        self.store.records()
+    }
+}
+
+#[test]
+fn test_authenticate_with_session_cache() {
+    let mut store = Htdigest::new();
+    store.insert(HtdigestRecord::new(
+        "Mufasa",
+        REALM,
+        "Circle Of Life".to_owned(),
+        &HashAlgorithm::Sha256,
+    ));
+    let mut authenticator = DigestAuthenticator::new(REALM, CountingStore::new(store))
+        .with_algorithms([HashAlgorithm::Sha256Session])
+        .with_nonces(NonceGenerator::with_key([7; 32]))
+        .with_session_cache(SessionCache::new(16));
+    let value = authenticator.challenges(None).remove(0);
+    let challenge = DigestChallenge::decode(value.to_str().expect("Invalid header value"))
+        .expect("Could not decode challenge");
+    let mut digest = respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle Of Life",
+        Method::GET,
+        "/dir/index.html",
+    );
+    let uri = Uri::from_static("/dir/index.html");
+    let request = AuthRequest::new(&Method::GET, &uri);
+    assert_eq!(
+        Ok("Mufasa".to_owned()),
+        authenticator.verify(&digest, &request)
+    );
+    assert_eq!(1, authenticator.store().lookups.get());
+    assert_eq!(
+        Some(1),
+        authenticator.session_cache().map(SessionCache::len)
+    );
+
+    digest.nonce_count = Some(NonceCount(2));
+    digest.response = digest
+        .using_password(Method::GET, b"", "Circle Of Life".to_owned())
+        .expect("Could not compute response");
+    assert_eq!(
+        Ok("Mufasa".to_owned()),
+        authenticator.verify(&digest, &request)
+    );
+    assert!(authenticator.authentication_info(&digest, b"").is_some());
+    digest.nonce_count = Some(NonceCount(3));
+    digest.response = digest
+        .using_password(Method::GET, b"", "Circle of Death".to_owned())
+        .expect("Could not compute response");
+    assert_eq!(
+        Err(AuthenticationError::InvalidCredentials),
+        authenticator.verify(&digest, &request)
+    );
+    assert_eq!(1, authenticator.store().lookups.get());
+
+    authenticator.store_mut().store = Htdigest::new();
+    assert_eq!(
+        Some(true),
+        authenticator.session_cache().map(SessionCache::is_empty)
+    );
+    digest.nonce_count = Some(NonceCount(4));
+    digest.response = digest
+        .using_password(Method::GET, b"", "Circle Of Life".to_owned())
+        .expect("Could not compute response");
+    assert_eq!(
+        Verification::UnknownUser,
+        authenticator.verification(&digest, &request)
+    );
+}
+
+#[test]
+fn test_authenticate_with_indexed_userhash() {
+    let mut store = Htdigest::new();
+    store.insert(HtdigestRecord::new(
+        "Mufasa",
+        REALM,
+        "Circle Of Life".to_owned(),
+        &HashAlgorithm::Sha256,
+    ));
+    let mut authenticator = DigestAuthenticator::new(REALM, CountingStore::new(store))
+        .with_algorithms([HashAlgorithm::Sha256])
+        .with_nonces(NonceGenerator::with_key([7; 32]))
+        .with_userhash(true);
+    let scans = authenticator.store().scans.get();
+    let value = authenticator.challenges(None).remove(0);
+    let challenge = DigestChallenge::decode(value.to_str().expect("Invalid header value"))
+        .expect("Could not decode challenge");
+    let mut digest = respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle Of Life",
+        Method::GET,
+        "/dir/index.html",
+    );
+    digest.userhash = true;
+    digest.username = Username::Plain(Digest::userhash(
+        &HashAlgorithm::Sha256,
+        b"Mufasa".to_vec(),
+        REALM.to_owned(),
+    ));
+    let uri = Uri::from_static("/dir/index.html");
+    let request = AuthRequest::new(&Method::GET, &uri);
+    assert_eq!(
+        Ok("Mufasa".to_owned()),
+        authenticator.verify(&digest, &request)
+    );
+    assert_eq!(scans, authenticator.store().scans.get());
+
+    authenticator.store_mut().store = Htdigest::new();
+    digest.nonce_count = Some(NonceCount(2));
+    assert_eq!(
+        Verification::UnknownUser,
+        authenticator.verification(&digest, &request)
+    );
+    assert_eq!(scans + 1, authenticator.store().scans.get());
+    assert_eq!(
+        Verification::UnknownUser,
+        authenticator.verification(&digest, &request)
+    );
+    assert_eq!(scans + 1, authenticator.store().scans.get());
+}
+
+#[test]
+fn test_authenticate_for_another_uri() {
+    let authenticator = authenticator(HashAlgorithm::Md5);
+    let challenge = challenge(&authenticator);
+    let digest = respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle Of Life",
+        Method::GET,
+        "/public",
+    );
+    let uri = Uri::from_static("/admin");
+    assert_eq!(
+        Verification::PolicyViolation(PolicyViolation::RequestUriMismatch),
+        authenticator.verification(&digest, &AuthRequest::new(&Method::GET, &uri))
+    );
+    assert_eq!(
+        Err(AuthenticationError::MalformedCredentials),
+        verify(&authenticator, &digest)
+    );
+}
+
+#[test]
+fn test_authenticate_without_client_nonce() {
+    let authenticator = authenticator(HashAlgorithm::Md5Session);
+    let challenge = challenge(&authenticator);
+    let mut digest = respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle Of Life",
+        Method::GET,
+        "/dir/index.html",
+    );
+    digest.client_nonce = None;
+    assert_eq!(
+        Err(AuthenticationError::MalformedCredentials),
+        verify(&authenticator, &digest)
+    );
+}
+
+#[test]
+fn test_authenticate_with_stale_nonce() {
+    let authenticator = authenticator(HashAlgorithm::Md5)
+        .with_nonces(NonceGenerator::with_key([7; 32]).with_lifetime(Duration::ZERO));
+    let challenge = challenge(&authenticator);
+    let digest = respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle Of Life",
+        Method::GET,
+        "/dir/index.html",
+    );
+    let error = verify(&authenticator, &digest).expect_err("Nonce should be stale");
+    assert_eq!(AuthenticationError::StaleNonce, error);
+
+    let value = authenticator.challenges(Some(&error)).remove(0);
+    assert!(
+        DigestChallenge::decode(value.to_str().expect("Invalid header value"))
+            .expect("Could not decode challenge")
+            .stale
+    );
+
+    let digest = respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle of Death",
+        Method::GET,
+        "/dir/index.html",
+    );
+    assert_eq!(
+        Err(AuthenticationError::InvalidCredentials),
+        verify(&authenticator, &digest)
+    );
+}
+
+#[test]
+fn test_authenticate_with_userhash() {
+    let authenticator = authenticator(HashAlgorithm::Sha256).with_userhash(true);
+    let challenge = challenge(&authenticator);
+    assert!(challenge.userhash);
+    let mut digest = respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle Of Life",
+        Method::GET,
+        "/dir/index.html",
+    );
+    digest.userhash = true;
+    digest.username = Username::Plain(Digest::userhash(
+        &HashAlgorithm::Sha256,
+        b"Mufasa".to_vec(),
+        REALM.to_owned(),
+    ));
+    assert_eq!(Ok("Mufasa".to_owned()), verify(&authenticator, &digest));
+
+    let authenticator = authenticator.with_userhash(false);
+    let uri = Uri::from_static("/dir/index.html");
+    assert_eq!(
+        Verification::PolicyViolation(PolicyViolation::DisallowedUserhash),
+        authenticator.verification(&digest, &AuthRequest::new(&Method::GET, &uri))
+    );
+}
+
+#[test]
+fn test_authenticate_malformed() {
+    let authenticator = authenticator(HashAlgorithm::Md5);
+    let uri = Uri::from_static("/");
+    let value = http::HeaderValue::from_static("Digest username=\"Mufasa\"");
+    assert_eq!(
+        Err(AuthenticationError::MalformedCredentials),
+        authenticator.authenticate(&value, &AuthRequest::new(&Method::GET, &uri))
+    );
+}
+
+#[test]
+fn test_authenticate_with_multiple_algorithms() {
+    let mut store = MultiDigest::new();
+    store.insert(MultiDigestRecord::new(
+        "Mufasa",
+        REALM,
+        "Circle Of Life",
+        &[HashAlgorithm::Sha256, HashAlgorithm::Md5],
+        true,
+    ));
+    let authenticator = DigestAuthenticator::new(REALM, store)
+        .with_algorithms([
+            HashAlgorithm::Sha256,
+            HashAlgorithm::Md5Session,
+            HashAlgorithm::Sha512256,
+        ])
+        .with_userhash(true);
+    let uri = Uri::from_static("/dir/index.html");
+    assert_eq!(3, authenticator.challenges(None).len());
+    for (index, expected) in [true, true, false].into_iter().enumerate() {
+        let value = &authenticator.challenges(None)[index];
+        let challenge = DigestChallenge::decode(value.to_str().expect("Invalid header value"))
+            .expect("Could not decode challenge");
+        let mut digest = respond_to_challenge(
+            &challenge,
+            "Mufasa",
+            "Circle Of Life",
+            Method::GET,
+            "/dir/index.html",
+        );
+        digest.userhash = true;
+        digest.username = Username::Plain(Digest::userhash(
+            &challenge.algorithm,
+            b"Mufasa".to_vec(),
+            REALM.to_owned(),
+        ));
+        let result =
+            authenticator.authenticate(&digest.encode(), &AuthRequest::new(&Method::GET, &uri));
+        assert_eq!(expected, result.is_ok(), "{}", challenge.algorithm);
+    }
+}
@@ -0,0 +1,121 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! The `Digest` challenge, sent by the server in the `WWW-Authenticate` header.
+
+use crate::challenge::Challenge;
+use crate::parsing::fromheaders::Charset;
+use crate::parsing::fromheaders::from_comma_delimited;
+use crate::parsing::{
+    DigestParameters, parse_parameters, raw_map_value, strip_scheme, to_header_value,
+    unraveled_map_value,
+};
+use crate::types::{HashAlgorithm, Qop};
+use http::HeaderValue;
+
+/// Parameters for the `WWW-Authenticate: Digest` header, as defined in
+/// [RFC 7616, section 3.3](https://tools.ietf.org/html/rfc7616#section-3.3).
+#[derive(Clone, Debug, PartialEq)]
+pub struct DigestChallenge {
+    /// The protection space
+    pub realm: String,
+    /// URIs that share the protection space, parameter name `domain`
+    pub domain: Vec<String>,
+    /// Server-generated nonce
+    pub nonce: String,
+    /// Opaque data, which the client returns unchanged
+    pub opaque: Option<String>,
+    /// Whether the previous request was rejected only because its nonce was stale
+    pub stale: bool,
+    /// The hash algorithm, `MD5` if not specified
+    pub algorithm: HashAlgorithm,
+    /// Supported qualities of protection, parameter name `qop`
+    pub qop: Vec<Qop>,
+    /// The encoding the server expects usernames and passwords in (only `UTF-8` is allowed)
+    pub charset: Option<Charset>,
+    /// Whether the server supports username hashing
+    pub userhash: bool,
+}
+
+impl Challenge for DigestChallenge {
+    const SCHEME: &'static str = "Digest";
+
+    fn decode(value: &str) -> Option<Self> {
+        let map = parse_parameters(strip_scheme(value, Self::SCHEME)?).ok()?;
+        let flag = |key: &str| {
+            unraveled_map_value(&map, key).is_some_and(|value| value.eq_ignore_ascii_case("true"))
+        };
+        let algorithm = match unraveled_map_value(&map, "algorithm") {
+            Some(algorithm) => algorithm.parse().ok()?,
+            None => HashAlgorithm::Md5,
+        };
+        let charset = match unraveled_map_value(&map, "charset") {
+            Some(charset) => Some(charset.parse().ok()?),
+            None => None,
+        };
+        // Unrecognized qop values are ignored, as required by RFC 7616.
+        let qop = unraveled_map_value(&map, "qop")
+            .and_then(|qop| from_comma_delimited(&qop).ok())
+            .map(|qop| qop.iter().filter_map(|value| value.parse().ok()).collect())
+            .unwrap_or_default();
+
+        Some(DigestChallenge {
+            realm: unraveled_map_value(&map, "realm")?,
+            // The domain is a list of URIs, which are already percent-encoded.
+            domain: raw_map_value(&map, "domain")
+                .map(|domain| domain.split_whitespace().map(str::to_owned).collect())
+                .unwrap_or_default(),
+            nonce: unraveled_map_value(&map, "nonce")?,
+            opaque: unraveled_map_value(&map, "opaque"),
+            stale: flag("stale"),
+            algorithm,
+            qop,
+            charset,
+            userhash: flag("userhash"),
+        })
+    }
+
+    fn encode(&self) -> HeaderValue {
+        let mut parameters = DigestParameters::new();
+        parameters.append("realm", &self.realm, true);
+        if !self.domain.is_empty() {
+            parameters.append_raw("domain", &self.domain.join(" "), true);
+        }
+        parameters.append("nonce", &self.nonce, true);
+        if let Some(ref opaque) = self.opaque {
+            parameters.append("opaque", opaque, true);
+        }
+        if self.stale {
+            parameters.append("stale", "true", false);
+        }
+        parameters.append("algorithm", &self.algorithm.to_string(), false);
+        if !self.qop.is_empty() {
+            let qop: Vec<String> = self.qop.iter().map(Qop::to_string).collect();
+            parameters.append("qop", &qop.join(", "), true);
+        }
+        if let Some(ref charset) = self.charset {
+            parameters.append("charset", &charset.to_string(), false);
+        }
+        if self.userhash {
+            parameters.append("userhash", "true", false);
+        }
+        to_header_value(&format!("{} {}", Self::SCHEME, parameters))
+    }
+}
@@ -0,0 +1,512 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! The client side of `Digest` authentication.
+
+use super::{Digest, DigestChallenge, ProtectionSpaces, Username};
+use crate::authentication_info::{AuthenticationInfo, ResponseDigest};
+use crate::challenge::WwwAuthenticate;
+use crate::parsing::fromheaders::{Charset, ExtendedValue};
+use crate::types::{NonceCount, Qop};
+use headers::authorization::Credentials;
+use headers::{Error, Header};
+use http::header::{
+    AUTHORIZATION, GetAll, HeaderName, PROXY_AUTHENTICATE, PROXY_AUTHORIZATION, WWW_AUTHENTICATE,
+};
+use http::uri::Scheme;
+use http::{HeaderMap, HeaderValue, Method, StatusCode, Uri};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use subtle::ConstantTimeEq;
+
+mod test;
+
+const CLIENT_NONCE_LENGTH: usize = 16;
+
+const AUTHENTICATION_INFO: HeaderName = HeaderName::from_static("authentication-info");
+/// Defined in [RFC 7615, section 4](https://tools.ietf.org/html/rfc7615#section-4).
+const PROXY_AUTHENTICATION_INFO: HeaderName = HeaderName::from_static("proxy-authentication-info");
+
+/// The origin of an absolute URI, e.g. `https://example.com:443`, which is one half of the key
+/// of the cache.
+fn origin(uri: &Uri) -> Option<String> {
+    let scheme = uri.scheme()?;
+    let authority = uri.authority()?;
+    let port = authority.port_u16().or(match scheme {
+        scheme if *scheme == Scheme::HTTP => Some(80),
+        scheme if *scheme == Scheme::HTTPS => Some(443),
+        _ => None,
+    });
+    let host = authority.host().to_ascii_lowercase();
+    Some(match port {
+        Some(port) => format!("{}://{}:{}", scheme, host, port),
+        None => format!("{}://{}", scheme, host),
+    })
+}
+
+/// The `uri` parameter for a request: the authority for `CONNECT`, the path and query otherwise.
+fn request_target(method: &Method, uri: &Uri) -> String {
+    match (method, uri.authority()) {
+        (&Method::CONNECT, Some(authority)) => authority.to_string(),
+        _ => uri
+            .path_and_query()
+            .map_or_else(|| "/".to_owned(), ToString::to_string),
+    }
+}
+
+struct CachedCredentials {
+    username: String,
+    password: String,
+}
+
+/// The password is omitted.
+impl fmt::Debug for CachedCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CachedCredentials")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The last challenge received for a protection space, and the last nonce count sent with its
+/// nonce.
+#[derive(Debug)]
+struct CachedChallenge {
+    challenge: DigestChallenge,
+    nonce_count: NonceCount,
+}
+
+impl CachedChallenge {
+    fn new(challenge: DigestChallenge) -> Self {
+        CachedChallenge {
+            challenge,
+            nonce_count: NonceCount(0),
+        }
+    }
+}
+
+impl Borrow<DigestChallenge> for CachedChallenge {
+    fn borrow(&self) -> &DigestChallenge {
+        &self.challenge
+    }
+}
+
+/// A client-side cache of `Digest` credentials, keyed by origin and realm, which avoids a
+/// `401 Unauthorized` round trip for every request.
+///
+/// The last challenge received for each protection space is remembered (see
+/// [`ProtectionSpaces`]), so that an `Authorization` header can be computed preemptively for
+/// subsequent requests to URIs in the same space, with an incremented nonce count. Each space
+/// counts the uses of its own nonce. When the server responds with `stale=true` or sends a
+/// `nextnonce`, the new nonce replaces the old one and the nonce count starts over.
+#[derive(Debug, Default)]
+pub struct DigestCredentialCache {
+    spaces: ProtectionSpaces<CachedChallenge>,
+    credentials: HashMap<(String, String), CachedCredentials>,
+}
+
+impl DigestCredentialCache {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the credentials to use for `realm` on the origin of `uri`, which must be absolute.
+    /// Returns `false` if it is not.
+    pub fn set_credentials(
+        &mut self,
+        uri: &Uri,
+        realm: &str,
+        username: &str,
+        password: &str,
+    ) -> bool {
+        let Some(origin) = origin(uri) else {
+            return false;
+        };
+        self.credentials.insert(
+            (origin, realm.to_owned()),
+            CachedCredentials {
+                username: username.to_owned(),
+                password: password.to_owned(),
+            },
+        );
+        true
+    }
+
+    /// Removes the credentials for `realm` on the origin of `uri`, e.g. after they have been
+    /// rejected.
+    pub fn remove_credentials(&mut self, uri: &Uri, realm: &str) {
+        if let Some(origin) = origin(uri) {
+            self.credentials.remove(&(origin, realm.to_owned()));
+        }
+    }
+
+    /// Records a challenge received in response to a request for `uri`. When a server sends
+    /// several challenges, the first one should be used.
+    ///
+    /// Returns whether credentials are known for the challenge's realm, i.e. whether the request
+    /// can be retried without asking the user (e.g. because the challenge is `stale`).
+    pub fn handle_challenge(&mut self, uri: &Uri, challenge: DigestChallenge) -> bool {
+        let Some(origin) = origin(uri) else {
+            return false;
+        };
+        let key = (origin, challenge.realm.clone());
+        self.spaces
+            .insert_challenge(uri, CachedChallenge::new(challenge));
+        self.credentials.contains_key(&key)
+    }
+
+    /// Records the `Authentication-Info` header received in response to a request for `uri`. If
+    /// it contains a `nextnonce`, that nonce is used for subsequent requests.
+    pub fn handle_authentication_info(&mut self, uri: &Uri, info: &AuthenticationInfo) {
+        let Some(ref next_nonce) = info.next_nonce else {
+            return;
+        };
+        let Some(space) = self.spaces.find_mut(uri) else {
+            return;
+        };
+        let cached = space.value_mut();
+        cached.challenge.nonce = next_nonce.clone();
+        cached.challenge.stale = false;
+        cached.nonce_count = NonceCount(0);
+    }
+
+    /// Verifies the `rspauth` value in the `Authentication-Info` header received in response to
+    /// a request for `uri` that was sent with `digest`, as defined in
+    /// [RFC 7616, section 3.5](https://tools.ietf.org/html/rfc7616#section-3.5), then records
+    /// any `nextnonce` (see [`DigestCredentialCache::handle_authentication_info`]).
+    ///
+    /// `response_body` is only used for `qop=auth-int`. Returns `true` if there is no `rspauth`
+    /// to verify.
+    pub fn verify_authentication_info(
+        &mut self,
+        uri: &Uri,
+        digest: &Digest,
+        info: &AuthenticationInfo,
+        response_body: &[u8],
+    ) -> bool {
+        if let Some(ResponseDigest::ResponseAuth(ref rspauth)) = info.digest {
+            let Some(credentials) = origin(uri)
+                .and_then(|origin| self.credentials.get(&(origin, digest.realm.clone())))
+            else {
+                return false;
+            };
+            let simple_hashed_a1 = Digest::simple_hashed_a1(
+                &digest.algorithm,
+                Username::Plain(credentials.username.clone()),
+                digest.realm.clone(),
+                credentials.password.clone(),
+            );
+            let expected = digest
+                .session_hashed_a1(&simple_hashed_a1)
+                .and_then(|a1| digest.response_auth_using_hashed_a1(response_body, a1));
+            let echoed = info
+                .client_nonce
+                .as_ref()
+                .is_none_or(|client_nonce| Some(client_nonce) == digest.client_nonce.as_ref())
+                && info
+                    .nonce_count
+                    .as_ref()
+                    .is_none_or(|nonce_count| Some(nonce_count) == digest.nonce_count.as_ref());
+            let matches = expected.is_ok_and(|expected| {
+                bool::from(
+                    expected
+                        .as_bytes()
+                        .ct_eq(rspauth.to_ascii_lowercase().as_bytes()),
+                )
+            });
+            if !(echoed && matches) {
+                return false;
+            }
+        }
+        self.handle_authentication_info(uri, info);
+        true
+    }
+
+    /// The challenge that applies to `uri`, if any.
+    pub fn challenge(&self, uri: &Uri) -> Option<&DigestChallenge> {
+        self.spaces.find(uri).map(|space| &space.value().challenge)
+    }
+
+    /// Computes the `Authorization` credentials for a request, if `uri` (which must be absolute)
+    /// is in a known protection space with credentials, incrementing the nonce count.
+    ///
+    /// `auth` is preferred over `auth-int` if both are offered; `entity_body` is only used for
+    /// the latter. Returns `None` once the nonce count is exhausted, in which case the server
+    /// needs to issue a new challenge.
+    pub fn authorization(
+        &mut self,
+        method: Method,
+        uri: &Uri,
+        entity_body: &[u8],
+    ) -> Option<Result<Digest, Error>> {
+        let origin = origin(uri)?;
+        let cached = self.spaces.find_mut(uri)?.value_mut();
+        let credentials = self
+            .credentials
+            .get(&(origin, cached.challenge.realm.clone()))?;
+        let nonce_count = cached.nonce_count.next()?;
+        cached.nonce_count = nonce_count.clone();
+        let challenge = &cached.challenge;
+
+        let qop = [Qop::Auth, Qop::AuthInt]
+            .into_iter()
+            .find(|qop| challenge.qop.contains(qop));
+        let username = if challenge.userhash {
+            Username::Plain(Digest::userhash(
+                &challenge.algorithm,
+                credentials.username.as_bytes().to_vec(),
+                challenge.realm.clone(),
+            ))
+        } else if credentials.username.is_ascii() {
+            Username::Plain(credentials.username.clone())
+        } else {
+            Username::Encoded(ExtendedValue {
+                charset: Charset::UTF_8,
+                language_tag: None,
+                value: credentials.username.as_bytes().to_vec(),
+            })
+        };
+        let client_nonce = (qop.is_some() || challenge.algorithm != challenge.algorithm.base())
+            .then(|| base16ct::lower::encode_string(&rand::random::<[u8; CLIENT_NONCE_LENGTH]>()));
+        let mut digest = Digest {
+            username,
+            realm: challenge.realm.clone(),
+            nonce: challenge.nonce.clone(),
+            nonce_count: qop.as_ref().map(|_| nonce_count),
+            response: String::new(),
+            request_uri: request_target(&method, uri),
+            algorithm: challenge.algorithm.clone(),
+            qop: qop.clone(),
+            client_nonce,
+            opaque: challenge.opaque.clone(),
+            charset: challenge.charset.clone(),
+            userhash: challenge.userhash,
+        };
+        let simple_hashed_a1 = Digest::simple_hashed_a1(
+            &digest.algorithm,
+            Username::Plain(credentials.username.clone()),
+            digest.realm.clone(),
+            credentials.password.clone(),
+        );
+        Some(
+            digest
+                .session_hashed_a1(&simple_hashed_a1)
+                .and_then(|a1| digest.using_hashed_a1(method, entity_body, a1))
+                .map(|response| {
+                    digest.response = response;
+                    digest
+                }),
+        )
+    }
+}
+
+/// An error from a client that retries requests challenged with `Digest`.
+#[derive(Debug, thiserror::Error)]
+pub enum DigestClientError {
+    /// The credentials could not be computed from the challenge.
+    #[error("Could not compute credentials")]
+    Credentials,
+    /// The server's `rspauth` does not match, so it does not know the user's secret.
+    #[error("Invalid rspauth")]
+    InvalidResponseAuth,
+    /// The request body could not be buffered for replaying.
+    #[error("Could not read request body: {0}")]
+    Body(BoxError),
+    /// The request failed.
+    #[error("Request failed: {0}")]
+    Request(BoxError),
+}
+
+/// A boxed error from a body or service.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The `Digest` credentials sent with one attempt at a request.
+#[derive(Clone, Debug, Default)]
+pub struct DigestAttempt {
+    origin: Option<Digest>,
+    proxy: Option<Digest>,
+}
+
+/// Client-side state for sending `Digest` credentials with requests and retrying requests that
+/// are challenged with `401 Unauthorized` or `407 Proxy Authentication Required`.
+///
+/// Credentials for origin servers and proxies are kept in separate [`DigestCredentialCache`]s,
+/// both keyed by the origin of the request URI, which must be absolute. Clones share the caches.
+/// This is used by the `tower` and `reqwest` integrations, and can be used directly with other
+/// clients:
+///
+/// 1. Call [`DigestClientAuth::authorize`] to add credentials to a request.
+/// 2. Call [`DigestClientAuth::handle_response`] with the response. If it returns `true`, send
+///    the request again, starting from step 1.
+#[derive(Clone)]
+pub struct DigestClientAuth {
+    username: String,
+    password: String,
+    origins: Arc<Mutex<DigestCredentialCache>>,
+    proxies: Arc<Mutex<DigestCredentialCache>>,
+}
+
+/// The password is omitted.
+impl fmt::Debug for DigestClientAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DigestClientAuth")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+fn lock(cache: &Mutex<DigestCredentialCache>) -> MutexGuard<'_, DigestCredentialCache> {
+    cache.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+impl DigestClientAuth {
+    /// The maximum number of times a request is sent: once for a proxy challenge, once for an
+    /// origin challenge, and once more for a stale nonce.
+    pub const MAX_ATTEMPTS: usize = 3;
+
+    /// Creates the state for a user, whose credentials are sent to any server or proxy that
+    /// challenges it.
+    pub fn new(username: &str, password: &str) -> DigestClientAuth {
+        DigestClientAuth {
+            username: username.to_owned(),
+            password: password.to_owned(),
+            origins: Arc::default(),
+            proxies: Arc::default(),
+        }
+    }
+
+    /// Adds the `Authorization` and `Proxy-Authorization` headers for a request, if `uri` is
+    /// in a protection space that has been challenged before.
+    pub fn authorize(
+        &self,
+        method: &Method,
+        uri: &Uri,
+        entity_body: &[u8],
+        headers: &mut HeaderMap,
+    ) -> Result<DigestAttempt, DigestClientError> {
+        let mut authorize =
+            |cache: &Mutex<DigestCredentialCache>, name: HeaderName| match lock(cache)
+                .authorization(method.clone(), uri, entity_body)
+            {
+                Some(Ok(digest)) => {
+                    headers.insert(name, digest.encode());
+                    Ok(Some(digest))
+                }
+                Some(Err(_)) => Err(DigestClientError::Credentials),
+                None => Ok(None),
+            };
+        Ok(DigestAttempt {
+            origin: authorize(&self.origins, AUTHORIZATION)?,
+            proxy: authorize(&self.proxies, PROXY_AUTHORIZATION)?,
+        })
+    }
+
+    /// Handles the response to an attempt at a request for `uri`, returning whether the request
+    /// should be sent again.
+    ///
+    /// A request is retried if it was challenged for credentials that had not been sent, or
+    /// whose nonce was stale. Otherwise, the `rspauth` in any `Authentication-Info` or
+    /// `Proxy-Authentication-Info` header is verified, except for `qop=auth-int` (which would
+    /// need the response body), and any `nextnonce` is recorded.
+    pub fn handle_response(
+        &self,
+        uri: &Uri,
+        attempt: &DigestAttempt,
+        status: StatusCode,
+        headers: &HeaderMap,
+    ) -> Result<bool, DigestClientError> {
+        match status {
+            StatusCode::UNAUTHORIZED => Ok(self.challenged(
+                &self.origins,
+                uri,
+                attempt.origin.is_some(),
+                headers.get_all(WWW_AUTHENTICATE),
+            )),
+            StatusCode::PROXY_AUTHENTICATION_REQUIRED => Ok(self.challenged(
+                &self.proxies,
+                uri,
+                attempt.proxy.is_some(),
+                headers.get_all(PROXY_AUTHENTICATE),
+            )),
+            _ => {
+                Self::verify(
+                    &self.origins,
+                    uri,
+                    &attempt.origin,
+                    headers.get_all(AUTHENTICATION_INFO),
+                )?;
+                Self::verify(
+                    &self.proxies,
+                    uri,
+                    &attempt.proxy,
+                    headers.get_all(PROXY_AUTHENTICATION_INFO),
+                )?;
+                Ok(false)
+            }
+        }
+    }
+
+    fn challenged(
+        &self,
+        cache: &Mutex<DigestCredentialCache>,
+        uri: &Uri,
+        sent: bool,
+        values: GetAll<'_, HeaderValue>,
+    ) -> bool {
+        let Some(challenge) = WwwAuthenticate::<DigestChallenge>::decode_all(values)
+            .ok()
+            .and_then(|challenges| challenges.into_iter().next())
+        else {
+            return false;
+        };
+        let stale = challenge.stale;
+        let realm = challenge.realm.clone();
+        let mut cache = lock(cache);
+        if cache.handle_challenge(uri, challenge) {
+            !sent || stale
+        } else {
+            cache.set_credentials(uri, &realm, &self.username, &self.password)
+        }
+    }
+
+    fn verify(
+        cache: &Mutex<DigestCredentialCache>,
+        uri: &Uri,
+        digest: &Option<Digest>,
+        values: GetAll<'_, HeaderValue>,
+    ) -> Result<(), DigestClientError> {
+        let (Some(digest), Ok(info)) = (digest, AuthenticationInfo::decode(&mut values.iter()))
+        else {
+            return Ok(());
+        };
+        let mut cache = lock(cache);
+        if digest.qop == Some(Qop::AuthInt) {
+            cache.handle_authentication_info(uri, &info);
+            Ok(())
+        } else if cache.verify_authentication_info(uri, digest, &info, b"") {
+            Ok(())
+        } else {
+            Err(DigestClientError::InvalidResponseAuth)
+        }
+    }
+}
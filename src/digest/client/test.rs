@@ -0,0 +1,196 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::DigestCredentialCache;
+use crate::authentication_info::{AuthenticationInfo, ResponseDigest};
+use crate::authenticator::{AuthRequest, AuthenticationError, Authenticator};
+use crate::challenge::Challenge;
+use crate::digest::nonce::NonceGenerator;
+use crate::digest::{DigestAuthenticator, DigestChallenge};
+use crate::store::htdigest::{Htdigest, HtdigestRecord};
+use crate::types::{HashAlgorithm, NonceCount, Qop};
+use headers::authorization::Credentials;
+use http::{Method, Uri};
+
+const REALM: &str = "testrealm@host.com";
+
+fn server(algorithm: HashAlgorithm) -> DigestAuthenticator<Htdigest> {
+    let mut store = Htdigest::new();
+    store.insert(HtdigestRecord::new(
+        "Mufasa",
+        REALM,
+        "Circle Of Life".to_owned(),
+        &algorithm.base(),
+    ));
+    DigestAuthenticator::new(REALM, store)
+        .with_algorithms([algorithm])
+        .with_nonces(NonceGenerator::with_key([7; 32]))
+}
+
+fn challenge(
+    server: &DigestAuthenticator<Htdigest>,
+    error: Option<&AuthenticationError>,
+) -> DigestChallenge {
+    let value = server.challenges(error).remove(0);
+    DigestChallenge::decode(value.to_str().expect("Invalid header value"))
+        .expect("Could not decode challenge")
+}
+
+fn send(
+    cache: &mut DigestCredentialCache,
+    server: &DigestAuthenticator<Htdigest>,
+    uri: &Uri,
+) -> Result<String, AuthenticationError> {
+    let digest = cache
+        .authorization(Method::GET, uri, b"")
+        .expect("No cached credentials")
+        .expect("Could not compute response");
+    let target = Uri::try_from(digest.request_uri.as_str()).expect("Invalid URI");
+    server.authenticate(&digest.encode(), &AuthRequest::new(&Method::GET, &target))
+}
+
+#[test]
+fn test_preemptive_authorization() {
+    for algorithm in [HashAlgorithm::Md5, HashAlgorithm::Sha256Session] {
+        let server = server(algorithm);
+        let mut cache = DigestCredentialCache::new();
+        let uri = Uri::from_static("http://www.example.com/dir/index.html");
+        assert!(cache.authorization(Method::GET, &uri, b"").is_none());
+
+        assert!(!cache.handle_challenge(&uri, challenge(&server, None)));
+        assert!(cache.set_credentials(&uri, REALM, "Mufasa", "Circle Of Life"));
+        assert_eq!(Ok("Mufasa".to_owned()), send(&mut cache, &server, &uri));
+
+        let other = Uri::from_static("http://WWW.example.com:80/other?page=2");
+        let digest = cache
+            .authorization(Method::GET, &other, b"")
+            .expect("No cached credentials")
+            .expect("Could not compute response");
+        assert_eq!(Some(NonceCount(2)), digest.nonce_count);
+        assert_eq!("/other?page=2", digest.request_uri);
+        assert_eq!(Ok("Mufasa".to_owned()), send(&mut cache, &server, &other));
+
+        let elsewhere = Uri::from_static("https://www.example.com/dir/index.html");
+        assert!(cache.authorization(Method::GET, &elsewhere, b"").is_none());
+    }
+}
+
+#[test]
+fn test_nonce_count_belongs_to_the_challenge() {
+    let server = server(HashAlgorithm::Md5);
+    let mut cache = DigestCredentialCache::new();
+    let uri = Uri::from_static("http://www.example.com/");
+    cache.set_credentials(&uri, REALM, "Mufasa", "Circle Of Life");
+    cache.handle_challenge(&uri, challenge(&server, None));
+    assert_eq!(Ok("Mufasa".to_owned()), send(&mut cache, &server, &uri));
+
+    // Replacing the credentials does not reuse nonce counts that were already sent.
+    cache.set_credentials(&uri, REALM, "Mufasa", "Circle Of Life");
+    assert_eq!(Ok("Mufasa".to_owned()), send(&mut cache, &server, &uri));
+}
+
+#[test]
+fn test_stale_challenge_and_next_nonce() {
+    let server = server(HashAlgorithm::Md5);
+    let mut cache = DigestCredentialCache::new();
+    let uri = Uri::from_static("http://www.example.com/");
+    cache.set_credentials(&uri, REALM, "Mufasa", "Circle Of Life");
+    let mut stale = challenge(&server, None);
+    stale.nonce = "expired".to_owned();
+    cache.handle_challenge(&uri, stale);
+    let error = send(&mut cache, &server, &uri).expect_err("Nonce should be stale");
+    assert_eq!(AuthenticationError::StaleNonce, error);
+
+    let challenge = challenge(&server, Some(&error));
+    assert!(challenge.stale);
+    assert!(cache.handle_challenge(&uri, challenge));
+    assert_eq!(Ok("Mufasa".to_owned()), send(&mut cache, &server, &uri));
+
+    let next_nonce = self::challenge(&server, None).nonce;
+    cache.handle_authentication_info(
+        &uri,
+        &AuthenticationInfo {
+            digest: None,
+            next_nonce: Some(next_nonce.clone()),
+            qop: Some(Qop::Auth),
+            client_nonce: None,
+            nonce_count: Some(NonceCount(2)),
+        },
+    );
+    let digest = cache
+        .authorization(Method::GET, &uri, b"")
+        .expect("No cached credentials")
+        .expect("Could not compute response");
+    assert_eq!(next_nonce, digest.nonce);
+    assert_eq!(Some(NonceCount(1)), digest.nonce_count);
+}
+
+#[test]
+fn test_userhash_and_exhausted_nonce_count() {
+    let server = server(HashAlgorithm::Sha256).with_userhash(true);
+    let mut cache = DigestCredentialCache::new();
+    let uri = Uri::from_static("http://www.example.com/");
+    cache.set_credentials(&uri, REALM, "Mufasa", "Circle Of Life");
+    cache.handle_challenge(&uri, challenge(&server, None));
+    assert_eq!(Ok("Mufasa".to_owned()), send(&mut cache, &server, &uri));
+
+    cache
+        .spaces
+        .find_mut(&uri)
+        .expect("No protection space found")
+        .value_mut()
+        .nonce_count = NonceCount(u32::MAX);
+    assert!(cache.authorization(Method::GET, &uri, b"").is_none());
+
+    cache.remove_credentials(&uri, REALM);
+    assert!(!cache.handle_challenge(&uri, challenge(&server, None)));
+}
+
+#[test]
+fn test_verify_authentication_info() {
+    let server = server(HashAlgorithm::Sha256Session);
+    let mut cache = DigestCredentialCache::new();
+    let uri = Uri::from_static("http://www.example.com/");
+    cache.set_credentials(&uri, REALM, "Mufasa", "Circle Of Life");
+    cache.handle_challenge(&uri, challenge(&server, None));
+    let digest = cache
+        .authorization(Method::GET, &uri, b"")
+        .expect("No cached credentials")
+        .expect("Could not compute response");
+    let mut info = server
+        .authentication_info(&digest, b"")
+        .expect("No Authentication-Info");
+    assert!(cache.verify_authentication_info(&uri, &digest, &info, b""));
+
+    info.digest = Some(ResponseDigest::ResponseAuth("0".repeat(64)));
+    assert!(!cache.verify_authentication_info(&uri, &digest, &info, b""));
+
+    info.digest = None;
+    info.next_nonce = Some("next".to_owned());
+    assert!(cache.verify_authentication_info(&uri, &digest, &info, b""));
+    assert_eq!(
+        Some("next"),
+        cache
+            .challenge(&uri)
+            .map(|challenge| challenge.nonce.as_str())
+    );
+}
@@ -0,0 +1,135 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Client-side `Digest` authentication as a `tower` layer.
+
+use super::{DigestClientAuth, DigestClientError};
+use bytes::Bytes;
+use http::{Request, Response};
+use http_body::Body;
+use http_body_util::{BodyExt, Full};
+use std::future::{Future, poll_fn};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// A `tower` layer that sends `Digest` credentials with requests, and retries requests that are
+/// challenged with `401 Unauthorized` or `407 Proxy Authentication Required`. See
+/// [`DigestClientAuth`] for the details.
+///
+/// Request bodies are buffered, so that they can be replayed; the wrapped service receives
+/// requests with a [`Full`] body. Request URIs must be absolute (as they are for `hyper`
+/// clients); otherwise, requests are sent once, without credentials.
+#[derive(Clone, Debug)]
+pub struct DigestAuthLayer {
+    auth: DigestClientAuth,
+}
+
+impl DigestAuthLayer {
+    /// Creates a layer that authenticates as the given user.
+    pub fn new(username: &str, password: &str) -> DigestAuthLayer {
+        Self::with_auth(DigestClientAuth::new(username, password))
+    }
+
+    /// Creates a layer that shares its credential caches with `auth`.
+    pub fn with_auth(auth: DigestClientAuth) -> DigestAuthLayer {
+        DigestAuthLayer { auth }
+    }
+}
+
+impl<S> Layer<S> for DigestAuthLayer {
+    type Service = DigestAuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DigestAuthService {
+            inner,
+            auth: self.auth.clone(),
+        }
+    }
+}
+
+/// The service created by [`DigestAuthLayer`].
+#[derive(Clone, Debug)]
+pub struct DigestAuthService<S> {
+    inner: S,
+    auth: DigestClientAuth,
+}
+
+impl<S, B, ResponseBody> Service<Request<B>> for DigestAuthService<S>
+where
+    S: Service<Request<Full<Bytes>>, Response = Response<ResponseBody>> + Clone + Send + 'static,
+    S::Error: Into<super::BoxError>,
+    S::Future: Send,
+    B: Body + Send + 'static,
+    B::Data: Send,
+    B::Error: Into<super::BoxError>,
+{
+    type Response = Response<ResponseBody>;
+    type Error = DigestClientError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner
+            .poll_ready(cx)
+            .map_err(|error| DigestClientError::Request(error.into()))
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        // The clone may not be ready, so use the service that was polled for the first attempt.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let auth = self.auth.clone();
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let body = body
+                .collect()
+                .await
+                .map_err(|error| DigestClientError::Body(error.into()))?
+                .to_bytes();
+
+            let mut attempts = 0;
+            loop {
+                attempts += 1;
+                let mut request = Request::from_parts(parts.clone(), Full::new(body.clone()));
+                let attempt =
+                    auth.authorize(&parts.method, &parts.uri, &body, request.headers_mut())?;
+                if attempts > 1 {
+                    poll_fn(|cx| inner.poll_ready(cx))
+                        .await
+                        .map_err(|error| DigestClientError::Request(error.into()))?;
+                }
+                let response = inner
+                    .call(request)
+                    .await
+                    .map_err(|error| DigestClientError::Request(error.into()))?;
+                let retry = auth.handle_response(
+                    &parts.uri,
+                    &attempt,
+                    response.status(),
+                    response.headers(),
+                )?;
+                if !retry || attempts >= DigestClientAuth::MAX_ATTEMPTS {
+                    return Ok(response);
+                }
+            }
+        })
+    }
+}
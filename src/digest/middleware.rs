@@ -0,0 +1,86 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Client-side `Digest` authentication as `reqwest` middleware.
+
+use super::DigestClientAuth;
+use http::{Extensions, Uri};
+use reqwest::{Request, Response};
+use reqwest_middleware::{Error, Middleware, Next, Result};
+
+/// Middleware for [`reqwest_middleware`] that sends `Digest` credentials with requests, and
+/// retries requests that are challenged with `401 Unauthorized` or
+/// `407 Proxy Authentication Required`. See [`DigestClientAuth`] for the details.
+///
+/// Requests whose bodies cannot be cloned (i.e. streams) are not retried.
+#[derive(Clone, Debug)]
+pub struct DigestAuthMiddleware {
+    auth: DigestClientAuth,
+}
+
+impl DigestAuthMiddleware {
+    /// Creates middleware that authenticates as the given user.
+    pub fn new(username: &str, password: &str) -> DigestAuthMiddleware {
+        Self::with_auth(DigestClientAuth::new(username, password))
+    }
+
+    /// Creates middleware that shares its credential caches with `auth`.
+    pub fn with_auth(auth: DigestClientAuth) -> DigestAuthMiddleware {
+        DigestAuthMiddleware { auth }
+    }
+}
+
+#[async_trait::async_trait]
+impl Middleware for DigestAuthMiddleware {
+    async fn handle(
+        &self,
+        mut request: Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> Result<Response> {
+        let uri: Uri = request.url().as_str().parse().map_err(Error::middleware)?;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let body = request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .unwrap_or_default()
+                .to_vec();
+            let method = request.method().clone();
+            let attempt = self
+                .auth
+                .authorize(&method, &uri, &body, request.headers_mut())
+                .map_err(Error::middleware)?;
+            let retry_request = request.try_clone();
+            let response = next.clone().run(request, extensions).await?;
+            let retry = self
+                .auth
+                .handle_response(&uri, &attempt, response.status(), response.headers())
+                .map_err(Error::middleware)?;
+            match retry_request {
+                Some(retry_request) if retry && attempts < DigestClientAuth::MAX_ATTEMPTS => {
+                    request = retry_request
+                }
+                _ => return Ok(response),
+            }
+        }
+    }
+}
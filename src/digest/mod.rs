@@ -1,4 +1,4 @@
-// Copyright (c) 2015, 2016, 2017, 2020, 2025 Mark Lee
+// Copyright (c) 2015, 2016, 2017, 2020, 2025, 2026 Mark Lee
 //
 // Permission is hereby granted, free of charge, to any person obtaining a copy
 // of this software and associated documentation files (the "Software"), to deal
@@ -21,7 +21,9 @@
 //! An HTTP Digest implementation for the [`headers`](https://docs.rs/headers) crate's `Authorization` header.
 
 use crate::parsing::fromheaders::{Charset, ExtendedValue};
-use crate::parsing::{DigestParameters, parse_parameters, unraveled_map_value};
+use crate::parsing::{
+    DigestParameters, parse_parameters, raw_map_value, to_header_value, unraveled_map_value,
+};
 use crate::types::{HashAlgorithm, NonceCount, Qop};
 use headers::authorization::Credentials;
 use headers::{Authorization, Error};
@@ -31,16 +33,54 @@ use std::fmt;
 use std::str::FromStr;
 use unicase::UniCase;
 
+mod authenticator;
+mod challenge;
+mod client;
+#[cfg(feature = "tower")]
+mod layer;
+#[cfg(feature = "reqwest")]
+mod middleware;
+pub mod nonce;
+mod protection_space;
+mod redacted;
+mod request_uri;
+mod session;
+mod streaming;
 mod test;
-mod test_helper;
+pub(crate) mod test_helper;
+mod trace;
+mod userhash_index;
+mod verification;
+
+pub use authenticator::DigestAuthenticator;
+pub use challenge::DigestChallenge;
+pub use client::{
+    BoxError, DigestAttempt, DigestClientAuth, DigestClientError, DigestCredentialCache,
+};
+#[cfg(feature = "tower")]
+pub use layer::{DigestAuthLayer, DigestAuthService};
+#[cfg(feature = "reqwest")]
+pub use middleware::DigestAuthMiddleware;
+pub use protection_space::{ProtectionSpace, ProtectionSpaces};
+pub use redacted::RedactedDigest;
+pub use session::SessionCache;
+pub use trace::ResponseTrace;
+pub use userhash_index::UserhashIndex;
+pub use verification::{PolicyViolation, Verification};
 
 /// Represents a `username` (or user hash, if the header's `userhash` parameter is `true`).
+///
+/// With the `serde` feature, it is serialized as the parameter that carries it in the header:
+/// `username` with the plain string, or `username*` with the RFC 5987 string.
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Username {
     /// Either an ASCII-encoded username, or a userhash (if the header's `userhash` parameter is
     /// `true`).
+    #[cfg_attr(feature = "serde", serde(rename = "username"))]
     Plain(String),
     /// An RFC 5987-encoded username.
+    #[cfg_attr(feature = "serde", serde(rename = "username*"))]
     Encoded(ExtendedValue),
 }
 
@@ -58,32 +98,61 @@ impl fmt::Display for Username {
 /// The parameters are described in more detail in
 /// [RFC 7616](https://tools.ietf.org/html/rfc7616#section-3.4).
 /// Unless otherwise noted, the parameter name maps to the struct variable name.
+///
+/// With the `serde` feature, the fields are serialized using their parameter names and values.
+///
+/// The `Debug` form includes the `response` and the nonces; use [`Digest::redacted`] for logging.
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Digest {
     /// Either the user name or the user hash (if `userhash` is `true` - see [RFC 7616, section
-    /// 3.4.4](https://tools.ietf.org/html/rfc7616#section-3.4.4)).
+    /// 3.4.4](https://tools.ietf.org/html/rfc7616#section-3.4.4)). Parameter name `username`,
+    /// or `username*` if it is RFC 5987-encoded.
+    #[cfg_attr(feature = "serde", serde(flatten))]
     pub username: Username,
     /// Authentication realm.
     pub realm: String,
     /// Cryptographic nonce.
     pub nonce: String,
     /// Nonce count, parameter name `nc`. Optional only in RFC 2067 mode.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "nc", default, skip_serializing_if = "Option::is_none")
+    )]
     pub nonce_count: Option<NonceCount>,
     /// The hexadecimal digest of the payload as described by the RFCs.
     pub response: String,
     /// Either the absolute path or URI of the HTTP request, parameter name `uri`.
+    #[cfg_attr(feature = "serde", serde(rename = "uri"))]
     pub request_uri: String,
     /// The hash algorithm to use when generating the `response`.
     pub algorithm: HashAlgorithm,
     /// Quality of protection. Optional only in RFC 2067 mode.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub qop: Option<Qop>,
     /// Cryptographic nonce from the client. Optional only in RFC 2067 mode.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "cnonce", default, skip_serializing_if = "Option::is_none")
+    )]
     pub client_nonce: Option<String>,
     /// Optional opaque string.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub opaque: Option<String>,
     /// The character set to use when generating the A1 value or the userhash. Added for RFC 7616.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
     pub charset: Option<Charset>,
     /// Whether `username` is a userhash. Added for RFC 7616.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub userhash: bool,
 }
 
@@ -99,15 +168,14 @@ macro_rules! ensure_ok {
 impl Credentials for Digest {
     const SCHEME: &'static str = "Digest";
 
+    /// Decodes the credentials, with or without the leading `Digest` scheme.
     fn decode(value: &HeaderValue) -> Option<Self> {
-        if let Ok(serialized) = value.to_str() {
-            match serialized.parse() {
-                Ok(digest) => Some(digest),
-                Err(_) => None,
-            }
-        } else {
-            None
-        }
+        let serialized = value.to_str().ok()?;
+        let parameters = match serialized.split_once(' ') {
+            Some((scheme, parameters)) if scheme.eq_ignore_ascii_case(Self::SCHEME) => parameters,
+            _ => serialized,
+        };
+        parameters.parse().ok()
     }
 
     fn encode(&self) -> headers::HeaderValue {
@@ -115,7 +183,7 @@ impl Credentials for Digest {
         match self.username {
             Username::Plain(ref username) => parameters.append("username", username, true),
             Username::Encoded(ref encoded) => {
-                parameters.append("username*", &encoded.to_string(), false)
+                parameters.append_raw("username*", &encoded.to_string(), false)
             }
         }
         parameters.append("realm", &self.realm, true);
@@ -124,7 +192,7 @@ impl Credentials for Digest {
             parameters.append("nc", &nonce_count.to_string(), false);
         }
         parameters.append("response", &self.response, true);
-        parameters.append("uri", &self.request_uri, true);
+        parameters.append_raw("uri", &self.request_uri, true);
         parameters.append("algorithm", &self.algorithm.to_string(), false);
         if let Some(ref qop) = self.qop {
             parameters.append("qop", &qop.to_string(), false);
@@ -141,23 +209,22 @@ impl Credentials for Digest {
         if self.userhash {
             parameters.append("userhash", "true", false);
         }
-        HeaderValue::from_str(&parameters.to_string())
-            .expect("Could not generate HeaderValue for Authorization")
+        to_header_value(&format!("{} {}", Self::SCHEME, parameters))
     }
 }
 
 fn parse_username(map: &HashMap<UniCase<String>, String>) -> Result<Username, Error> {
     if let Some(value) = unraveled_map_value(map, "username") {
-        if unraveled_map_value(map, "username*").is_some() {
+        if raw_map_value(map, "username*").is_some() {
             Err(Error::invalid())
         } else {
             Ok(Username::Plain(value))
         }
-    } else if let Some(encoded) = unraveled_map_value(map, "username*") {
-        if let Some(userhash) = unraveled_map_value(map, "userhash") {
-            if userhash == "true" {
-                return Err(Error::invalid());
-            }
+    } else if let Some(encoded) = raw_map_value(map, "username*") {
+        if let Some(userhash) = unraveled_map_value(map, "userhash")
+            && userhash == "true"
+        {
+            return Err(Error::invalid());
         }
 
         let extended_value = ensure_ok!(encoded.parse());
@@ -179,13 +246,17 @@ macro_rules! unravel_map_value {
 impl FromStr for Digest {
     type Err = Error;
     fn from_str(s: &str) -> Result<Digest, Error> {
-        let param_map = parse_parameters(s);
+        let param_map = parse_parameters(s)?;
         let username: Username = parse_username(&param_map)?;
         let realm: String = unravel_map_value!(param_map, "realm");
         let nonce: String = unravel_map_value!(param_map, "nonce");
         let nonce_count = ensure_ok!(NonceCount::from_parameters(&param_map));
         let response: String = unravel_map_value!(param_map, "response");
-        let request_uri: String = unravel_map_value!(param_map, "uri");
+        // The request URI is hashed exactly as it was sent, so it is not percent-decoded.
+        let request_uri: String = match raw_map_value(&param_map, "uri") {
+            Some(value) => value,
+            None => return Err(Error::invalid()),
+        };
         let algorithm: HashAlgorithm =
             if let Some(value) = unraveled_map_value(&param_map, "algorithm") {
                 ensure_ok!(HashAlgorithm::from_str(&value[..]))
@@ -242,7 +313,6 @@ impl Digest {
         let mut to_hash = username.clone();
         to_hash.push(b':');
         to_hash.append(&mut realm.into_bytes());
-        println!("value: {:?}", std::str::from_utf8(&to_hash));
         algorithm.hex_digest(to_hash.as_slice())
     }
 
@@ -251,6 +321,8 @@ impl Digest {
     /// `Digest` header.
     ///
     /// If userhash is `false`, returns `false`.
+    ///
+    /// To find the user that a userhash belongs to, use a [`UserhashIndex`].
     pub fn validate_userhash(&self, username: Username) -> bool {
         match self.username {
             Username::Plain(ref userhash) => {
@@ -294,6 +366,27 @@ impl Digest {
         algorithm.hex_digest(Digest::simple_a1(username, realm, password).as_slice())
     }
 
+    /// Converts the hexadecimal digest of a simple A1 value (see [`Digest::simple_hashed_a1`])
+    /// into the one for this header's algorithm. For the `-sess` algorithms, this incorporates the
+    /// server and client nonces, as defined in
+    /// [RFC 7616, section 3.4.2](https://tools.ietf.org/html/rfc7616#section-3.4.2); otherwise the
+    /// digest is returned unchanged.
+    pub fn session_hashed_a1(&self, simple_hashed_a1: &str) -> Result<String, Error> {
+        match self.algorithm {
+            HashAlgorithm::Md5 | HashAlgorithm::Sha256 | HashAlgorithm::Sha512256 => {
+                Ok(simple_hashed_a1.to_owned())
+            }
+            HashAlgorithm::Md5Session
+            | HashAlgorithm::Sha256Session
+            | HashAlgorithm::Sha512256Session => match self.client_nonce {
+                Some(ref client_nonce) => Ok(self.algorithm.hex_digest(
+                    format!("{}:{}:{}", simple_hashed_a1, self.nonce, client_nonce).as_bytes(),
+                )),
+                None => Err(Error::invalid()),
+            },
+        }
+    }
+
     // RFC 7616, Section 3.4.2
     fn a1(&self, username: Username, password: String) -> Result<Vec<u8>, Error> {
         let realm = self.realm.clone();
@@ -349,7 +442,31 @@ impl Digest {
             .hex_digest(self.a2(method, entity_body).as_bytes())
     }
 
-    fn kd(algorithm: &HashAlgorithm, secret: String, data: String) -> String {
+    // RFC 7616, Section 3.5: the same as A2, but without the method
+    fn response_auth_a2(&self, entity_body: &[u8]) -> String {
+        match self.qop {
+            Some(Qop::AuthInt) => format!(
+                ":{}:{}",
+                self.request_uri,
+                self.algorithm.hex_digest(entity_body)
+            ),
+            _ => format!(":{}", self.request_uri),
+        }
+    }
+
+    // The data passed to KD alongside the secret, RFC 7616, Section 3.4.1
+    fn kd_data(&self, hashed_a2: &str) -> Result<String, Error> {
+        match (&self.qop, &self.nonce_count, &self.client_nonce) {
+            (Some(qop), Some(nonce_count), Some(client_nonce)) => Ok(format!(
+                "{}:{}:{}:{}:{}",
+                self.nonce, nonce_count, client_nonce, qop, hashed_a2
+            )),
+            (Some(_), _, _) => Err(Error::invalid()),
+            (None, _, _) => Ok(format!("{}:{}", self.nonce, hashed_a2)),
+        }
+    }
+
+    pub(crate) fn kd(algorithm: &HashAlgorithm, secret: String, data: String) -> String {
         let value = format!("{}:{}", secret, data);
         algorithm.hex_digest(value.as_bytes())
     }
@@ -392,23 +509,22 @@ impl Digest {
         entity_body: &[u8],
         a1: String,
     ) -> Result<String, Error> {
-        let a2 = self.hashed_a2(method, entity_body);
-        let data: String;
-        if let Some(ref qop) = self.qop {
-            match *qop {
-                Qop::Auth | Qop::AuthInt => {
-                    if self.client_nonce.is_none() || self.nonce_count.is_none() {
-                        return Err(Error::invalid());
-                    }
-                    let nonce = self.nonce.clone();
-                    let nonce_count = self.nonce_count.clone().expect("No nonce count found");
-                    let client_nonce = self.client_nonce.clone().expect("No client nonce found");
-                    data = format!("{}:{}:{}:{}:{}", nonce, nonce_count, client_nonce, qop, a2);
-                }
-            }
-        } else {
-            data = format!("{}:{}", self.nonce, a2);
-        }
+        let data = self.kd_data(&self.hashed_a2(method, entity_body))?;
+        Ok(Digest::kd(&self.algorithm, a1, data))
+    }
+
+    /// Generates the `rspauth` value that the server sends back in the `Authentication-Info`
+    /// header, given the hexadecimal digest of an A1 string (as with
+    /// [`Digest::using_hashed_a1`]) and, for `qop=auth-int`, the body of the response.
+    ///
+    /// See [RFC 7616, section 3.5](https://tools.ietf.org/html/rfc7616#section-3.5).
+    pub fn response_auth_using_hashed_a1(
+        &self,
+        response_body: &[u8],
+        a1: String,
+    ) -> Result<String, Error> {
+        let a2 = self.response_auth_a2(response_body);
+        let data = self.kd_data(&self.algorithm.hex_digest(a2.as_bytes()))?;
         Ok(Digest::kd(&self.algorithm, a1, data))
     }
 
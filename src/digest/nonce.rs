@@ -0,0 +1,361 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Generation and validation of server nonces.
+
+use crate::types::NonceCount;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64URL;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use subtle::ConstantTimeEq;
+
+mod test;
+
+const TIMESTAMP_LENGTH: usize = 8;
+const RANDOM_LENGTH: usize = 8;
+const TAG_LENGTH: usize = 16;
+
+/// The result of validating a nonce.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum NonceValidity {
+    /// The nonce was generated by this server and has not expired.
+    Valid,
+    /// The nonce was generated by this server, but has expired. The client should retry with a
+    /// new nonce (i.e. the challenge should be sent with `stale=true`).
+    Stale,
+    /// The nonce was not generated by this server.
+    Invalid,
+    /// The nonce is valid, but the nonce count has already been used with it.
+    Replayed,
+}
+
+/// Checks the nonce and nonce count of a `Digest` response whose `response` value has already
+/// been verified. Used by the `Digest::verify_*` methods.
+///
+/// Closures of the form `Fn(&str, Option<&NonceCount>) -> NonceValidity` also implement this
+/// trait.
+pub trait NonceVerifier {
+    /// Checks `nonce` and `nonce_count` (absent for RFC 2069-style credentials). Implementations
+    /// that track nonce counts should record `nonce_count` as used.
+    fn verify_nonce(&self, nonce: &str, nonce_count: Option<&NonceCount>) -> NonceValidity;
+}
+
+impl<F> NonceVerifier for F
+where
+    F: Fn(&str, Option<&NonceCount>) -> NonceValidity,
+{
+    fn verify_nonce(&self, nonce: &str, nonce_count: Option<&NonceCount>) -> NonceValidity {
+        self(nonce, nonce_count)
+    }
+}
+
+/// Generates nonces that can be validated without storing them, as suggested by
+/// [RFC 7616, section 3.3](https://tools.ietf.org/html/rfc7616#section-3.3): each nonce is a
+/// timestamp and random bytes, followed by a keyed hash of both. The random bytes make each nonce
+/// unique, even when several are generated in the same millisecond.
+///
+/// Nonces are valid for 5 minutes by default. Nonces are not single-use; nonce counts are not
+/// tracked.
+#[derive(Clone)]
+pub struct NonceGenerator {
+    key: [u8; 32],
+    lifetime: Duration,
+}
+
+impl NonceGenerator {
+    /// Creates a generator with a random key. Nonces generated by one instance are invalid for
+    /// any other instance.
+    pub fn new() -> NonceGenerator {
+        Self::with_key(rand::random())
+    }
+
+    /// Creates a generator with a fixed key, e.g. one shared by several servers.
+    pub fn with_key(key: [u8; 32]) -> NonceGenerator {
+        NonceGenerator {
+            key,
+            lifetime: Duration::from_secs(300),
+        }
+    }
+
+    /// Sets how long nonces are valid for.
+    pub fn with_lifetime(mut self, lifetime: Duration) -> NonceGenerator {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// How long nonces are valid for.
+    pub fn lifetime(&self) -> Duration {
+        self.lifetime
+    }
+
+    fn tag(&self, data: &[u8]) -> [u8; TAG_LENGTH] {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(data);
+        let mut tag = [0; TAG_LENGTH];
+        tag.copy_from_slice(&mac.finalize().into_bytes()[..TAG_LENGTH]);
+        tag
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64)
+    }
+
+    /// Generates a nonce.
+    pub fn generate(&self) -> String {
+        let mut nonce = Self::now().to_be_bytes().to_vec();
+        nonce.extend_from_slice(&rand::random::<[u8; RANDOM_LENGTH]>());
+        let tag = self.tag(&nonce);
+        nonce.extend_from_slice(&tag);
+        BASE64URL.encode(nonce)
+    }
+
+    /// The time at which a nonce generated by this server was generated, in milliseconds since
+    /// the Unix epoch.
+    fn timestamp(&self, nonce: &str) -> Option<u64> {
+        let decoded = BASE64URL.decode(nonce).ok()?;
+        if decoded.len() != TIMESTAMP_LENGTH + RANDOM_LENGTH + TAG_LENGTH {
+            return None;
+        }
+        let (data, tag) = decoded.split_at(TIMESTAMP_LENGTH + RANDOM_LENGTH);
+        if !bool::from(self.tag(data).ct_eq(tag)) {
+            return None;
+        }
+
+        let mut millis = [0; TIMESTAMP_LENGTH];
+        millis.copy_from_slice(&data[..TIMESTAMP_LENGTH]);
+        Some(u64::from_be_bytes(millis))
+    }
+
+    /// How long ago a nonce generated by this server was generated, or `None` if it was not
+    /// generated by this server.
+    pub fn age(&self, nonce: &str) -> Option<Duration> {
+        self.timestamp(nonce)
+            .map(|timestamp| Duration::from_millis(Self::now().saturating_sub(timestamp)))
+    }
+
+    /// Validates a nonce.
+    pub fn validate(&self, nonce: &str) -> NonceValidity {
+        match self.age(nonce) {
+            None => NonceValidity::Invalid,
+            Some(age) if age < self.lifetime => NonceValidity::Valid,
+            Some(_) => NonceValidity::Stale,
+        }
+    }
+}
+
+impl Default for NonceGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Ignores the nonce count; see [`NonceCountTracker`] for replay protection.
+impl NonceVerifier for NonceGenerator {
+    fn verify_nonce(&self, nonce: &str, _nonce_count: Option<&NonceCount>) -> NonceValidity {
+        self.validate(nonce)
+    }
+}
+
+/// Validates nonces with a [`NonceGenerator`], and rejects nonce counts that have already been
+/// used with a nonce, as suggested by
+/// [RFC 7616, section 3.3](https://tools.ietf.org/html/rfc7616#section-3.3).
+///
+/// Nonce counts may arrive out of order (e.g. from pipelined requests), so every count is
+/// remembered until its nonce expires, rather than only the highest one. Credentials without a
+/// nonce count can only be used once per nonce.
+///
+/// A tracker can also decide when a client should switch to a new nonce before the current one
+/// expires, which is sent in the `nextnonce` parameter of the `Authentication-Info` header (see
+/// [`NonceCountTracker::next_nonce`]). By default, it never does.
+///
+/// Expired nonces are forgotten lazily, in the order that they were first used. At most 10,000
+/// nonces are tracked by default (see [`NonceCountTracker::with_max_nonces`]); when the limit is
+/// reached, the oldest nonce is forgotten, and from then on any nonce that is not being tracked
+/// and was generated no later than it is treated as stale, so that its counts cannot be replayed.
+pub struct NonceCountTracker {
+    generator: NonceGenerator,
+    seen: Mutex<TrackedNonces>,
+    max_nonces: usize,
+    next_nonce_uses: Option<usize>,
+    next_nonce_age: Option<Duration>,
+}
+
+#[derive(Default)]
+struct TrackedNonces {
+    counts: HashMap<String, HashSet<u32>>,
+    /// When each tracked nonce expires, in the order that they were first used.
+    order: VecDeque<(Instant, String)>,
+    /// The newest timestamp of a nonce that was forgotten before it expired.
+    forgotten_until: Option<u64>,
+}
+
+impl TrackedNonces {
+    /// Forgets the nonces that were first used earliest, up to the first one that has not
+    /// expired.
+    fn forget_expired(&mut self, now: Instant) {
+        while self
+            .order
+            .front()
+            .is_some_and(|(expires, _)| *expires <= now)
+        {
+            if let Some((_, nonce)) = self.order.pop_front() {
+                self.counts.remove(&nonce);
+            }
+        }
+    }
+}
+
+impl NonceCountTracker {
+    /// Creates a tracker for the nonces created by `generator`.
+    pub fn new(generator: NonceGenerator) -> NonceCountTracker {
+        NonceCountTracker {
+            generator,
+            seen: Mutex::new(TrackedNonces::default()),
+            max_nonces: 10_000,
+            next_nonce_uses: None,
+            next_nonce_age: None,
+        }
+    }
+
+    /// Sets the maximum number of nonces to track (at least 1).
+    pub fn with_max_nonces(mut self, max_nonces: usize) -> NonceCountTracker {
+        self.max_nonces = max_nonces.max(1);
+        self
+    }
+
+    /// Issues a `nextnonce` once a nonce has been used successfully `uses` times.
+    pub fn with_next_nonce_after_uses(mut self, uses: usize) -> NonceCountTracker {
+        self.next_nonce_uses = Some(uses);
+        self
+    }
+
+    /// Issues a `nextnonce` once a nonce is older than `age`. This should be shorter than the
+    /// generator's lifetime, so that clients switch nonces before being sent `stale=true`.
+    pub fn with_next_nonce_after(mut self, age: Duration) -> NonceCountTracker {
+        self.next_nonce_age = Some(age);
+        self
+    }
+
+    /// The generator used to create and validate nonces.
+    pub fn generator(&self) -> &NonceGenerator {
+        &self.generator
+    }
+
+    /// Generates a nonce.
+    pub fn generate(&self) -> String {
+        self.generator.generate()
+    }
+
+    /// Generates the nonce that the client should use from now on instead of `nonce`, if the
+    /// policy says that it has been used too often or for too long.
+    pub fn next_nonce(&self, nonce: &str) -> Option<String> {
+        let too_old = self
+            .next_nonce_age
+            .zip(self.generator.age(nonce))
+            .is_some_and(|(max_age, age)| age >= max_age);
+        let too_often = self.next_nonce_uses.is_some_and(|max_uses| {
+            let seen = self.seen.lock().unwrap_or_else(|error| error.into_inner());
+            seen.counts.get(nonce).map_or(0, HashSet::len) >= max_uses
+        });
+        (too_old || too_often).then(|| self.generate())
+    }
+}
+
+impl Default for NonceCountTracker {
+    fn default() -> Self {
+        Self::new(NonceGenerator::default())
+    }
+}
+
+impl NonceVerifier for NonceCountTracker {
+    fn verify_nonce(&self, nonce: &str, nonce_count: Option<&NonceCount>) -> NonceValidity {
+        let Some(timestamp) = self.generator.timestamp(nonce) else {
+            return NonceValidity::Invalid;
+        };
+        let age = Duration::from_millis(NonceGenerator::now().saturating_sub(timestamp));
+        let Some(expires_in) = self
+            .generator
+            .lifetime
+            .checked_sub(age)
+            .filter(|left| !left.is_zero())
+        else {
+            return NonceValidity::Stale;
+        };
+
+        let count = nonce_count.map_or(0, |nonce_count| nonce_count.0);
+        let now = Instant::now();
+        let mut seen = self.seen.lock().unwrap_or_else(|error| error.into_inner());
+        seen.forget_expired(now);
+        if !seen.counts.contains_key(nonce) {
+            if seen
+                .forgotten_until
+                .is_some_and(|forgotten_until| timestamp <= forgotten_until)
+            {
+                return NonceValidity::Stale;
+            }
+            seen.order.push_back((now + expires_in, nonce.to_owned()));
+            while seen.order.len() > self.max_nonces {
+                if let Some((_, oldest)) = seen.order.pop_front() {
+                    seen.counts.remove(&oldest);
+                    let forgotten = self.generator.timestamp(&oldest);
+                    seen.forgotten_until = seen.forgotten_until.max(forgotten);
+                }
+            }
+        }
+        if seen
+            .counts
+            .entry(nonce.to_owned())
+            .or_default()
+            .insert(count)
+        {
+            NonceValidity::Valid
+        } else {
+            NonceValidity::Replayed
+        }
+    }
+}
+
+/// The key is omitted.
+impl std::fmt::Debug for NonceGenerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NonceGenerator")
+            .field("lifetime", &self.lifetime)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The tracked nonces are omitted.
+impl std::fmt::Debug for NonceCountTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NonceCountTracker")
+            .field("generator", &self.generator)
+            .field("max_nonces", &self.max_nonces)
+            .field("next_nonce_uses", &self.next_nonce_uses)
+            .field("next_nonce_age", &self.next_nonce_age)
+            .finish_non_exhaustive()
+    }
+}
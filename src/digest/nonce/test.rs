@@ -0,0 +1,157 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::{NonceCountTracker, NonceGenerator, NonceValidity, NonceVerifier};
+use crate::types::NonceCount;
+use std::time::Duration;
+
+#[test]
+fn test_generated_nonce_is_valid() {
+    let generator = NonceGenerator::new();
+    let nonce = generator.generate();
+    assert_eq!(43, nonce.len());
+    assert_ne!(nonce, generator.generate());
+    assert_eq!(NonceValidity::Valid, generator.validate(&nonce))
+}
+
+#[test]
+fn test_nonce_from_another_key_is_invalid() {
+    let nonce = NonceGenerator::with_key([1; 32]).generate();
+    let generator = NonceGenerator::with_key([2; 32]);
+    assert_eq!(NonceValidity::Invalid, generator.validate(&nonce));
+    assert_eq!(NonceValidity::Invalid, generator.validate("not-a-nonce"));
+    assert_eq!(NonceValidity::Invalid, generator.validate(""));
+}
+
+#[test]
+fn test_expired_nonce_is_stale() {
+    let generator = NonceGenerator::with_key([1; 32]).with_lifetime(Duration::ZERO);
+    assert_eq!(
+        NonceValidity::Stale,
+        generator.validate(&generator.generate())
+    )
+}
+
+#[test]
+fn test_tracker_rejects_replayed_nonce_counts() {
+    let tracker = NonceCountTracker::new(NonceGenerator::with_key([1; 32]));
+    let nonce = tracker.generate();
+    for count in [1, 3, 2] {
+        assert_eq!(
+            NonceValidity::Valid,
+            tracker.verify_nonce(&nonce, Some(&NonceCount(count)))
+        );
+    }
+    assert_eq!(
+        NonceValidity::Replayed,
+        tracker.verify_nonce(&nonce, Some(&NonceCount(2)))
+    );
+    assert_eq!(NonceValidity::Valid, tracker.verify_nonce(&nonce, None));
+    assert_eq!(NonceValidity::Replayed, tracker.verify_nonce(&nonce, None));
+
+    let other = tracker.generate();
+    assert_eq!(
+        NonceValidity::Valid,
+        tracker.verify_nonce(&other, Some(&NonceCount(1)))
+    );
+    assert_eq!(
+        NonceValidity::Invalid,
+        tracker.verify_nonce("forged", Some(&NonceCount(1)))
+    );
+    assert!(!format!("{:?}", tracker).contains(&nonce));
+}
+
+#[test]
+fn test_tracker_forgets_expired_nonces() {
+    let tracker =
+        NonceCountTracker::new(NonceGenerator::with_key([1; 32]).with_lifetime(Duration::ZERO));
+    let nonce = tracker.generate();
+    assert_eq!(
+        NonceValidity::Stale,
+        tracker.verify_nonce(&nonce, Some(&NonceCount(1)))
+    );
+    assert!(
+        tracker
+            .seen
+            .lock()
+            .expect("Poisoned lock")
+            .counts
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_tracker_forgets_oldest_nonces() {
+    let tracker = NonceCountTracker::new(NonceGenerator::with_key([1; 32])).with_max_nonces(2);
+    let nonces = [tracker.generate(), tracker.generate(), tracker.generate()];
+    for nonce in &nonces {
+        assert_eq!(
+            NonceValidity::Valid,
+            tracker.verify_nonce(nonce, Some(&NonceCount(1)))
+        );
+    }
+    assert_eq!(2, tracker.seen.lock().expect("Poisoned lock").counts.len());
+    assert_eq!(
+        NonceValidity::Stale,
+        tracker.verify_nonce(&nonces[0], Some(&NonceCount(1)))
+    );
+    assert_eq!(
+        NonceValidity::Replayed,
+        tracker.verify_nonce(&nonces[1], Some(&NonceCount(1)))
+    );
+    assert_eq!(
+        NonceValidity::Valid,
+        tracker.verify_nonce(&nonces[2], Some(&NonceCount(2)))
+    );
+}
+
+#[test]
+fn test_next_nonce_after_uses() {
+    let tracker =
+        NonceCountTracker::new(NonceGenerator::with_key([1; 32])).with_next_nonce_after_uses(2);
+    let nonce = tracker.generate();
+    assert_eq!(None, tracker.next_nonce(&nonce));
+    tracker.verify_nonce(&nonce, Some(&NonceCount(1)));
+    assert_eq!(None, tracker.next_nonce(&nonce));
+    tracker.verify_nonce(&nonce, Some(&NonceCount(2)));
+    let next_nonce = tracker.next_nonce(&nonce).expect("No next nonce");
+    assert_ne!(nonce, next_nonce);
+    assert_eq!(
+        NonceValidity::Valid,
+        tracker.verify_nonce(&next_nonce, Some(&NonceCount(1)))
+    );
+}
+
+#[test]
+fn test_next_nonce_after_age() {
+    let generator = NonceGenerator::with_key([1; 32]);
+    let nonce = generator.generate();
+    assert!(generator.age(&nonce).is_some());
+    assert_eq!(None, generator.age("forged"));
+
+    let tracker = NonceCountTracker::new(generator.clone());
+    assert_eq!(None, tracker.next_nonce(&nonce));
+    let tracker = tracker.with_next_nonce_after(Duration::ZERO);
+    assert!(tracker.next_nonce(&nonce).is_some());
+    let tracker = NonceCountTracker::new(generator).with_next_nonce_after(Duration::from_secs(60));
+    assert_eq!(None, tracker.next_nonce(&nonce));
+}
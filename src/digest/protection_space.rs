@@ -0,0 +1,251 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Protection spaces, i.e. the sets of URIs that share a realm and credentials.
+
+use super::request_uri::{authorities_match, normalize_percent_encoding, remove_dot_segments};
+use super::{DigestAuthenticator, DigestChallenge};
+use crate::store::{CredentialStore, DigestRecord};
+use http::Uri;
+use std::borrow::Borrow;
+
+/// The normalized path and query of a URI, for prefix matching: percent-encoding is normalized,
+/// then dot segments are removed, so that e.g. `/public/../admin/` matches the prefix `/admin/`.
+fn target(uri: &Uri) -> Vec<u8> {
+    let mut target = match uri.path() {
+        "" => b"/".to_vec(),
+        path => remove_dot_segments(&normalize_percent_encoding(path)),
+    };
+    if let Some(query) = uri.query() {
+        target.push(b'?');
+        target.extend(normalize_percent_encoding(query));
+    }
+    target
+}
+
+fn same_origin(uri: &Uri, other: &Uri) -> bool {
+    match (uri.authority(), other.authority()) {
+        (Some(authority), Some(other_authority)) => {
+            uri.scheme() == other.scheme()
+                && authorities_match((uri.scheme(), authority), (other.scheme(), other_authority))
+        }
+        _ => true,
+    }
+}
+
+/// Resolves a URI from a `domain` parameter against the URI of the request that was challenged.
+fn resolve(request_uri: &Uri, reference: &str) -> Option<Uri> {
+    if !reference.starts_with('/') {
+        return reference
+            .parse::<Uri>()
+            .ok()
+            .filter(|uri| uri.scheme().is_some());
+    }
+    match (request_uri.scheme(), request_uri.authority()) {
+        (Some(scheme), Some(authority)) => Uri::builder()
+            .scheme(scheme.clone())
+            .authority(authority.clone())
+            .path_and_query(reference)
+            .build()
+            .ok(),
+        _ => reference.parse().ok(),
+    }
+}
+
+/// A set of URIs that share a realm and credentials, along with a value that describes how to
+/// authenticate within it.
+///
+/// The URIs are prefixes, as described for the `domain` parameter of the `Digest` challenge in
+/// [RFC 7616, section 3.3](https://tools.ietf.org/html/rfc7616#section-3.3). A protection space
+/// without any URIs contains every URI.
+#[derive(Clone, Debug)]
+pub struct ProtectionSpace<T> {
+    domain: Vec<Uri>,
+    value: T,
+}
+
+impl<T> ProtectionSpace<T> {
+    /// The URI prefixes of the protection space.
+    pub fn domain(&self) -> &[Uri] {
+        &self.domain
+    }
+
+    /// The value associated with the protection space.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// The value associated with the protection space, e.g. to update it.
+    pub fn value_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    /// Whether `uri` is in the protection space.
+    pub fn contains(&self, uri: &Uri) -> bool {
+        self.match_length(uri).is_some()
+    }
+
+    /// The length of the longest prefix of `uri` in the domain. Scheme and authority are only
+    /// compared when both URIs are in absolute-form.
+    fn match_length(&self, uri: &Uri) -> Option<usize> {
+        if self.domain.is_empty() {
+            return Some(0);
+        }
+        let uri_target = target(uri);
+        self.domain
+            .iter()
+            .filter(|prefix| same_origin(prefix, uri))
+            .map(target)
+            .filter(|prefix| uri_target.starts_with(prefix))
+            .map(|prefix| prefix.len())
+            .max()
+    }
+}
+
+/// A registry of protection spaces, which finds the one that a URI belongs to.
+///
+/// On the server side, it holds a [`DigestAuthenticator`] per protection space, and selects the
+/// one that issues challenges for, and verifies credentials sent to, a request. On the client
+/// side, it holds the last [`DigestChallenge`] received for each protection space, so that
+/// credentials can be sent preemptively to other URIs in the same space.
+#[derive(Clone, Debug)]
+pub struct ProtectionSpaces<T> {
+    spaces: Vec<ProtectionSpace<T>>,
+}
+
+impl<T> ProtectionSpaces<T> {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        ProtectionSpaces { spaces: vec![] }
+    }
+
+    /// Adds a protection space consisting of the URIs with the given prefixes (or of every URI,
+    /// if there are none).
+    pub fn insert<I: IntoIterator<Item = Uri>>(&mut self, domain: I, value: T) {
+        self.spaces.push(ProtectionSpace {
+            domain: domain.into_iter().collect(),
+            value,
+        })
+    }
+
+    /// Finds the protection space that `uri` belongs to. If several contain it, the one with the
+    /// longest matching prefix wins, then the one added first.
+    pub fn find(&self, uri: &Uri) -> Option<&ProtectionSpace<T>> {
+        self.position(uri).map(|index| &self.spaces[index])
+    }
+
+    /// Finds the protection space that `uri` belongs to, e.g. to update its value.
+    pub fn find_mut(&mut self, uri: &Uri) -> Option<&mut ProtectionSpace<T>> {
+        self.position(uri).map(|index| &mut self.spaces[index])
+    }
+
+    fn position(&self, uri: &Uri) -> Option<usize> {
+        let mut best: Option<(usize, usize)> = None;
+        for (index, space) in self.spaces.iter().enumerate() {
+            match (space.match_length(uri), best) {
+                (Some(length), Some((_, best_length))) if length <= best_length => {}
+                (Some(length), _) => best = Some((index, length)),
+                (None, _) => {}
+            }
+        }
+        best.map(|(index, _)| index)
+    }
+
+    /// Removes the protection spaces for which `predicate` returns `false`.
+    pub fn retain<F: FnMut(&ProtectionSpace<T>) -> bool>(&mut self, predicate: F) {
+        self.spaces.retain(predicate)
+    }
+
+    /// Iterates over the protection spaces, in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &ProtectionSpace<T>> {
+        self.spaces.iter()
+    }
+
+    /// The number of protection spaces.
+    pub fn len(&self) -> usize {
+        self.spaces.len()
+    }
+
+    /// Whether there are no protection spaces.
+    pub fn is_empty(&self) -> bool {
+        self.spaces.is_empty()
+    }
+}
+
+impl<T> Default for ProtectionSpaces<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> ProtectionSpaces<DigestAuthenticator<S>>
+where
+    S: CredentialStore,
+    S::Record: DigestRecord,
+{
+    /// Adds a protection space for the URIs in the authenticator's domain. URIs that cannot be
+    /// parsed are ignored.
+    pub fn register(&mut self, authenticator: DigestAuthenticator<S>) {
+        let domain: Vec<Uri> = authenticator
+            .domain()
+            .iter()
+            .filter_map(|uri| uri.parse().ok())
+            .collect();
+        self.insert(domain, authenticator)
+    }
+}
+
+impl<T: Borrow<DigestChallenge>> ProtectionSpaces<T> {
+    /// Records a challenge received in response to a request for `uri`. The protection space
+    /// consists of the URIs in its `domain` (resolved against `uri`), or of every URI on the
+    /// same origin as `uri` if there are none. Any previous challenge for the same realm on the
+    /// same origin is replaced.
+    ///
+    /// URIs in the `domain` on a different origin than `uri` are ignored, so that a server
+    /// cannot have credentials sent preemptively to another one. If the `domain` only lists such
+    /// URIs, the protection space consists of `uri` alone.
+    ///
+    /// The value may hold more than the challenge, e.g. the state of the client that received it.
+    pub fn insert_challenge(&mut self, uri: &Uri, value: T) {
+        let challenge: &DigestChallenge = value.borrow();
+        let mut domain: Vec<Uri> = challenge
+            .domain
+            .iter()
+            .filter_map(|reference| resolve(uri, reference))
+            .filter(|prefix| {
+                prefix.authority().is_none()
+                    || (uri.authority().is_some() && same_origin(prefix, uri))
+            })
+            .collect();
+        if domain.is_empty() {
+            if challenge.domain.is_empty() {
+                domain.extend(resolve(uri, "/"));
+            } else {
+                domain.push(uri.clone());
+            }
+        }
+        let realm = challenge.realm.clone();
+        self.retain(|space| {
+            space.value.borrow().realm != realm
+                || !space.domain.iter().any(|prefix| same_origin(prefix, uri))
+        });
+        self.insert(domain, value)
+    }
+}
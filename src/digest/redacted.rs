@@ -0,0 +1,112 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Representations of `Digest` credentials that are safe to log.
+
+use super::{Digest, Username};
+use headers::authorization::Credentials;
+use std::fmt;
+
+mod test;
+
+/// What a masked value is replaced with.
+const REDACTED: &str = "[redacted]";
+
+/// A view of `Digest` credentials that is safe to log. See [`Digest::redacted`].
+///
+/// It shows the scheme, realm, algorithm, `qop` and username, and masks the `response`, the
+/// nonces and any userhash. Its `Display` form resembles the header:
+///
+/// ```text
+/// Digest username="Mufasa", realm="testrealm@host.com", algorithm=MD5, qop=auth, nonce=[redacted], cnonce=[redacted], response=[redacted]
+/// ```
+#[derive(Clone, Copy)]
+pub struct RedactedDigest<'a>(&'a Digest);
+
+impl Digest {
+    /// A view of these credentials that is safe to log, e.g. with `Debug`, `Display` or (with the
+    /// `serde` feature) `Serialize`.
+    pub fn redacted(&self) -> RedactedDigest<'_> {
+        RedactedDigest(self)
+    }
+}
+
+impl fmt::Debug for RedactedDigest<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digest = self.0;
+        let mut debug = f.debug_struct("Digest");
+        if digest.userhash {
+            debug.field("userhash", &true);
+        } else {
+            debug.field("username", &digest.username);
+        }
+        debug
+            .field("realm", &digest.realm)
+            .field("algorithm", &digest.algorithm)
+            .field("qop", &digest.qop)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for RedactedDigest<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digest = self.0;
+        write!(f, "{} ", Digest::SCHEME)?;
+        match digest.username {
+            _ if digest.userhash => write!(f, "userhash=true")?,
+            Username::Plain(ref username) => write!(f, "username={:?}", username)?,
+            Username::Encoded(ref encoded) => write!(f, "username*={}", encoded)?,
+        }
+        write!(
+            f,
+            ", realm={:?}, algorithm={}",
+            digest.realm, digest.algorithm
+        )?;
+        if let Some(ref qop) = digest.qop {
+            write!(f, ", qop={}", qop)?;
+        }
+        write!(f, ", nonce={}", REDACTED)?;
+        if digest.client_nonce.is_some() {
+            write!(f, ", cnonce={}", REDACTED)?;
+        }
+        write!(f, ", response={}", REDACTED)
+    }
+}
+
+/// Serializes the credentials with every secret replaced.
+#[cfg(feature = "serde")]
+impl serde::Serialize for RedactedDigest<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let digest = self.0;
+        let mask = |value: &Option<String>| value.as_ref().map(|_| REDACTED.to_owned());
+        let masked = Digest {
+            username: if digest.userhash {
+                Username::Plain(REDACTED.to_owned())
+            } else {
+                digest.username.clone()
+            },
+            nonce: REDACTED.to_owned(),
+            response: REDACTED.to_owned(),
+            client_nonce: mask(&digest.client_nonce),
+            ..digest.clone()
+        };
+        masked.serialize(serializer)
+    }
+}
@@ -0,0 +1,78 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use crate::digest::Username;
+use crate::digest::test_helper::{rfc2617_digest_header, rfc7616_sha512_256_header};
+use crate::types::HashAlgorithm;
+
+const SECRETS: [&str; 3] = [
+    "dcd98b7102dd2f0e8b11d0f600bfb0c093",
+    "0a4f113b",
+    "6629fae49393a05397450978507c4ef1",
+];
+
+#[test]
+fn test_display() {
+    let digest = rfc2617_digest_header(HashAlgorithm::Md5);
+    let redacted = digest.redacted().to_string();
+    assert_eq!(
+        "Digest username=\"Mufasa\", realm=\"testrealm@host.com\", algorithm=MD5, qop=auth, \
+         nonce=[redacted], cnonce=[redacted], response=[redacted]",
+        redacted
+    );
+
+    let mut digest = digest;
+    digest.qop = None;
+    digest.client_nonce = None;
+    digest.username = Username::Plain("Muf\"asa\n".to_owned());
+    assert_eq!(
+        "Digest username=\"Muf\\\"asa\\n\", realm=\"testrealm@host.com\", algorithm=MD5, \
+         nonce=[redacted], response=[redacted]",
+        digest.redacted().to_string()
+    );
+}
+
+#[test]
+fn test_debug() {
+    let digest = rfc2617_digest_header(HashAlgorithm::Md5Session);
+    let redacted = format!("{:?}", digest.redacted());
+    assert!(redacted.contains("Mufasa"));
+    assert!(redacted.contains("Md5Session"));
+    for secret in SECRETS {
+        assert!(!redacted.contains(secret), "{} in {}", secret, redacted);
+    }
+}
+
+#[test]
+fn test_userhash_is_masked() {
+    let digest = rfc7616_sha512_256_header(
+        "793263caabb707a56211940d90411ea4a575adeccb7e360aeb624ed06ece9b0b".to_owned(),
+        true,
+    );
+    for redacted in [
+        digest.redacted().to_string(),
+        format!("{:?}", digest.redacted()),
+    ] {
+        assert!(redacted.contains("userhash"));
+        assert!(!redacted.contains("793263ca"), "{}", redacted);
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Matching the `uri` parameter of `Digest` credentials against the request target.
+
+use super::Digest;
+use http::uri::{Authority, Scheme};
+use http::{Method, Uri};
+
+/// Normalizes percent-encoding, as described in
+/// [RFC 3986, section 6.2.2](https://tools.ietf.org/html/rfc3986#section-6.2.2): unreserved
+/// characters are decoded, and the hexadecimal digits of other escapes are uppercased.
+pub(super) fn normalize_percent_encoding(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut normalized = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = bytes
+            .get(index + 1..index + 3)
+            .filter(|_| bytes[index] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) => {
+                normalized.push(byte);
+                index += 3;
+            }
+            Some(_) => {
+                normalized.extend(bytes[index..index + 3].iter().map(u8::to_ascii_uppercase));
+                index += 3;
+            }
+            None => {
+                normalized.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    normalized
+}
+
+/// Removes the `.` and `..` segments from a path, as described in
+/// [RFC 3986, section 5.2.4](https://tools.ietf.org/html/rfc3986#section-5.2.4).
+pub(super) fn remove_dot_segments(path: &[u8]) -> Vec<u8> {
+    let mut input = path;
+    let mut output = Vec::with_capacity(path.len());
+    let pop_segment = |output: &mut Vec<u8>| {
+        let end = output.iter().rposition(|byte| *byte == b'/').unwrap_or(0);
+        output.truncate(end);
+    };
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix(b"../") {
+            input = rest;
+        } else if let Some(rest) = input.strip_prefix(b"./") {
+            input = rest;
+        } else if input.starts_with(b"/./") {
+            input = &input[2..];
+        } else if input == b"/." {
+            input = b"/";
+        } else if input.starts_with(b"/../") {
+            input = &input[3..];
+            pop_segment(&mut output);
+        } else if input == b"/.." {
+            input = b"/";
+            pop_segment(&mut output);
+        } else if input == b"." || input == b".." {
+            input = b"";
+        } else {
+            let end = input
+                .iter()
+                .skip(1)
+                .position(|byte| *byte == b'/')
+                .map_or(input.len(), |position| position + 1);
+            output.extend_from_slice(&input[..end]);
+            input = &input[end..];
+        }
+    }
+    output
+}
+
+fn default_port(scheme: Option<&Scheme>) -> Option<u16> {
+    match scheme {
+        Some(scheme) if *scheme == Scheme::HTTP => Some(80),
+        Some(scheme) if *scheme == Scheme::HTTPS => Some(443),
+        _ => None,
+    }
+}
+
+pub(super) fn authorities_match(
+    (scheme, authority): (Option<&Scheme>, &Authority),
+    (other_scheme, other_authority): (Option<&Scheme>, &Authority),
+) -> bool {
+    authority
+        .host()
+        .eq_ignore_ascii_case(other_authority.host())
+        && authority.port_u16().or(default_port(scheme))
+            == other_authority.port_u16().or(default_port(other_scheme))
+}
+
+fn paths_match(uri: &Uri, other: &Uri) -> bool {
+    let path = |uri: &Uri| match uri.path() {
+        "" => b"/".to_vec(),
+        path => normalize_percent_encoding(path),
+    };
+    path(uri) == path(other)
+        && uri.query().map(normalize_percent_encoding)
+            == other.query().map(normalize_percent_encoding)
+}
+
+impl Digest {
+    /// Validates that `Digest.request_uri` designates the same resource as the request target,
+    /// as required by [RFC 7616, section 3.4.6](https://tools.ietf.org/html/rfc7616#section-3.4.6).
+    ///
+    /// Either may be in absolute-form or origin-form. When both are in absolute-form, the schemes
+    /// and authorities are compared. When only `Digest.request_uri` is, its authority is compared
+    /// with `host`, the value of the request's `Host` header; without one, the URIs do not match.
+    /// Hosts are compared case-insensitively, and default ports are implied. Paths and queries
+    /// are compared after normalizing percent-encoding. `CONNECT` requests are compared by
+    /// authority.
+    pub fn validate_request_uri(&self, method: &Method, uri: &Uri, host: Option<&str>) -> bool {
+        let Ok(digest_uri) = self.request_uri.parse::<Uri>() else {
+            return false;
+        };
+
+        if method == Method::CONNECT {
+            return match (digest_uri.authority(), uri.authority()) {
+                (Some(authority), Some(other)) => {
+                    authorities_match((digest_uri.scheme(), authority), (uri.scheme(), other))
+                }
+                _ => false,
+            };
+        }
+        match (digest_uri.authority(), uri.authority()) {
+            (Some(authority), Some(other)) => {
+                let schemes_match = match (digest_uri.scheme(), uri.scheme()) {
+                    (Some(scheme), Some(other)) => scheme == other,
+                    _ => false,
+                };
+                if !schemes_match
+                    || !authorities_match((digest_uri.scheme(), authority), (uri.scheme(), other))
+                {
+                    return false;
+                }
+            }
+            (Some(authority), None) => {
+                // The Host header has no scheme, so it is assumed to be the same as the URI's.
+                let Some(host) = host.and_then(|host| host.parse::<Authority>().ok()) else {
+                    return false;
+                };
+                if !authorities_match(
+                    (digest_uri.scheme(), authority),
+                    (digest_uri.scheme(), &host),
+                ) {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+        paths_match(&digest_uri, uri)
+    }
+}
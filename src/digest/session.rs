@@ -0,0 +1,198 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Remembering the session A1 values of the `-sess` algorithms.
+
+use super::Digest;
+use crate::types::HashAlgorithm;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+mod test;
+
+/// A bounded cache of session A1 values for the `-sess` algorithms.
+///
+/// With a `-sess` algorithm, A1 is `H(username:realm:password):nonce:cnonce`, which is fixed for
+/// as long as the client keeps using the same nonce and client nonce. As described in
+/// [RFC 2617, section 3.2.2.2](https://tools.ietf.org/html/rfc2617#section-3.2.2.2), this lets
+/// the server validate later requests in the same session without looking up the user's
+/// credentials again.
+///
+/// Sessions are forgotten once their nonce expires. When the cache is full, the oldest session
+/// is forgotten first.
+///
+/// Expired sessions are forgotten lazily, from the oldest, whenever a session is remembered.
+/// Nonces mostly expire in the order that their sessions started, so this rarely leaves an
+/// expired session behind a live one for long; either way, an expired session is never used.
+pub struct SessionCache {
+    capacity: usize,
+    sessions: Mutex<Sessions>,
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct SessionKey {
+    algorithm: HashAlgorithm,
+    nonce: String,
+    client_nonce: String,
+    username: String,
+    userhash: bool,
+}
+
+impl SessionKey {
+    fn new(digest: &Digest) -> Option<SessionKey> {
+        if digest.algorithm == digest.algorithm.base() {
+            return None;
+        }
+        Some(SessionKey {
+            algorithm: digest.algorithm.clone(),
+            nonce: digest.nonce.clone(),
+            client_nonce: digest.client_nonce.clone()?,
+            username: digest.username.to_string(),
+            userhash: digest.userhash,
+        })
+    }
+}
+
+/// The session A1 value of an authenticated user.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Session {
+    pub(super) hashed_a1: String,
+    pub(super) username: String,
+    expires: Instant,
+}
+
+#[derive(Debug, Default)]
+struct Sessions {
+    entries: HashMap<SessionKey, Session>,
+    order: VecDeque<SessionKey>,
+}
+
+impl Sessions {
+    /// Forgets the oldest sessions, up to the first one that has not expired.
+    fn forget_expired(&mut self, now: Instant) {
+        while let Some(key) = self.order.front() {
+            if self
+                .entries
+                .get(key)
+                .is_some_and(|session| session.expires > now)
+            {
+                break;
+            }
+            if let Some(key) = self.order.pop_front() {
+                self.entries.remove(&key);
+            }
+        }
+    }
+}
+
+impl SessionCache {
+    /// Creates a cache that remembers at most `capacity` sessions.
+    pub fn new(capacity: usize) -> SessionCache {
+        SessionCache {
+            capacity,
+            sessions: Mutex::new(Sessions::default()),
+        }
+    }
+
+    /// The maximum number of sessions.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of sessions, including any whose nonce has expired but that have not been
+    /// forgotten yet.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Whether there are no sessions.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Forgets every session, e.g. after a user's password has been changed.
+    pub fn clear(&self) {
+        let mut sessions = self.lock();
+        sessions.entries.clear();
+        sessions.order.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Sessions> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+
+    /// The session that `digest` belongs to, unless its nonce has expired.
+    pub(super) fn get(&self, digest: &Digest) -> Option<Session> {
+        let key = SessionKey::new(digest)?;
+        self.lock()
+            .entries
+            .get(&key)
+            .filter(|session| session.expires > Instant::now())
+            .cloned()
+    }
+
+    /// Remembers the session A1 value of the user that `digest` was authenticated as, until its
+    /// nonce expires in `expires_in`.
+    pub(super) fn insert(
+        &self,
+        digest: &Digest,
+        hashed_a1: String,
+        username: String,
+        expires_in: Duration,
+    ) {
+        let Some(key) = SessionKey::new(digest) else {
+            return;
+        };
+        if self.capacity == 0 {
+            return;
+        }
+        let now = Instant::now();
+        let session = Session {
+            hashed_a1,
+            username,
+            expires: now + expires_in,
+        };
+        let mut sessions = self.lock();
+        sessions.forget_expired(now);
+        if sessions.entries.insert(key.clone(), session).is_some() {
+            return;
+        }
+        sessions.order.push_back(key);
+        while sessions.order.len() > self.capacity {
+            if let Some(oldest) = sessions.order.pop_front() {
+                sessions.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// The sessions are omitted, since their A1 values are as good as passwords within them.
+impl fmt::Debug for SessionCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionCache")
+            .field("capacity", &self.capacity)
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
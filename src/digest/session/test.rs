@@ -0,0 +1,119 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::SessionCache;
+use crate::digest::test_helper::rfc2617_digest_header;
+use crate::types::HashAlgorithm;
+use std::time::Duration;
+
+const HASHED_A1: &str = "939e7578ed9e3c518a452acee763bce9";
+const LIFETIME: Duration = Duration::from_secs(300);
+
+#[test]
+fn test_only_remembers_session_algorithms() {
+    let cache = SessionCache::new(4);
+    let digest = rfc2617_digest_header(HashAlgorithm::Md5);
+    cache.insert(&digest, HASHED_A1.to_owned(), "Mufasa".to_owned(), LIFETIME);
+    assert!(cache.is_empty());
+    assert_eq!(None, cache.get(&digest));
+
+    let mut digest = rfc2617_digest_header(HashAlgorithm::Md5Session);
+    digest.client_nonce = None;
+    cache.insert(&digest, HASHED_A1.to_owned(), "Mufasa".to_owned(), LIFETIME);
+    assert!(cache.is_empty());
+}
+
+#[test]
+fn test_remembers_sessions_by_nonce_and_client_nonce() {
+    let cache = SessionCache::new(4);
+    let digest = rfc2617_digest_header(HashAlgorithm::Md5Session);
+    cache.insert(&digest, HASHED_A1.to_owned(), "Mufasa".to_owned(), LIFETIME);
+    let session = cache.get(&digest).expect("No session");
+    assert_eq!(HASHED_A1, session.hashed_a1);
+    assert_eq!("Mufasa", session.username);
+
+    let mut other = digest.clone();
+    other.client_nonce = Some("0a4f113c".to_owned());
+    assert_eq!(None, cache.get(&other));
+    let mut other = digest.clone();
+    other.nonce = "dcd98b7102dd2f0e8b11d0f600bfb0c094".to_owned();
+    assert_eq!(None, cache.get(&other));
+    let mut other = digest.clone();
+    other.algorithm = HashAlgorithm::Sha256Session;
+    assert_eq!(None, cache.get(&other));
+
+    let debug = format!("{:?}", cache);
+    assert!(!debug.contains(HASHED_A1), "{}", debug);
+    assert!(!debug.contains(&digest.nonce), "{}", debug);
+
+    cache.clear();
+    assert_eq!(None, cache.get(&digest));
+}
+
+#[test]
+fn test_forgets_expired_sessions() {
+    let cache = SessionCache::new(4);
+    let digest = rfc2617_digest_header(HashAlgorithm::Md5Session);
+    cache.insert(
+        &digest,
+        HASHED_A1.to_owned(),
+        "Mufasa".to_owned(),
+        Duration::ZERO,
+    );
+    assert_eq!(None, cache.get(&digest));
+    assert_eq!(1, cache.len());
+
+    let mut other = digest.clone();
+    other.client_nonce = Some("0a4f113c".to_owned());
+    cache.insert(&other, HASHED_A1.to_owned(), "Mufasa".to_owned(), LIFETIME);
+    assert_eq!(1, cache.len());
+    assert!(cache.get(&other).is_some());
+
+    // Sessions behind a live one are only forgotten once they reach the front.
+    cache.insert(
+        &digest,
+        HASHED_A1.to_owned(),
+        "Mufasa".to_owned(),
+        Duration::ZERO,
+    );
+    assert_eq!(2, cache.len());
+    assert_eq!(None, cache.get(&digest));
+}
+
+#[test]
+fn test_forgets_oldest_session_when_full() {
+    let cache = SessionCache::new(2);
+    let digests: Vec<_> = (0..3)
+        .map(|index| {
+            let mut digest = rfc2617_digest_header(HashAlgorithm::Md5Session);
+            digest.client_nonce = Some(format!("cnonce{}", index));
+            digest
+        })
+        .collect();
+    for digest in &digests {
+        cache.insert(digest, HASHED_A1.to_owned(), "Mufasa".to_owned(), LIFETIME);
+    }
+    assert_eq!(2, cache.len());
+    assert_eq!(None, cache.get(&digests[0]));
+    assert!(cache.get(&digests[1]).is_some());
+    assert!(cache.get(&digests[2]).is_some());
+}
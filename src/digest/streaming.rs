@@ -0,0 +1,162 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Validating `Digest` responses without allocating: values are fed directly into the hash
+//! function, and hexadecimal digests are written to buffers on the stack.
+
+use super::Digest;
+use crate::types::{HashAlgorithm, Qop};
+use digest::Digest as _;
+use http::Method;
+use subtle::ConstantTimeEq;
+
+/// Large enough for the hexadecimal digest of any supported algorithm.
+const MAX_HEX_LENGTH: usize = 64;
+
+type HexBuffer = [u8; MAX_HEX_LENGTH];
+
+/// The hash function of an algorithm, which is fed incrementally.
+enum Hasher {
+    Md5(md5::Md5),
+    Sha256(sha2::Sha256),
+    Sha512256(sha2::Sha512_256),
+}
+
+impl Hasher {
+    fn new(algorithm: &HashAlgorithm) -> Hasher {
+        match algorithm.base() {
+            HashAlgorithm::Sha256 => Hasher::Sha256(sha2::Sha256::new()),
+            HashAlgorithm::Sha512256 => Hasher::Sha512256(sha2::Sha512_256::new()),
+            _ => Hasher::Md5(md5::Md5::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) -> &mut Hasher {
+        match self {
+            Hasher::Md5(hasher) => hasher.update(data),
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::Sha512256(hasher) => hasher.update(data),
+        }
+        self
+    }
+
+    /// Feeds the values, separated by colons.
+    fn update_joined(&mut self, values: &[&[u8]]) -> &mut Hasher {
+        for (index, value) in values.iter().enumerate() {
+            if index > 0 {
+                self.update(b":");
+            }
+            self.update(value);
+        }
+        self
+    }
+
+    /// Writes the lowercase hexadecimal digest to `buffer`, returning the written part.
+    fn finalize_hex(self, buffer: &mut HexBuffer) -> &[u8] {
+        let encoded = match self {
+            Hasher::Md5(hasher) => base16ct::lower::encode(&hasher.finalize(), buffer),
+            Hasher::Sha256(hasher) => base16ct::lower::encode(&hasher.finalize(), buffer),
+            Hasher::Sha512256(hasher) => base16ct::lower::encode(&hasher.finalize(), buffer),
+        };
+        encoded.expect("The buffer fits any supported digest")
+    }
+}
+
+/// Writes the 8 lowercase hexadecimal digits of a nonce count.
+fn nonce_count_hex(value: u32, buffer: &mut [u8; 8]) -> &[u8] {
+    base16ct::lower::encode(&value.to_be_bytes(), buffer).expect("The buffer fits a nonce count")
+}
+
+impl Digest {
+    /// Validates a `Digest.response`, given an HTTP request and a hexadecimal digest of an A1
+    /// string. This is equivalent to [`Digest::validate_using_hashed_a1`], but does not allocate.
+    pub fn validate_response(&self, method: &Method, entity_body: &[u8], hashed_a1: &str) -> bool {
+        self.response_matches(method, entity_body, hashed_a1.as_bytes())
+    }
+
+    /// Validates a `Digest.response`, given an HTTP request and the hexadecimal digest of a
+    /// simple A1 value (see [`Digest::simple_hashed_a1`]), e.g. from an `htdigest` file. For the
+    /// `-sess` algorithms, the session A1 value is derived first (see
+    /// [`Digest::session_hashed_a1`]). This does not allocate.
+    pub fn validate_response_using_simple_hashed_a1(
+        &self,
+        method: &Method,
+        entity_body: &[u8],
+        simple_hashed_a1: &str,
+    ) -> bool {
+        if self.algorithm == self.algorithm.base() {
+            return self.response_matches(method, entity_body, simple_hashed_a1.as_bytes());
+        }
+        let Some(ref client_nonce) = self.client_nonce else {
+            return false;
+        };
+        let mut buffer = [0; MAX_HEX_LENGTH];
+        let mut hasher = Hasher::new(&self.algorithm);
+        hasher.update_joined(&[
+            simple_hashed_a1.as_bytes(),
+            self.nonce.as_bytes(),
+            client_nonce.as_bytes(),
+        ]);
+        let hashed_a1 = hasher.finalize_hex(&mut buffer);
+        self.response_matches(method, entity_body, hashed_a1)
+    }
+
+    fn response_matches(&self, method: &Method, entity_body: &[u8], hashed_a1: &[u8]) -> bool {
+        let mut body_buffer = [0; MAX_HEX_LENGTH];
+        let mut a2_buffer = [0; MAX_HEX_LENGTH];
+        let mut response_buffer = [0; MAX_HEX_LENGTH];
+        let mut nonce_count_buffer = [0; 8];
+
+        let mut hasher = Hasher::new(&self.algorithm);
+        hasher.update_joined(&[method.as_str().as_bytes(), self.request_uri.as_bytes()]);
+        if self.qop == Some(Qop::AuthInt) {
+            let mut body_hasher = Hasher::new(&self.algorithm);
+            body_hasher.update(entity_body);
+            hasher
+                .update(b":")
+                .update(body_hasher.finalize_hex(&mut body_buffer));
+        }
+        let hashed_a2 = hasher.finalize_hex(&mut a2_buffer);
+
+        let mut hasher = Hasher::new(&self.algorithm);
+        match (&self.qop, &self.nonce_count, &self.client_nonce) {
+            (Some(qop), Some(nonce_count), Some(client_nonce)) => {
+                let qop: &[u8] = match qop {
+                    Qop::Auth => b"auth",
+                    Qop::AuthInt => b"auth-int",
+                };
+                hasher.update_joined(&[
+                    hashed_a1,
+                    self.nonce.as_bytes(),
+                    nonce_count_hex(nonce_count.0, &mut nonce_count_buffer),
+                    client_nonce.as_bytes(),
+                    qop,
+                    hashed_a2,
+                ]);
+            }
+            (Some(_), _, _) => return false,
+            (None, _, _) => {
+                hasher.update_joined(&[hashed_a1, self.nonce.as_bytes(), hashed_a2]);
+            }
+        }
+        let response = hasher.finalize_hex(&mut response_buffer);
+        bool::from(response.ct_eq(self.response.as_bytes()))
+    }
+}
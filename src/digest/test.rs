@@ -1,4 +1,4 @@
-// Copyright (c) 2015, 2016, 2017, 2025 Mark Lee
+// Copyright (c) 2015, 2016, 2017, 2025, 2026 Mark Lee
 //
 // Permission is hereby granted, free of charge, to any person obtaining a copy
 // of this software and associated documentation files (the "Software"), to deal
@@ -19,14 +19,25 @@
 // THE SOFTWARE.
 
 #![cfg(test)]
+use super::nonce::NonceValidity;
+use super::request_uri::remove_dot_segments;
 use super::test_helper::{
     assert_header_parsing_error, assert_parsed_header_equal, assert_serialized_header_equal,
     parse_digest_header, rfc2069_a1_digest_header, rfc2069_a2_digest_header, rfc2069_username,
     rfc2617_digest_header, rfc7616_digest_header, rfc7616_sha512_256_header, rfc7616_username,
 };
-use super::{Digest, Username};
-use crate::types::{HashAlgorithm, Qop};
-use http::Method;
+use super::{
+    Digest, DigestAuthenticator, DigestChallenge, PolicyViolation, ProtectionSpaces, Username,
+    Verification,
+};
+use crate::authenticator::{AuthRequest, Authenticator};
+use crate::challenge::{Challenge, WwwAuthenticate};
+use crate::parsing::fromheaders::Charset;
+use crate::store::htdigest::{Htdigest, HtdigestRecord};
+use crate::types::{HashAlgorithm, NonceCount, Qop};
+use headers::authorization::Credentials;
+use headers::{Authorization, Header, HeaderMapExt, HeaderValue};
+use http::{Method, StatusCode, Uri};
 
 #[test]
 fn test_display_sha256_for_hashalgorithm() {
@@ -566,6 +577,96 @@ fn test_using_hashed_a1_sans_qop() -> Result<(), headers::Error> {
     Ok(())
 }
 
+#[test]
+fn test_validate_response_matches_using_hashed_a1() -> Result<(), headers::Error> {
+    let algorithms = [
+        HashAlgorithm::Md5,
+        HashAlgorithm::Md5Session,
+        HashAlgorithm::Sha256,
+        HashAlgorithm::Sha256Session,
+        HashAlgorithm::Sha512256,
+        HashAlgorithm::Sha512256Session,
+    ];
+    for algorithm in algorithms {
+        for qop in [None, Some(Qop::Auth), Some(Qop::AuthInt)] {
+            let mut digest = rfc2617_digest_header(algorithm.clone());
+            digest.qop = qop;
+            let simple_hashed_a1 = Digest::simple_hashed_a1(
+                &algorithm,
+                digest.username.clone(),
+                digest.realm.clone(),
+                "Circle Of Life".to_owned(),
+            );
+            let hashed_a1 = digest.session_hashed_a1(&simple_hashed_a1)?;
+            digest.response =
+                digest.using_hashed_a1(Method::POST, b"foo=bar", hashed_a1.clone())?;
+            assert!(digest.validate_response(&Method::POST, b"foo=bar", &hashed_a1));
+            assert!(digest.validate_response_using_simple_hashed_a1(
+                &Method::POST,
+                b"foo=bar",
+                &simple_hashed_a1
+            ));
+            assert!(!digest.validate_response(&Method::GET, b"foo=bar", &hashed_a1));
+            assert!(
+                !digest.validate_response_using_simple_hashed_a1(
+                    &Method::POST,
+                    b"foo=bar",
+                    &hashed_a1
+                ) || hashed_a1 == simple_hashed_a1
+            );
+            let expected_with_other_body = digest.qop != Some(Qop::AuthInt);
+            assert_eq!(
+                expected_with_other_body,
+                digest.validate_response(&Method::POST, b"foo=baz", &hashed_a1)
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_validate_response_sans_nonce_count() {
+    let mut digest = rfc2617_digest_header(HashAlgorithm::Md5);
+    digest.nonce_count = None;
+    assert!(!digest.validate_response(&Method::GET, b"", "939e7578ed9e3c518a452acee763bce9"));
+}
+
+#[test]
+fn test_validate_response_using_simple_hashed_a1_for_md5_sess_sans_client_nonce() {
+    let mut digest = rfc2617_digest_header(HashAlgorithm::Md5Session);
+    digest.qop = None;
+    digest.client_nonce = None;
+    assert!(!digest.validate_response_using_simple_hashed_a1(
+        &Method::GET,
+        b"",
+        "939e7578ed9e3c518a452acee763bce9"
+    ));
+}
+
+#[test]
+fn test_verify_using_simple_hashed_a1() {
+    let digest = rfc2617_digest_header(HashAlgorithm::Md5);
+    let valid = |_: &str, _: Option<&NonceCount>| NonceValidity::Valid;
+    assert_eq!(
+        Verification::Ok(digest.username.clone()),
+        digest.verify_using_simple_hashed_a1(
+            &Method::GET,
+            b"",
+            "939e7578ed9e3c518a452acee763bce9",
+            &valid
+        )
+    );
+    assert_eq!(
+        Verification::BadResponse,
+        digest.verify_using_simple_hashed_a1(
+            &Method::GET,
+            b"",
+            "00000000000000000000000000000000",
+            &valid
+        )
+    );
+}
+
 #[test]
 fn test_validate_using_password() {
     let password = "Circle of Life".to_owned();
@@ -661,3 +762,665 @@ fn test_validate_using_hashed_a1() {
     digest.client_nonce = Some("different".to_owned());
     assert!(!digest.validate_using_hashed_a1(Method::GET, b"", hashed_a1,));
 }
+
+fn accept_nonces(_nonce: &str, _nonce_count: Option<&NonceCount>) -> NonceValidity {
+    NonceValidity::Valid
+}
+
+#[test]
+fn test_verify_using_hashed_a1() {
+    let hashed_a1 = "3d78807defe7de2157e2b0b6573a855f".to_owned();
+    let mut digest = rfc7616_digest_header(HashAlgorithm::Md5, "8ca523f5e9506fed4657c9700eebdbec");
+    let verification =
+        digest.verify_using_hashed_a1(Method::GET, b"", hashed_a1.clone(), &accept_nonces);
+    assert_eq!(Verification::Ok(digest.username.clone()), verification);
+    assert_eq!(StatusCode::OK, verification.status_code());
+
+    for (validity, expected) in [
+        (NonceValidity::Stale, Verification::StaleNonce),
+        (NonceValidity::Invalid, Verification::StaleNonce),
+        (NonceValidity::Replayed, Verification::ReplayedNonceCount),
+    ] {
+        let nonces = |_: &str, _: Option<&NonceCount>| validity;
+        let verification =
+            digest.verify_using_hashed_a1(Method::GET, b"", hashed_a1.clone(), &nonces);
+        assert_eq!(StatusCode::UNAUTHORIZED, verification.status_code());
+        assert_eq!(expected, verification);
+    }
+
+    let nonces = |_: &str, _: Option<&NonceCount>| -> NonceValidity {
+        panic!("The nonce should not be checked for a bad response")
+    };
+    digest.client_nonce = Some("different".to_owned());
+    assert_eq!(
+        Verification::BadResponse,
+        digest.verify_using_hashed_a1(Method::GET, b"", hashed_a1, &nonces)
+    );
+}
+
+#[test]
+fn test_verify_with_missing_qop_parameters() {
+    let mut digest = rfc2617_digest_header(HashAlgorithm::Md5);
+    digest.nonce_count = None;
+    let verification = digest.verify_using_password(
+        Method::GET,
+        b"",
+        "Circle Of Life".to_owned(),
+        &accept_nonces,
+    );
+    assert_eq!(
+        Verification::PolicyViolation(PolicyViolation::MissingQopParameters),
+        verification
+    );
+    assert_eq!(StatusCode::BAD_REQUEST, verification.status_code());
+
+    let mut digest = rfc2617_digest_header(HashAlgorithm::Md5Session);
+    digest.qop = None;
+    digest.nonce_count = None;
+    digest.client_nonce = None;
+    assert_eq!(
+        Verification::PolicyViolation(PolicyViolation::MissingClientNonce),
+        digest.verify_using_password(
+            Method::GET,
+            b"",
+            "Circle Of Life".to_owned(),
+            &accept_nonces,
+        )
+    );
+}
+
+#[test]
+fn test_verify_using_userhash_and_password() {
+    let password = "Secret, or not?".to_owned();
+    let mut digest = parse_digest_header(
+        "Digest username=\"793263caabb707a56211940d90411ea4a575adeccb7e360aeb624ed06ece9b0b\", \
+                                      realm=\"api@example.org\", uri=\"/doe.json\", \
+                                      algorithm=SHA-512-256, \
+                                      nonce=\"5TsQWLVdgBdmrQ0XsxbDODV+57QdFR34I9HAbC/RVvkK\", \
+                                      nc=00000001, \
+                                      cnonce=\"NTg6RKcb9boFIAS3KrFK9BGeh+iDa/sm6jUMp2wds69v\", \
+                                      qop=auth, \
+                                      response=\"3798d4131c277846293534c3edc11bd8a5e4cdcbff78b05db9d95eeb1cec68a5\", \
+                                      opaque=\"HRPCssKJSGjCrkzDg8OhwpzCiGPChXYjwrI2QmXDnsOS\", \
+                                      charset=UTF-8, userhash=true",
+    );
+    assert_eq!(
+        Verification::Ok(rfc7616_username()),
+        digest.verify_using_userhash_and_password(
+            Method::GET,
+            b"",
+            rfc7616_username(),
+            password.clone(),
+            &accept_nonces,
+        )
+    );
+    assert_eq!(
+        Verification::BadResponse,
+        digest.verify_using_userhash_and_password(
+            Method::GET,
+            b"",
+            rfc7616_username(),
+            "Secret".to_owned(),
+            &accept_nonces,
+        )
+    );
+
+    digest.username = Username::Plain("invalid".to_owned());
+    assert_eq!(
+        Verification::UnknownUser,
+        digest.verify_using_userhash_and_password(
+            Method::GET,
+            b"",
+            rfc7616_username(),
+            password,
+            &accept_nonces,
+        )
+    );
+}
+
+fn validate_request_uri(digest_uri: &str, method: Method, uri: &str) -> bool {
+    validate_request_uri_with_host(digest_uri, method, uri, None)
+}
+
+fn validate_request_uri_with_host(
+    digest_uri: &str,
+    method: Method,
+    uri: &str,
+    host: Option<&str>,
+) -> bool {
+    let mut digest = rfc2617_digest_header(HashAlgorithm::Md5);
+    digest.request_uri = digest_uri.to_owned();
+    digest.validate_request_uri(&method, &uri.parse().expect("Invalid URI"), host)
+}
+
+#[test]
+fn test_validate_request_uri() {
+    for (digest_uri, uri) in [
+        ("/dir/index.html", "/dir/index.html"),
+        ("/dir/index.html?a=b", "/dir/index.html?a=b"),
+        ("/dir/index.html", "http://www.example.com/dir/index.html"),
+        (
+            "http://WWW.Example.com:80/dir/index.html",
+            "http://www.example.com/dir/index.html",
+        ),
+        ("https://www.example.com", "https://www.example.com:443/"),
+        ("/%7Euser/a%2fb", "/~user/a%2Fb"),
+        ("/dir?q=%41", "/dir?q=A"),
+        ("*", "*"),
+    ] {
+        assert!(
+            validate_request_uri(digest_uri, Method::GET, uri),
+            "{} should match {}",
+            digest_uri,
+            uri
+        );
+    }
+}
+
+#[test]
+fn test_validate_request_uri_mismatch() {
+    for (digest_uri, uri) in [
+        ("/public", "/admin"),
+        ("/dir/index.html", "/dir/index.html?a=b"),
+        ("/dir/index.html?a=b", "/dir/index.html?a=c"),
+        ("/a%2Fb", "/a/b"),
+        (
+            "http://www.example.com/dir/index.html",
+            "https://www.example.com/dir/index.html",
+        ),
+        (
+            "http://www.example.com/dir/index.html",
+            "http://www.example.org/dir/index.html",
+        ),
+        (
+            "http://www.example.com:8080/dir/index.html",
+            "http://www.example.com/dir/index.html",
+        ),
+        ("not a uri", "/"),
+    ] {
+        assert!(
+            !validate_request_uri(digest_uri, Method::GET, uri),
+            "{} should not match {}",
+            digest_uri,
+            uri
+        );
+    }
+}
+
+#[test]
+fn test_validate_request_uri_with_host() {
+    for (digest_uri, host, expected) in [
+        ("http://www.example.com/dir/index.html", None, false),
+        (
+            "http://www.example.com/dir/index.html",
+            Some("www.example.com"),
+            true,
+        ),
+        (
+            "http://www.example.com/dir/index.html",
+            Some("WWW.example.com:80"),
+            true,
+        ),
+        (
+            "https://www.example.com/dir/index.html",
+            Some("www.example.com:443"),
+            true,
+        ),
+        (
+            "http://www.example.com/dir/index.html",
+            Some("www.example.org"),
+            false,
+        ),
+        (
+            "http://www.example.com/dir/index.html",
+            Some("www.example.com:8080"),
+            false,
+        ),
+        (
+            "http://www.example.com/dir/index.html",
+            Some("not a host"),
+            false,
+        ),
+        ("/dir/index.html", None, true),
+    ] {
+        assert_eq!(
+            expected,
+            validate_request_uri_with_host(digest_uri, Method::GET, "/dir/index.html", host),
+            "{} with host {:?}",
+            digest_uri,
+            host
+        );
+    }
+}
+
+#[test]
+fn test_validate_request_uri_for_connect() {
+    assert!(validate_request_uri(
+        "www.example.com:443",
+        Method::CONNECT,
+        "WWW.example.com:443"
+    ));
+    assert!(!validate_request_uri(
+        "www.example.com:443",
+        Method::CONNECT,
+        "www.example.com:8443"
+    ));
+    assert!(!validate_request_uri(
+        "/",
+        Method::CONNECT,
+        "www.example.com:443"
+    ));
+}
+
+#[test]
+fn test_decode_typed_header_with_scheme() {
+    let mut headers = headers::HeaderMap::new();
+    headers.insert(
+        http::header::AUTHORIZATION,
+        HeaderValue::from_static(
+            "digest username=\"Mufasa\", realm=\"testrealm@host.com\", \
+             nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", uri=\"/dir/index.html\", \
+             response=\"1949323746fe6a43ef61f9606e7febea\"",
+        ),
+    );
+    let header: Option<Authorization<Digest>> = headers.typed_get();
+    assert_eq!(Some(rfc2069_a1_digest_header()), header.map(|h| h.0));
+}
+
+#[test]
+fn test_decode_with_parameter_without_value() {
+    let value = HeaderValue::from_static("Digest username=\"Mufasa\", realm");
+    assert!(Digest::decode(&value).is_none());
+}
+
+#[test]
+fn test_decode_with_opaque_bytes() {
+    let value = HeaderValue::from_bytes(b"Digest username=\"\xff\"").expect("Invalid header");
+    assert!(Digest::decode(&value).is_none());
+}
+
+#[test]
+fn test_encode_with_control_characters() {
+    let mut digest = rfc2069_a1_digest_header();
+    digest.realm = "test\r\nrealm".to_owned();
+    let value = digest.encode();
+    assert!(
+        value
+            .to_str()
+            .expect("Invalid header value")
+            .contains("realm=\"test%0D%0Arealm\"")
+    );
+    assert_eq!(Some(digest), Digest::decode(&value));
+}
+
+#[test]
+fn test_encode_and_decode_with_percent_signs() {
+    let mut digest = rfc2617_digest_header(HashAlgorithm::Md5);
+    digest.username = Username::Plain("Mu%66asa".to_owned());
+    digest.request_uri = "/dir/index%2Ehtml".to_owned();
+    let value = digest.encode();
+    let serialized = value.to_str().expect("Invalid header value");
+    assert!(serialized.contains("username=\"Mu%2566asa\""));
+    assert!(serialized.contains("uri=\"/dir/index%2Ehtml\""));
+    assert_eq!(Some(digest), Digest::decode(&value));
+}
+
+#[test]
+fn test_encode_and_decode_with_quoted_pairs() {
+    let mut digest = rfc2617_digest_header(HashAlgorithm::Md5);
+    digest.username = Username::Plain("\"Mufasa\", the \\king\\".to_owned());
+    digest.realm = "testrealm@host.com, or not".to_owned();
+    let value = digest.encode();
+    assert!(
+        value
+            .to_str()
+            .expect("Invalid header value")
+            .starts_with("Digest username=\"\\\"Mufasa\\\", the \\\\king\\\\\", ")
+    );
+    assert_eq!(Some(digest), Digest::decode(&value));
+}
+
+#[test]
+fn test_parse_header_with_percent_encoded_username() {
+    let mut expected = rfc2617_digest_header(HashAlgorithm::Md5);
+    expected.username = Username::Plain("Mufasa".to_owned());
+    assert_parsed_header_equal(
+        expected,
+        "Digest username=\"Mu%66asa\", realm=\"testrealm@host.com\", \
+                                nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", \
+                                uri=\"/dir/index.html\", qop=auth, nc=00000001, \
+                                cnonce=\"0a4f113b\", \
+                                response=\"6629fae49393a05397450978507c4ef1\", \
+                                opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"",
+    )
+}
+
+#[test]
+fn test_parse_header_with_duplicate_parameter() {
+    assert_header_parsing_error(
+        "Digest username=\"Mufasa\", username=\"Scar\", realm=\"testrealm@host.com\", \
+                                 nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", \
+                                 uri=\"/dir/index.html\", \
+                                 response=\"6629fae49393a05397450978507c4ef1\"",
+    )
+}
+
+#[test]
+fn test_decode_with_unterminated_quoted_string() {
+    let value = HeaderValue::from_static("Digest username=\"Mufasa, realm=\"testrealm@host.com");
+    assert!(Digest::decode(&value).is_none());
+}
+
+#[test]
+fn test_decode_rfc7616_challenge() {
+    let value = HeaderValue::from_static(
+        "Digest realm=\"http-auth@example.org\", qop=\"auth, auth-int\", algorithm=SHA-256, \
+         nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", \
+         opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\"",
+    );
+    let challenge = WwwAuthenticate::<DigestChallenge>::decode(&mut std::iter::once(&value))
+        .expect("Could not decode challenge")
+        .0;
+    assert_eq!(
+        DigestChallenge {
+            realm: "http-auth@example.org".to_owned(),
+            domain: vec![],
+            nonce: "7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v".to_owned(),
+            opaque: Some("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS".to_owned()),
+            stale: false,
+            algorithm: HashAlgorithm::Sha256,
+            qop: vec![Qop::Auth, Qop::AuthInt],
+            charset: None,
+            userhash: false,
+        },
+        challenge
+    );
+}
+
+#[test]
+fn test_decode_challenge_ignores_unknown_qop() {
+    let challenge = DigestChallenge::decode(
+        "digest realm=\"a\", nonce=\"b\", qop=\"auth-conf,auth\", stale=TRUE, userhash=true",
+    )
+    .expect("Could not decode challenge");
+    assert_eq!(vec![Qop::Auth], challenge.qop);
+    assert_eq!(HashAlgorithm::Md5, challenge.algorithm);
+    assert!(challenge.stale);
+    assert!(challenge.userhash);
+    assert!(DigestChallenge::decode("Digest realm=\"a\"").is_none());
+    assert!(DigestChallenge::decode("Digest realm=\"a\", nonce=\"b\", algorithm=SHA-1").is_none());
+}
+
+#[test]
+fn test_encode_challenge() {
+    let challenge = DigestChallenge {
+        realm: "http-auth@example.org".to_owned(),
+        domain: vec!["/a".to_owned(), "/b".to_owned()],
+        nonce: "7ypf".to_owned(),
+        opaque: None,
+        stale: true,
+        algorithm: HashAlgorithm::Sha512256,
+        qop: vec![Qop::Auth, Qop::AuthInt],
+        charset: Some(Charset::UTF_8),
+        userhash: true,
+    };
+    let value = challenge.encode();
+    assert_eq!(
+        "Digest realm=\"http-auth@example.org\", domain=\"/a /b\", nonce=\"7ypf\", stale=true, \
+         algorithm=SHA-512-256, qop=\"auth, auth-int\", charset=UTF-8, userhash=true",
+        value.to_str().expect("Invalid header value")
+    );
+    assert_eq!(
+        Some(challenge),
+        DigestChallenge::decode(value.to_str().expect("Invalid header value"))
+    );
+}
+
+#[test]
+fn test_trace_using_password() {
+    let digest = rfc2617_digest_header(HashAlgorithm::Md5);
+    let trace = digest
+        .trace_using_password(Method::GET, b"", "Circle Of Life".to_owned())
+        .expect("Could not trace response");
+    assert_eq!(
+        Some("Mufasa:testrealm@host.com:Circle Of Life"),
+        trace.a1.as_deref()
+    );
+    assert_eq!("939e7578ed9e3c518a452acee763bce9", trace.hashed_a1);
+    assert_eq!("GET:/dir/index.html", trace.a2);
+    assert_eq!("39aff3a2bab6126f332b942af96d3366", trace.hashed_a2);
+    assert_eq!(
+        "939e7578ed9e3c518a452acee763bce9:dcd98b7102dd2f0e8b11d0f600bfb0c093:00000001:0a4f113b:\
+         auth:39aff3a2bab6126f332b942af96d3366",
+        trace.kd_input
+    );
+    assert!(trace.matches(&digest));
+    assert!(
+        !digest
+            .trace_using_password(Method::POST, b"", "Circle Of Life".to_owned())
+            .expect("Could not trace response")
+            .matches(&digest)
+    );
+}
+
+#[test]
+fn test_trace_using_simple_hashed_a1() {
+    let digest = rfc2617_digest_header(HashAlgorithm::Md5);
+    let trace = digest
+        .trace_using_simple_hashed_a1(Method::GET, b"", "939e7578ed9e3c518a452acee763bce9")
+        .expect("Could not trace response");
+    assert_eq!(None, trace.a1);
+    assert!(trace.matches(&digest));
+
+    let mut digest = rfc2617_digest_header(HashAlgorithm::Md5Session);
+    let from_password = digest
+        .trace_using_password(Method::GET, b"", "Circle Of Life".to_owned())
+        .expect("Could not trace response");
+    let trace = digest
+        .trace_using_simple_hashed_a1(Method::GET, b"", "939e7578ed9e3c518a452acee763bce9")
+        .expect("Could not trace response");
+    assert_eq!(from_password, trace);
+    assert_eq!(
+        Some(
+            "939e7578ed9e3c518a452acee763bce9:dcd98b7102dd2f0e8b11d0f600bfb0c093:0a4f113b"
+                .to_owned()
+        ),
+        trace.a1
+    );
+
+    digest.client_nonce = None;
+    assert!(
+        digest
+            .trace_using_simple_hashed_a1(Method::GET, b"", "939e7578ed9e3c518a452acee763bce9")
+            .is_err()
+    );
+}
+
+#[test]
+fn test_response_auth_using_hashed_a1() {
+    let digest = rfc2617_digest_header(HashAlgorithm::Md5);
+    assert_eq!(
+        Ok("376602cfd2f4e8e5e78b948a85263e85".to_owned()),
+        digest
+            .response_auth_using_hashed_a1(b"", "939e7578ed9e3c518a452acee763bce9".to_owned())
+            .map_err(|_| ())
+    );
+}
+
+#[test]
+fn test_decode_all_challenges() {
+    let values = [
+        HeaderValue::from_static(
+            "Digest realm=\"a\", nonce=\"b\", algorithm=SHA-256, Basic realm=\"a\"",
+        ),
+        HeaderValue::from_static(
+            "Digest realm=\"a\", algorithm=SHA-256, Digest realm=\"a\", nonce=\"b\"",
+        ),
+    ];
+    let challenges = WwwAuthenticate::<DigestChallenge>::decode_all(&values)
+        .expect("Could not decode challenges");
+    assert_eq!(
+        vec![HashAlgorithm::Sha256, HashAlgorithm::Md5],
+        challenges
+            .into_iter()
+            .map(|challenge| challenge.algorithm)
+            .collect::<Vec<_>>()
+    );
+}
+
+fn protected_authenticator(realm: &str, domain: &[&str]) -> DigestAuthenticator<Htdigest> {
+    let mut store = Htdigest::new();
+    store.insert(HtdigestRecord::new(
+        "Mufasa",
+        realm,
+        "Circle Of Life".to_owned(),
+        &HashAlgorithm::Md5,
+    ));
+    DigestAuthenticator::new(realm, store).with_domain(domain.iter().map(|uri| uri.to_string()))
+}
+
+#[test]
+fn test_server_protection_spaces() {
+    let mut spaces = ProtectionSpaces::new();
+    spaces.register(protected_authenticator("public@example.com", &[]));
+    spaces.register(protected_authenticator(
+        "admin@example.com",
+        &["/admin/", "/%7Eroot/"],
+    ));
+    assert_eq!(2, spaces.len());
+
+    for (uri, realm) in [
+        ("/admin/users", "admin@example.com"),
+        ("http://example.com/admin/", "admin@example.com"),
+        ("/~root/", "admin@example.com"),
+        ("/admin", "public@example.com"),
+        ("/index.html", "public@example.com"),
+    ] {
+        let uri: Uri = uri.parse().expect("Invalid URI");
+        let space = spaces.find(&uri).expect("No protection space found");
+        assert_eq!(
+            realm,
+            space.value().digest_challenges(false)[0].realm,
+            "{}",
+            uri
+        );
+    }
+
+    let uri = Uri::from_static("/admin/users");
+    let authenticator = spaces
+        .find(&uri)
+        .expect("No protection space found")
+        .value();
+    let challenge = DigestChallenge::decode(
+        authenticator.challenges(None)[0]
+            .to_str()
+            .expect("Invalid value"),
+    )
+    .expect("Could not decode challenge");
+    assert_eq!(vec!["/admin/", "/%7Eroot/"], challenge.domain);
+    let digest = super::test_helper::respond_to_challenge(
+        &challenge,
+        "Mufasa",
+        "Circle Of Life",
+        Method::GET,
+        "/admin/users",
+    );
+    assert_eq!(
+        Ok("Mufasa".to_owned()),
+        authenticator.authenticate(&digest.encode(), &AuthRequest::new(&Method::GET, &uri))
+    );
+}
+
+#[test]
+fn test_remove_dot_segments() {
+    for (path, expected) in [
+        ("/a/b/c/./../../g", "/a/g"),
+        ("mid/content=5/../6", "mid/6"),
+        ("/a/b/..", "/a/"),
+        ("/a/./b/.", "/a/b/"),
+        ("/../a", "/a"),
+        ("../a/./", "a/"),
+        ("/a//b", "/a//b"),
+    ] {
+        assert_eq!(
+            expected.as_bytes(),
+            remove_dot_segments(path.as_bytes()),
+            "{}",
+            path
+        );
+    }
+}
+
+#[test]
+fn test_client_protection_spaces() {
+    let mut spaces = ProtectionSpaces::new();
+    let mut challenge = DigestChallenge::decode(
+        "Digest realm=\"admin@example.com\", nonce=\"abc\", \
+         domain=\"/admin/ https://api.example.com/v1/\"",
+    )
+    .expect("Could not decode challenge");
+    spaces.insert_challenge(
+        &Uri::from_static("http://example.com/admin/index.html"),
+        challenge.clone(),
+    );
+    spaces.insert_challenge(
+        &Uri::from_static("http://example.org/"),
+        DigestChallenge::decode("Digest realm=\"example.org\", nonce=\"def\"")
+            .expect("Could not decode challenge"),
+    );
+
+    for (uri, realm) in [
+        ("http://example.com/admin/users", Some("admin@example.com")),
+        ("http://EXAMPLE.com:80/admin/", Some("admin@example.com")),
+        (
+            "http://example.com/public/../admin/users",
+            Some("admin@example.com"),
+        ),
+        ("https://api.example.com/v1/items", None),
+        ("http://example.org/anything", Some("example.org")),
+        ("http://example.com/index.html", None),
+        ("http://example.com/admin/../index.html", None),
+        ("http://example.com/admin/%2E%2E/index.html", None),
+        ("https://example.com/admin/users", None),
+        ("http://example.net/admin/users", None),
+    ] {
+        let uri: Uri = uri.parse().expect("Invalid URI");
+        assert_eq!(
+            realm,
+            spaces.find(&uri).map(|space| space.value().realm.as_str()),
+            "{}",
+            uri
+        );
+    }
+
+    let mut foreign = challenge.clone();
+    foreign.realm = "foreign".to_owned();
+    foreign.domain = vec!["https://api.example.com/v1/".to_owned()];
+    spaces.insert_challenge(
+        &Uri::from_static("http://example.com/foreign/index.html"),
+        foreign,
+    );
+    for (uri, realm) in [
+        ("http://example.com/foreign/index.html", Some("foreign")),
+        ("http://example.com/foreign/other.html", None),
+        ("https://api.example.com/v1/items", None),
+    ] {
+        let uri: Uri = uri.parse().expect("Invalid URI");
+        assert_eq!(
+            realm,
+            spaces.find(&uri).map(|space| space.value().realm.as_str()),
+            "{}",
+            uri
+        );
+    }
+    spaces.retain(|space| space.value().realm != "foreign");
+
+    challenge.nonce = "ghi".to_owned();
+    challenge.domain = vec![];
+    spaces.insert_challenge(&Uri::from_static("http://example.com/admin/"), challenge);
+    assert_eq!(2, spaces.len());
+    let space = spaces
+        .find(&Uri::from_static("http://example.com/index.html"))
+        .expect("No protection space found");
+    assert_eq!("ghi", space.value().nonce);
+}
@@ -1,4 +1,4 @@
-// Copyright (c) 2015, 2016, 2017, 2020, 2025 Mark Lee
+// Copyright (c) 2015, 2016, 2017, 2020, 2025, 2026 Mark Lee
 //
 // Permission is hereby granted, free of charge, to any person obtaining a copy
 // of this software and associated documentation files (the "Software"), to deal
@@ -20,12 +20,13 @@
 
 #![allow(dead_code)]
 
-use crate::digest::{Digest, Username};
+use crate::digest::{Digest, DigestChallenge, Username};
 use crate::parsing::fromheaders::ExtendedValue;
 use crate::parsing::test_helper;
 use crate::types::{HashAlgorithm, NonceCount, Qop};
-use headers::HeaderValue;
 use headers::authorization::Credentials;
+use headers::{Authorization, HeaderMapExt, HeaderValue};
+use http::Method;
 
 fn serialize_headers(headers: headers::HeaderMap) -> Result<String, http::header::ToStrError> {
     let mut serialized = String::new();
@@ -57,18 +58,7 @@ pub fn assert_header_parsing_error(data: &str) {
 
 pub fn assert_serialized_header_equal(digest: Digest, actual: &str) {
     let mut headers = headers::HeaderMap::new();
-    let credentials = format!(
-        "Digest {}",
-        digest
-            .encode()
-            .to_str()
-            .expect("Could not serialize Digest credentials")
-    );
-    headers.insert(
-        http::header::AUTHORIZATION,
-        HeaderValue::from_str(credentials.as_str())
-            .expect("Could not deserialize Digest credentials"),
-    );
+    headers.typed_insert(Authorization(digest));
     let expected = serialize_headers(headers).expect("Could not serialize headers");
     assert_eq!(expected, format!("{}\r\n", actual))
 }
@@ -178,3 +168,32 @@ pub fn rfc7616_sha512_256_header(username: String, userhash: bool) -> Digest {
         userhash,
     }
 }
+
+/// Answers a challenge the way a client would, using the first offered qop (if any).
+pub fn respond_to_challenge(
+    challenge: &DigestChallenge,
+    username: &str,
+    password: &str,
+    method: Method,
+    request_uri: &str,
+) -> Digest {
+    let qop = challenge.qop.first().cloned();
+    let mut digest = Digest {
+        username: Username::Plain(username.to_owned()),
+        realm: challenge.realm.clone(),
+        nonce: challenge.nonce.clone(),
+        nonce_count: qop.as_ref().map(|_| NonceCount(1)),
+        response: String::new(),
+        request_uri: request_uri.to_owned(),
+        algorithm: challenge.algorithm.clone(),
+        qop: qop.clone(),
+        client_nonce: qop.map(|_| "0a4f113b".to_owned()),
+        opaque: challenge.opaque.clone(),
+        charset: None,
+        userhash: false,
+    };
+    digest.response = digest
+        .using_password(method, b"", password.to_owned())
+        .expect("Could not compute response");
+    digest
+}
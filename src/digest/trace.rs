@@ -0,0 +1,118 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Step-by-step computation of a `Digest` response, for debugging.
+
+use super::Digest;
+use crate::types::HashAlgorithm;
+use headers::Error;
+use http::Method;
+
+/// Every intermediate value used to compute a `Digest` response, as defined in
+/// [RFC 7616, section 3.4.1](https://tools.ietf.org/html/rfc7616#section-3.4.1).
+///
+/// This exposes the user's secret, so it should only be used for debugging.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ResponseTrace {
+    /// The A1 value (lossily converted to UTF-8), if it was computed. It is not computed when
+    /// tracing from the digest of a non-session A1 value.
+    pub a1: Option<String>,
+    /// The hexadecimal digest of the A1 value, which is used as the secret for KD.
+    pub hashed_a1: String,
+    /// The A2 value.
+    pub a2: String,
+    /// The hexadecimal digest of the A2 value.
+    pub hashed_a2: String,
+    /// The input to KD, i.e. `secret:data`.
+    pub kd_input: String,
+    /// The expected `response`.
+    pub response: String,
+}
+
+impl ResponseTrace {
+    /// Whether the `response` in a header matches the expected one.
+    pub fn matches(&self, digest: &Digest) -> bool {
+        self.response == digest.response
+    }
+}
+
+impl Digest {
+    /// Computes the response step by step, given a password. If `userhash` is `true`,
+    /// `username` must hold the actual username, not the userhash.
+    pub fn trace_using_password(
+        &self,
+        method: Method,
+        entity_body: &[u8],
+        password: String,
+    ) -> Result<ResponseTrace, Error> {
+        let a1 = self.a1(self.username.clone(), password)?;
+        let hashed_a1 = self.algorithm.hex_digest(&a1);
+        self.trace(
+            Some(String::from_utf8_lossy(&a1).into_owned()),
+            hashed_a1,
+            method,
+            entity_body,
+        )
+    }
+
+    /// Computes the response step by step, given the digest of a simple A1 value (see
+    /// [`Digest::simple_hashed_a1`]), e.g. from an `htdigest` file. For the `-sess` algorithms,
+    /// the session A1 value is derived from it.
+    pub fn trace_using_simple_hashed_a1(
+        &self,
+        method: Method,
+        entity_body: &[u8],
+        simple_hashed_a1: &str,
+    ) -> Result<ResponseTrace, Error> {
+        let a1 = match self.algorithm {
+            HashAlgorithm::Md5 | HashAlgorithm::Sha256 | HashAlgorithm::Sha512256 => None,
+            _ => match self.client_nonce {
+                Some(ref client_nonce) => Some(format!(
+                    "{}:{}:{}",
+                    simple_hashed_a1, self.nonce, client_nonce
+                )),
+                None => return Err(Error::invalid()),
+            },
+        };
+        let hashed_a1 = self.session_hashed_a1(simple_hashed_a1)?;
+        self.trace(a1, hashed_a1, method, entity_body)
+    }
+
+    fn trace(
+        &self,
+        a1: Option<String>,
+        hashed_a1: String,
+        method: Method,
+        entity_body: &[u8],
+    ) -> Result<ResponseTrace, Error> {
+        let a2 = self.a2(method, entity_body);
+        let hashed_a2 = self.algorithm.hex_digest(a2.as_bytes());
+        let kd_input = format!("{}:{}", hashed_a1, self.kd_data(&hashed_a2)?);
+        let response = self.algorithm.hex_digest(kd_input.as_bytes());
+        Ok(ResponseTrace {
+            a1,
+            hashed_a1,
+            a2,
+            hashed_a2,
+            kd_input,
+            response,
+        })
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Resolving userhashes back to the users that they belong to.
+
+use super::{Digest, Username};
+use crate::store::{CredentialRecord, CredentialStore};
+use crate::types::HashAlgorithm;
+use std::collections::HashMap;
+
+mod test;
+
+/// A lookup table from userhashes (as defined in
+/// [RFC 7616, section 3.4.4](https://tools.ietf.org/html/rfc7616#section-3.4.4)) to credential
+/// store entries.
+///
+/// When `userhash` is `true`, the server only receives `H(username:realm)`, so it has to hash
+/// every known username to find out who is authenticating. This index does that work up front,
+/// once per realm and algorithm, so that an incoming `Digest` can be resolved to its entry before
+/// the response is validated.
+///
+/// The `-sess` variant of an algorithm shares its userhashes with the non-session variant.
+#[derive(Clone, Debug)]
+pub struct UserhashIndex<R> {
+    algorithms: Vec<HashAlgorithm>,
+    userhashes: HashMap<(String, HashAlgorithm), HashMap<String, R>>,
+}
+
+impl<R: CredentialRecord> UserhashIndex<R> {
+    /// Creates an empty index that computes userhashes for the given algorithms.
+    pub fn new<I: IntoIterator<Item = HashAlgorithm>>(algorithms: I) -> Self {
+        let mut bases: Vec<HashAlgorithm> = vec![];
+        for algorithm in algorithms {
+            let base = algorithm.base();
+            if !bases.contains(&base) {
+                bases.push(base);
+            }
+        }
+
+        UserhashIndex {
+            algorithms: bases,
+            userhashes: HashMap::new(),
+        }
+    }
+
+    /// Creates an index containing every entry of a credential store.
+    pub fn from_store<S, I>(store: &S, algorithms: I) -> Self
+    where
+        S: CredentialStore<Record = R>,
+        I: IntoIterator<Item = HashAlgorithm>,
+        R: Clone,
+    {
+        let mut index = Self::new(algorithms);
+        for record in store.records() {
+            index.insert(record.clone());
+        }
+        index
+    }
+
+    /// Adds an entry to the index, replacing (and returning) any existing entry for the same user
+    /// and realm.
+    pub fn insert(&mut self, record: R) -> Option<R>
+    where
+        R: Clone,
+    {
+        let mut replaced = None;
+        for algorithm in &self.algorithms {
+            let userhash = Digest::userhash(
+                algorithm,
+                record.username().as_bytes().to_vec(),
+                record.realm().to_owned(),
+            );
+            let key = (record.realm().to_owned(), algorithm.clone());
+            let previous = self
+                .userhashes
+                .entry(key)
+                .or_default()
+                .insert(userhash, record.clone());
+            replaced = replaced.or(previous);
+        }
+        replaced
+    }
+
+    /// Adds an entry under a userhash that has already been computed, e.g. one that the credential
+    /// store keeps alongside the entry.
+    pub(super) fn insert_userhash(
+        &mut self,
+        algorithm: &HashAlgorithm,
+        userhash: &str,
+        record: R,
+    ) -> Option<R> {
+        let key = (record.realm().to_owned(), algorithm.base());
+        self.userhashes
+            .entry(key)
+            .or_default()
+            .insert(userhash.to_ascii_lowercase(), record)
+    }
+
+    /// Removes (and returns) the entry for a given user and realm.
+    pub fn remove(&mut self, username: &str, realm: &str) -> Option<R> {
+        let mut removed = None;
+        for algorithm in &self.algorithms {
+            let userhash =
+                Digest::userhash(algorithm, username.as_bytes().to_vec(), realm.to_owned());
+            let key = (realm.to_owned(), algorithm.clone());
+            if let Some(userhashes) = self.userhashes.get_mut(&key) {
+                removed = removed.or(userhashes.remove(&userhash));
+                if userhashes.is_empty() {
+                    self.userhashes.remove(&key);
+                }
+            }
+        }
+        removed
+    }
+
+    /// Looks up the entry that a userhash belongs to.
+    pub fn get(&self, algorithm: &HashAlgorithm, realm: &str, userhash: &str) -> Option<&R> {
+        self.userhashes
+            .get(&(realm.to_owned(), algorithm.base()))?
+            .get(&userhash.to_ascii_lowercase())
+    }
+
+    /// Looks up the entry for the user that sent a `Digest`.
+    ///
+    /// Returns `None` if the `Digest` does not use a userhash, or if the userhash is unknown.
+    pub fn resolve(&self, digest: &Digest) -> Option<&R> {
+        if !digest.userhash {
+            return None;
+        }
+        match digest.username {
+            Username::Plain(ref userhash) => self.get(&digest.algorithm, &digest.realm, userhash),
+            Username::Encoded(_) => None,
+        }
+    }
+}
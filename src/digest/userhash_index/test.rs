@@ -0,0 +1,126 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::UserhashIndex;
+use crate::digest::test_helper::{parse_digest_header, rfc7616_username};
+use crate::digest::{Digest, Username};
+use crate::store::htdigest::{Htdigest, HtdigestRecord};
+use crate::types::HashAlgorithm;
+use http::Method;
+
+fn store() -> Htdigest {
+    let mut store = Htdigest::new();
+    let username = match rfc7616_username() {
+        Username::Encoded(encoded) => String::from_utf8(encoded.value).expect("Not UTF-8"),
+        Username::Plain(_) => panic!("Bad username"),
+    };
+    store.insert(HtdigestRecord::new(
+        &username,
+        "api@example.org",
+        "Secret, or not?".to_owned(),
+        &HashAlgorithm::Sha512256,
+    ));
+    store.insert(HtdigestRecord::new(
+        "Mufasa",
+        "api@example.org",
+        "Circle of Life".to_owned(),
+        &HashAlgorithm::Sha512256,
+    ));
+    store
+}
+
+fn userhash_digest() -> Digest {
+    // From: RFC 7616, Section 3.9.2
+    // https://datatracker.ietf.org/doc/html/rfc7616#section-3.9.2
+    // Adjusted from errata: https://www.rfc-editor.org/errata/eid4897
+    parse_digest_header(
+        "Digest username=\"793263caabb707a56211940d90411ea4a575adeccb7e360aeb624ed06ece9b0b\", \
+         realm=\"api@example.org\", uri=\"/doe.json\", algorithm=SHA-512-256, \
+         nonce=\"5TsQWLVdgBdmrQ0XsxbDODV+57QdFR34I9HAbC/RVvkK\", nc=00000001, \
+         cnonce=\"NTg6RKcb9boFIAS3KrFK9BGeh+iDa/sm6jUMp2wds69v\", qop=auth, \
+         response=\"3798d4131c277846293534c3edc11bd8a5e4cdcbff78b05db9d95eeb1cec68a5\", \
+         opaque=\"HRPCssKJSGjCrkzDg8OhwpzCiGPChXYjwrI2QmXDnsOS\", \
+         charset=UTF-8, userhash=true",
+    )
+}
+
+#[test]
+fn test_resolve_and_validate() {
+    let index = UserhashIndex::from_store(&store(), vec![HashAlgorithm::Sha512256]);
+    let digest = userhash_digest();
+    let record = index.resolve(&digest).expect("Could not resolve userhash");
+    assert_eq!("Jäsøn Doe", record.username);
+    assert!(digest.validate_using_hashed_a1(Method::GET, b"", record.hashed_a1.clone()));
+}
+
+#[test]
+fn test_resolve_with_session_algorithm() {
+    let index = UserhashIndex::from_store(&store(), vec![HashAlgorithm::Sha512256Session]);
+    let mut digest = userhash_digest();
+    digest.algorithm = HashAlgorithm::Sha512256;
+    assert!(index.resolve(&digest).is_some());
+}
+
+#[test]
+fn test_resolve_without_userhash() {
+    let index = UserhashIndex::from_store(&store(), vec![HashAlgorithm::Sha512256]);
+    let mut digest = userhash_digest();
+    digest.userhash = false;
+    assert!(index.resolve(&digest).is_none());
+}
+
+#[test]
+fn test_resolve_with_unindexed_algorithm() {
+    let index = UserhashIndex::from_store(&store(), vec![HashAlgorithm::Md5]);
+    assert!(index.resolve(&userhash_digest()).is_none());
+}
+
+#[test]
+fn test_incremental_updates() {
+    let mut index = UserhashIndex::new(vec![HashAlgorithm::Md5, HashAlgorithm::Sha256]);
+    let record = HtdigestRecord::new(
+        "Mufasa",
+        "testrealm@host.com",
+        "Circle Of Life".to_owned(),
+        &HashAlgorithm::Md5,
+    );
+    assert!(index.insert(record.clone()).is_none());
+    assert_eq!(
+        Some(&record),
+        index.get(
+            &HashAlgorithm::Md5,
+            "testrealm@host.com",
+            "74F54FE2C8045A5FFDA7D02FD97F1716"
+        )
+    );
+    assert!(index.insert(record.clone()).is_some());
+    assert_eq!(Some(record), index.remove("Mufasa", "testrealm@host.com"));
+    assert!(
+        index
+            .get(
+                &HashAlgorithm::Md5,
+                "testrealm@host.com",
+                "74f54fe2c8045a5ffda7d02fd97f1716"
+            )
+            .is_none()
+    );
+}
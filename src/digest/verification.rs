@@ -0,0 +1,232 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Verification of `Digest` credentials with a detailed outcome.
+
+use super::nonce::{NonceValidity, NonceVerifier};
+use super::{Digest, Username};
+use http::{Method, StatusCode};
+
+/// A way in which `Digest` credentials break the protocol or the server's policy.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PolicyViolation {
+    /// `qop` was specified without `nc` or `cnonce`, or vice versa.
+    MissingQopParameters,
+    /// A session algorithm was used without `cnonce`.
+    MissingClientNonce,
+    /// The `uri` does not designate the request target.
+    RequestUriMismatch,
+    /// The `realm` does not match the challenge.
+    RealmMismatch,
+    /// The `opaque` value does not match the challenge.
+    OpaqueMismatch,
+    /// The algorithm was not offered by the server.
+    DisallowedAlgorithm,
+    /// The quality of protection was not offered by the server.
+    DisallowedQop,
+    /// A userhash was sent, but the server does not support them.
+    DisallowedUserhash,
+}
+
+impl PolicyViolation {
+    /// The status code to respond with: `400 Bad Request` for malformed credentials,
+    /// `401 Unauthorized` (with a fresh challenge) for credentials that answer a different
+    /// challenge, and `403 Forbidden` for parameters that the server refuses.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            PolicyViolation::MissingQopParameters
+            | PolicyViolation::MissingClientNonce
+            | PolicyViolation::RequestUriMismatch => StatusCode::BAD_REQUEST,
+            PolicyViolation::RealmMismatch | PolicyViolation::OpaqueMismatch => {
+                StatusCode::UNAUTHORIZED
+            }
+            PolicyViolation::DisallowedAlgorithm
+            | PolicyViolation::DisallowedQop
+            | PolicyViolation::DisallowedUserhash => StatusCode::FORBIDDEN,
+        }
+    }
+}
+
+/// The outcome of verifying `Digest` credentials, as returned by the `Digest::verify_*` methods.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verification {
+    /// The credentials are valid. Contains the authenticated user.
+    Ok(Username),
+    /// The response is valid, but the nonce has expired or was not issued by this server. The
+    /// challenge should be sent with `stale=true`, as specified in
+    /// [RFC 7616, section 3.3](https://tools.ietf.org/html/rfc7616#section-3.3).
+    StaleNonce,
+    /// The response does not match the request and the user's secret.
+    BadResponse,
+    /// The user does not exist, or the userhash does not match the user.
+    UnknownUser,
+    /// The response is valid, but the nonce count has already been used with the nonce.
+    ReplayedNonceCount,
+    /// The credentials break the protocol or the server's policy.
+    PolicyViolation(PolicyViolation),
+}
+
+impl Verification {
+    /// Whether the credentials are valid.
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Verification::Ok(_))
+    }
+
+    /// Whether the challenge should be sent with `stale=true`.
+    pub fn is_stale(&self) -> bool {
+        *self == Verification::StaleNonce
+    }
+
+    /// The status code to respond with. `200 OK` is returned for valid credentials.
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Verification::Ok(_) => StatusCode::OK,
+            Verification::PolicyViolation(violation) => violation.status_code(),
+            _ => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+impl From<PolicyViolation> for Verification {
+    fn from(violation: PolicyViolation) -> Self {
+        Verification::PolicyViolation(violation)
+    }
+}
+
+impl Digest {
+    fn protocol_violation(&self) -> Option<PolicyViolation> {
+        if self.qop.is_some() != (self.nonce_count.is_some() && self.client_nonce.is_some()) {
+            Some(PolicyViolation::MissingQopParameters)
+        } else if self.algorithm != self.algorithm.base() && self.client_nonce.is_none() {
+            Some(PolicyViolation::MissingClientNonce)
+        } else {
+            None
+        }
+    }
+
+    /// Verifies a `Digest.response`, given an HTTP request, a hexadecimal digest of an A1 string
+    /// (as with [`Digest::validate_using_hashed_a1`]), and a way to check the nonce. The
+    /// principal is `Digest.username`.
+    ///
+    /// The nonce is checked last, so that [`Verification::StaleNonce`] and
+    /// [`Verification::ReplayedNonceCount`] are only returned for otherwise valid credentials.
+    pub fn verify_using_hashed_a1<N: NonceVerifier + ?Sized>(
+        &self,
+        method: Method,
+        entity_body: &[u8],
+        a1: String,
+        nonces: &N,
+    ) -> Verification {
+        self.verify_response(
+            self.validate_response(&method, entity_body, &a1),
+            self.username.clone(),
+            nonces,
+        )
+    }
+
+    /// Verifies a `Digest.response`, given an HTTP request, the hexadecimal digest of a simple A1
+    /// value (as with [`Digest::validate_response_using_simple_hashed_a1`]), and a way to check
+    /// the nonce. The principal is `Digest.username`.
+    ///
+    /// Apart from the principal, this does not allocate, which makes it suitable for servers that
+    /// verify many requests (e.g. with `htdigest` files).
+    pub fn verify_using_simple_hashed_a1<N: NonceVerifier + ?Sized>(
+        &self,
+        method: &Method,
+        entity_body: &[u8],
+        simple_hashed_a1: &str,
+        nonces: &N,
+    ) -> Verification {
+        self.verify_response(
+            self.validate_response_using_simple_hashed_a1(method, entity_body, simple_hashed_a1),
+            self.username.clone(),
+            nonces,
+        )
+    }
+
+    /// Verifies a `Digest.response`, given an HTTP request, a password, and a way to check the
+    /// nonce. The principal is `Digest.username`.
+    pub fn verify_using_password<N: NonceVerifier + ?Sized>(
+        &self,
+        method: Method,
+        entity_body: &[u8],
+        password: String,
+        nonces: &N,
+    ) -> Verification {
+        self.verify_using_username_and_password(
+            method,
+            entity_body,
+            self.username.clone(),
+            password,
+            nonces,
+        )
+    }
+
+    /// Verifies a `Digest.username` and `Digest.response`, given an HTTP request, a username, a
+    /// password, and a way to check the nonce. If a userhash is specified, that is verified
+    /// first. The principal is `username`.
+    pub fn verify_using_userhash_and_password<N: NonceVerifier + ?Sized>(
+        &self,
+        method: Method,
+        entity_body: &[u8],
+        username: Username,
+        password: String,
+        nonces: &N,
+    ) -> Verification {
+        if self.userhash && !self.validate_userhash(username.clone()) {
+            return Verification::UnknownUser;
+        }
+        self.verify_using_username_and_password(method, entity_body, username, password, nonces)
+    }
+
+    fn verify_using_username_and_password<N: NonceVerifier + ?Sized>(
+        &self,
+        method: Method,
+        entity_body: &[u8],
+        username: Username,
+        password: String,
+        nonces: &N,
+    ) -> Verification {
+        let valid = self
+            .hashed_a1(username.clone(), password)
+            .is_ok_and(|a1| self.validate_response(&method, entity_body, &a1));
+        self.verify_response(valid, username, nonces)
+    }
+
+    fn verify_response<N: NonceVerifier + ?Sized>(
+        &self,
+        valid: bool,
+        principal: Username,
+        nonces: &N,
+    ) -> Verification {
+        if let Some(violation) = self.protocol_violation() {
+            return violation.into();
+        }
+        if !valid {
+            return Verification::BadResponse;
+        }
+
+        match nonces.verify_nonce(&self.nonce, self.nonce_count.as_ref()) {
+            NonceValidity::Valid => Verification::Ok(principal),
+            NonceValidity::Stale | NonceValidity::Invalid => Verification::StaleNonce,
+            NonceValidity::Replayed => Verification::ReplayedNonceCount,
+        }
+    }
+}
@@ -0,0 +1,585 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! An implementation of HTTP Origin-Bound Authentication (HOBA), as defined in
+//! [RFC 7486](https://tools.ietf.org/html/rfc7486).
+//!
+//! Instead of a password, the client proves possession of a private key that it registered with
+//! the server, by signing the server's challenge along with the origin and realm.
+//!
+//! Only the RSA-SHA256 signature algorithm (`alg=0`) is supported. This module requires the `hoba`
+//! feature.
+//!
+//! ## Security
+//!
+//! Signatures are made with the `rsa` crate, which is affected by
+//! [RUSTSEC-2023-0071](https://rustsec.org/advisories/RUSTSEC-2023-0071), the "Marvin" timing side
+//! channel, and has no fixed release yet. An attacker who can time many private key operations may
+//! be able to recover the private key. Verifying signatures, which is all that [`HobaServer`] does,
+//! only uses the public key and is not affected. [`HobaResult::sign`] is, so clients should avoid
+//! signing where an attacker can measure how long it takes, e.g. over a shared network with many
+//! repeated challenges.
+
+use crate::challenge::Challenge;
+use crate::parsing::{
+    DigestParameters, parse_parameters, strip_scheme, to_header_value, unraveled_map_value,
+};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD as BASE64URL;
+use headers::authorization::Credentials;
+use http::HeaderValue;
+use rsa::pkcs1v15::{Signature, SigningKey, VerifyingKey};
+use rsa::pkcs8::{DecodePublicKey, EncodePublicKey};
+use rsa::signature::{SignatureEncoding, Signer, Verifier};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+mod test;
+
+/// The authentication scheme name.
+pub const SCHEME: &str = "HOBA";
+
+/// Errors relating to HOBA registration and authentication.
+#[derive(Debug, Error, PartialEq)]
+pub enum HobaError {
+    /// A registration request is missing a field, or has an invalid one.
+    #[error("Invalid registration: {0}")]
+    InvalidRegistration(&'static str),
+    /// The signature algorithm is not supported.
+    #[error("Unsupported signature algorithm: {0}")]
+    UnsupportedAlgorithm(String),
+    /// No key is registered with the key identifier.
+    #[error("Unknown key identifier")]
+    UnknownKey,
+    /// A key is already registered with the key identifier.
+    #[error("Key identifier is already registered")]
+    KeyAlreadyRegistered,
+    /// The challenge was not issued by the server, has already been used, or has expired.
+    #[error("Unknown or expired challenge")]
+    UnknownChallenge,
+    /// The signature does not verify with the registered key.
+    #[error("Invalid signature")]
+    InvalidSignature,
+}
+
+/// Signature algorithms, the `alg` registration field.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum HobaAlgorithm {
+    /// `0`: RSASSA-PKCS1-v1_5 with SHA-256
+    RsaSha256,
+}
+
+impl FromStr for HobaAlgorithm {
+    type Err = HobaError;
+
+    fn from_str(s: &str) -> Result<HobaAlgorithm, HobaError> {
+        match s {
+            "0" => Ok(HobaAlgorithm::RsaSha256),
+            _ => Err(HobaError::UnsupportedAlgorithm(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for HobaAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HobaAlgorithm::RsaSha256 => write!(f, "0"),
+        }
+    }
+}
+
+/// How a key identifier was constructed, the `kidtype` registration field.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum KidType {
+    /// `0`: a hash of the public key
+    HashedPublicKey,
+    /// `1`: a URI
+    Uri,
+    /// `2`: an unformatted string, chosen by the client
+    Unformatted,
+}
+
+impl FromStr for KidType {
+    type Err = HobaError;
+
+    fn from_str(s: &str) -> Result<KidType, HobaError> {
+        match s {
+            "0" => Ok(KidType::HashedPublicKey),
+            "1" => Ok(KidType::Uri),
+            "2" => Ok(KidType::Unformatted),
+            _ => Err(HobaError::InvalidRegistration("kidtype")),
+        }
+    }
+}
+
+impl fmt::Display for KidType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            KidType::HashedPublicKey => write!(f, "0"),
+            KidType::Uri => write!(f, "1"),
+            KidType::Unformatted => write!(f, "2"),
+        }
+    }
+}
+
+/// The SHA-256 hash of a DER-encoded public key, which is the key identifier for
+/// [`KidType::HashedPublicKey`].
+pub fn public_key_hash(public_key: &RsaPublicKey) -> Option<Vec<u8>> {
+    let der = public_key.to_public_key_der().ok()?;
+    Some(Sha256::digest(der.as_bytes()).to_vec())
+}
+
+fn is_base64url(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+/// A HOBA challenge, sent by the server in the `WWW-Authenticate` header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HobaChallenge {
+    /// The base64url-encoded challenge, which the client signs
+    pub challenge: String,
+    /// How long, in seconds, the challenge is valid for
+    pub max_age: u64,
+    /// The protection space
+    pub realm: Option<String>,
+}
+
+impl HobaChallenge {
+    /// Creates a challenge with 32 random octets.
+    pub fn generate(max_age: u64, realm: Option<&str>) -> HobaChallenge {
+        HobaChallenge {
+            challenge: BASE64URL.encode(rand::random::<[u8; 32]>()),
+            max_age,
+            realm: realm.map(str::to_owned),
+        }
+    }
+}
+
+impl Challenge for HobaChallenge {
+    const SCHEME: &'static str = SCHEME;
+
+    fn decode(value: &str) -> Option<Self> {
+        let map = parse_parameters(strip_scheme(value, SCHEME)?).ok()?;
+        let challenge = unraveled_map_value(&map, "challenge")?;
+        let max_age = unraveled_map_value(&map, "max-age")?;
+        if !is_base64url(&challenge) || !max_age.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        Some(HobaChallenge {
+            challenge,
+            max_age: max_age.parse().ok()?,
+            realm: unraveled_map_value(&map, "realm"),
+        })
+    }
+
+    fn encode(&self) -> HeaderValue {
+        let mut parameters = DigestParameters::new();
+        parameters.append("challenge", &self.challenge, true);
+        parameters.append("max-age", &self.max_age.to_string(), false);
+        if let Some(ref realm) = self.realm {
+            parameters.append("realm", realm, true);
+        }
+        to_header_value(&format!("{} {}", SCHEME, parameters))
+    }
+}
+
+/// Builds the blob that the client signs.
+///
+/// ## ABNF
+///
+/// ```text
+/// HOBA-TBS = len ":" nonce
+///            len ":" alg
+///            len ":" origin
+///            len ":" [ realm ]
+///            len ":" kid
+///            len ":" challenge
+/// ```
+fn to_be_signed(
+    nonce: &str,
+    algorithm: HobaAlgorithm,
+    origin: &str,
+    realm: Option<&str>,
+    kid: &str,
+    challenge: &str,
+) -> Vec<u8> {
+    let algorithm = algorithm.to_string();
+    let mut blob = String::new();
+    for item in [
+        nonce,
+        &algorithm,
+        origin,
+        realm.unwrap_or_default(),
+        kid,
+        challenge,
+    ] {
+        blob.push_str(&format!("{}:{}", item.len(), item));
+    }
+    blob.into_bytes()
+}
+
+/// The client's signed response to a challenge, sent in the `result` parameter of the
+/// `Authorization` header.
+///
+/// ## ABNF
+///
+/// ```text
+/// HOBA-client-result = kid "." challenge "." nonce "." signature
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct HobaResult {
+    /// The base64url-encoded key identifier
+    pub kid: String,
+    /// The base64url-encoded challenge from the server
+    pub challenge: String,
+    /// The base64url-encoded client nonce
+    pub nonce: String,
+    /// The signature over the to-be-signed blob
+    pub signature: Vec<u8>,
+}
+
+impl HobaResult {
+    /// Signs a challenge, with a random client nonce. `origin` is the serialized origin of the
+    /// request, e.g. `https://example.com:443`.
+    pub fn sign(
+        private_key: &RsaPrivateKey,
+        kid: impl AsRef<[u8]>,
+        challenge: &HobaChallenge,
+        origin: &str,
+    ) -> HobaResult {
+        let kid = BASE64URL.encode(kid);
+        let nonce = BASE64URL.encode(rand::random::<[u8; 16]>());
+        let blob = to_be_signed(
+            &nonce,
+            HobaAlgorithm::RsaSha256,
+            origin,
+            challenge.realm.as_deref(),
+            &kid,
+            &challenge.challenge,
+        );
+        let signature = SigningKey::<Sha256>::new(private_key.clone()).sign(&blob);
+        HobaResult {
+            kid,
+            challenge: challenge.challenge.clone(),
+            nonce,
+            signature: signature.to_vec(),
+        }
+    }
+
+    /// The decoded key identifier, if it is valid UTF-8.
+    pub fn key_id(&self) -> Option<String> {
+        String::from_utf8(BASE64URL.decode(&self.kid).ok()?).ok()
+    }
+
+    /// Verifies the signature with a registered key.
+    pub fn verify(
+        &self,
+        record: &HobaKeyRecord,
+        origin: &str,
+        realm: Option<&str>,
+    ) -> Result<(), HobaError> {
+        let blob = to_be_signed(
+            &self.nonce,
+            record.algorithm,
+            origin,
+            realm,
+            &self.kid,
+            &self.challenge,
+        );
+        let signature = Signature::try_from(self.signature.as_slice())
+            .map_err(|_| HobaError::InvalidSignature)?;
+        VerifyingKey::<Sha256>::new(record.public_key.clone())
+            .verify(&blob, &signature)
+            .map_err(|_| HobaError::InvalidSignature)
+    }
+}
+
+impl FromStr for HobaResult {
+    type Err = headers::Error;
+
+    fn from_str(s: &str) -> Result<HobaResult, headers::Error> {
+        let parts: Vec<&str> = s.split('.').collect();
+        let [kid, challenge, nonce, signature] = parts[..] else {
+            return Err(headers::Error::invalid());
+        };
+        if !is_base64url(kid) || !is_base64url(challenge) || !is_base64url(nonce) {
+            return Err(headers::Error::invalid());
+        }
+
+        Ok(HobaResult {
+            kid: kid.to_owned(),
+            challenge: challenge.to_owned(),
+            nonce: nonce.to_owned(),
+            signature: BASE64URL
+                .decode(signature)
+                .map_err(|_| headers::Error::invalid())?,
+        })
+    }
+}
+
+impl fmt::Display for HobaResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}.{}.{}.{}",
+            self.kid,
+            self.challenge,
+            self.nonce,
+            BASE64URL.encode(&self.signature)
+        )
+    }
+}
+
+impl Credentials for HobaResult {
+    const SCHEME: &'static str = SCHEME;
+
+    fn decode(value: &HeaderValue) -> Option<Self> {
+        let map = parse_parameters(strip_scheme(value.to_str().ok()?, SCHEME)?).ok()?;
+        unraveled_map_value(&map, "result")?.parse().ok()
+    }
+
+    fn encode(&self) -> HeaderValue {
+        let mut parameters = DigestParameters::new();
+        parameters.append("result", &self.to_string(), true);
+        to_header_value(&format!("{} {}", SCHEME, parameters))
+    }
+}
+
+/// A key registered by a client, as defined in
+/// [RFC 7486, section 6.1](https://tools.ietf.org/html/rfc7486#section-6.1).
+#[derive(Clone, Debug, PartialEq)]
+pub struct HobaKeyRecord {
+    /// The key identifier, as sent in [`HobaResult::kid`]
+    pub kid: String,
+    /// How the key identifier was constructed
+    pub kid_type: KidType,
+    /// The signature algorithm
+    pub algorithm: HobaAlgorithm,
+    /// The public key
+    pub public_key: RsaPublicKey,
+    /// The device identifier, if the client sent one
+    pub device_id: Option<String>,
+    /// The account that the key belongs to, which is the key identifier of the first key
+    /// registered to it
+    pub account: String,
+}
+
+impl HobaKeyRecord {
+    /// Parses a registration request body (`application/x-www-form-urlencoded`), with the
+    /// `pub` (PEM-encoded public key), `kidtype`, `kid`, `alg`, and optional `did` fields.
+    ///
+    /// The key starts a new account. When `kidtype` is `0`, `kid` must be the
+    /// [hash of the public key](public_key_hash).
+    pub fn from_registration(body: &str) -> Result<HobaKeyRecord, HobaError> {
+        let fields: HashMap<String, String> = body
+            .split('&')
+            .filter(|field| !field.is_empty())
+            .map(|field| {
+                let (name, value) = field.split_once('=').unwrap_or((field, ""));
+                let decode = |s: &str| {
+                    percent_encoding::percent_decode_str(&s.replace('+', " "))
+                        .decode_utf8_lossy()
+                        .into_owned()
+                };
+                (decode(name), decode(value))
+            })
+            .collect();
+        let field = |name: &'static str| {
+            fields
+                .get(name)
+                .filter(|value| !value.is_empty())
+                .ok_or(HobaError::InvalidRegistration(name))
+        };
+
+        let kid = field("kid")?;
+        if !is_base64url(kid) {
+            return Err(HobaError::InvalidRegistration("kid"));
+        }
+        let public_key = RsaPublicKey::from_public_key_pem(field("pub")?)
+            .map_err(|_| HobaError::InvalidRegistration("pub"))?;
+
+        let kid_type = field("kidtype")?.parse()?;
+        if kid_type == KidType::HashedPublicKey
+            && BASE64URL.decode(kid).ok() != public_key_hash(&public_key)
+        {
+            return Err(HobaError::InvalidRegistration("kid"));
+        }
+
+        Ok(HobaKeyRecord {
+            kid: kid.clone(),
+            kid_type,
+            algorithm: field("alg")?.parse()?,
+            public_key,
+            device_id: fields.get("did").cloned(),
+            account: kid.clone(),
+        })
+    }
+}
+
+/// Issues challenges and authenticates clients for an origin and realm.
+///
+/// Challenges are single-use, and expire after `max-age` seconds. Once too many are outstanding,
+/// the oldest ones are discarded.
+#[derive(Debug)]
+pub struct HobaServer {
+    origin: String,
+    realm: Option<String>,
+    max_age: u64,
+    max_challenges: usize,
+    keys: HashMap<String, HobaKeyRecord>,
+    challenges: HashMap<String, Instant>,
+    order: VecDeque<String>,
+}
+
+impl HobaServer {
+    /// Creates a server for an origin (e.g. `https://example.com:443`) and optional realm, whose
+    /// challenges are valid for `max_age` seconds.
+    pub fn new(origin: &str, realm: Option<&str>, max_age: u64) -> HobaServer {
+        HobaServer {
+            origin: origin.to_owned(),
+            realm: realm.map(str::to_owned),
+            max_age,
+            max_challenges: 10_000,
+            keys: HashMap::new(),
+            challenges: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Sets how many challenges can be outstanding at once (at least one).
+    pub fn with_max_challenges(mut self, max_challenges: usize) -> HobaServer {
+        self.max_challenges = max_challenges.max(1);
+        self
+    }
+
+    /// Forgets challenges that have expired. They all have the same lifetime, so they expire in
+    /// the order that they were issued.
+    fn expire_challenges(&mut self) {
+        let max_age = Duration::from_secs(self.max_age);
+        while let Some(challenge) = self.order.front() {
+            if self
+                .challenges
+                .get(challenge)
+                .is_some_and(|issued| issued.elapsed() < max_age)
+            {
+                break;
+            }
+            if let Some(challenge) = self.order.pop_front() {
+                self.challenges.remove(&challenge);
+            }
+        }
+    }
+
+    /// Issues a new challenge.
+    pub fn challenge(&mut self) -> HobaChallenge {
+        self.expire_challenges();
+        while self.challenges.len() >= self.max_challenges {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.challenges.remove(&oldest);
+        }
+        // Used challenges are only removed from the front of the queue, so compact it once it is
+        // mostly made up of them.
+        if self.order.len() >= 2 * self.max_challenges {
+            let challenges = &self.challenges;
+            self.order
+                .retain(|challenge| challenges.contains_key(challenge));
+        }
+        let challenge = HobaChallenge::generate(self.max_age, self.realm.as_deref());
+        self.order.push_back(challenge.challenge.clone());
+        self.challenges
+            .insert(challenge.challenge.clone(), Instant::now());
+        challenge
+    }
+
+    fn take_challenge(&mut self, result: &HobaResult) -> Result<(), HobaError> {
+        self.expire_challenges();
+        self.challenges
+            .remove(&result.challenge)
+            .map(|_| ())
+            .ok_or(HobaError::UnknownChallenge)
+    }
+
+    fn registration(&self, body: &str, result: &HobaResult) -> Result<HobaKeyRecord, HobaError> {
+        let record = HobaKeyRecord::from_registration(body)?;
+        if record.kid != result.kid {
+            return Err(HobaError::InvalidRegistration("kid"));
+        }
+        if self.keys.contains_key(&record.kid) {
+            return Err(HobaError::KeyAlreadyRegistered);
+        }
+        Ok(record)
+    }
+
+    /// Registers a key for a new account. The registration request must be signed with the key
+    /// being registered.
+    pub fn register(&mut self, body: &str, result: &HobaResult) -> Result<(), HobaError> {
+        let record = self.registration(body, result)?;
+        self.take_challenge(result)?;
+        result.verify(&record, &self.origin, self.realm.as_deref())?;
+        self.keys.insert(record.kid.clone(), record);
+        Ok(())
+    }
+
+    /// Adds a key to an existing account. The registration request must be signed with the key
+    /// being registered, and `existing` with a key that is already registered to the account.
+    /// Both results need their own challenge.
+    pub fn add_key(
+        &mut self,
+        body: &str,
+        result: &HobaResult,
+        existing: &HobaResult,
+    ) -> Result<(), HobaError> {
+        let mut record = self.registration(body, result)?;
+        let account = self.authenticate(existing)?.account.clone();
+        self.take_challenge(result)?;
+        result.verify(&record, &self.origin, self.realm.as_deref())?;
+        record.account = account;
+        self.keys.insert(record.kid.clone(), record);
+        Ok(())
+    }
+
+    /// Removes a registered key, e.g. when the user logs out of a device.
+    pub fn revoke(&mut self, kid: &str) -> Option<HobaKeyRecord> {
+        self.keys.remove(kid)
+    }
+
+    /// Looks up a registered key.
+    pub fn key(&self, kid: &str) -> Option<&HobaKeyRecord> {
+        self.keys.get(kid)
+    }
+
+    /// Authenticates a client, returning the key it signed with.
+    pub fn authenticate(&mut self, result: &HobaResult) -> Result<&HobaKeyRecord, HobaError> {
+        self.take_challenge(result)?;
+        let record = self.keys.get(&result.kid).ok_or(HobaError::UnknownKey)?;
+        result.verify(record, &self.origin, self.realm.as_deref())?;
+        Ok(record)
+    }
+}
@@ -1,4 +1,4 @@
-// Copyright (c) 2015, 2016, 2017 Mark Lee
+// Copyright (c) 2015, 2016, 2017, 2026 Mark Lee
 //
 // Permission is hereby granted, free of charge, to any person obtaining a copy
 // of this software and associated documentation files (the "Software"), to deal
@@ -23,8 +23,31 @@
 #[warn(missing_docs)]
 pub mod authentication_info;
 #[warn(missing_docs)]
+pub mod authenticator;
+#[warn(missing_docs)]
+pub mod basic;
+#[warn(missing_docs)]
+pub mod bearer;
+#[warn(missing_docs)]
+pub mod challenge;
+#[cfg(feature = "concealed")]
+#[warn(missing_docs)]
+pub mod concealed;
+#[warn(missing_docs)]
 pub mod digest;
+#[cfg(feature = "hoba")]
+#[warn(missing_docs)]
+pub mod hoba;
+#[cfg(feature = "mutual")]
+#[warn(missing_docs)]
+pub mod mutual;
 #[warn(missing_docs)]
 mod parsing;
 #[warn(missing_docs)]
+pub mod scram;
+#[cfg(feature = "serde")]
+mod serialization;
+#[warn(missing_docs)]
+pub mod store;
+#[warn(missing_docs)]
 pub mod types;
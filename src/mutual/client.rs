@@ -0,0 +1,149 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! The client side of the `Mutual` exchange.
+
+use super::{KexC1, KexS1, MutualError, MutualParameters, VfyC, VfyS, kam3};
+use num_bigint::BigUint;
+use std::fmt;
+use subtle::ConstantTimeEq;
+
+/// The client's state before it receives `401-KEX-S1`.
+#[derive(Clone)]
+pub struct MutualClient {
+    parameters: MutualParameters,
+    username: String,
+    secret: BigUint,
+    client_secret: BigUint,
+    kc1: BigUint,
+}
+
+impl MutualClient {
+    /// Starts an exchange, using the parameters from the server's `401-INIT` challenge.
+    pub fn new(parameters: MutualParameters, username: &str, password: &str) -> MutualClient {
+        let secret = kam3::password_secret(&parameters, username, password);
+        let client_secret = kam3::random_exponent();
+        let kc1 = kam3::client_key(&client_secret);
+        MutualClient {
+            parameters,
+            username: username.to_owned(),
+            secret,
+            client_secret,
+            kc1,
+        }
+    }
+
+    /// The message to send in the first `Authorization` header.
+    pub fn kex_c1(&self) -> KexC1 {
+        KexC1 {
+            parameters: self.parameters.clone(),
+            user: self.username.clone(),
+            kc1: kam3::octets(&self.kc1),
+        }
+    }
+
+    /// Derives the session secret from the server's key.
+    pub fn establish(self, kex_s1: &KexS1) -> Result<MutualClientSession, MutualError> {
+        if kex_s1.parameters != self.parameters {
+            return Err(MutualError::ParameterMismatch);
+        }
+        let ks1 = kam3::element(&kex_s1.ks1).ok_or(MutualError::InvalidKey)?;
+        let z = kam3::client_session_secret(&self.secret, &self.client_secret, &self.kc1, &ks1)
+            .ok_or(MutualError::InvalidKey)?;
+        Ok(MutualClientSession {
+            parameters: self.parameters,
+            sid: kex_s1.sid.clone(),
+            kc1: self.kc1,
+            ks1,
+            z,
+            nc: 0,
+            nc_max: kex_s1.nc_max,
+        })
+    }
+}
+
+/// The secrets derived from the password and the key exchange are omitted.
+impl fmt::Debug for MutualClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MutualClient")
+            .field("parameters", &self.parameters)
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+/// An established session, which can authenticate several requests.
+#[derive(Clone)]
+pub struct MutualClientSession {
+    parameters: MutualParameters,
+    sid: String,
+    kc1: BigUint,
+    ks1: BigUint,
+    z: BigUint,
+    nc: u32,
+    nc_max: u32,
+}
+
+impl MutualClientSession {
+    /// The session ID.
+    pub fn sid(&self) -> &str {
+        &self.sid
+    }
+
+    /// The message to send in the `Authorization` header of the next request, with the next
+    /// nonce count. `validation_value` is the value for the exchange's [`super::Validation`]
+    /// method, e.g. `https://example.com:443` for `host`.
+    pub fn vfy_c(&mut self, validation_value: &str) -> Result<VfyC, MutualError> {
+        if self.nc >= self.nc_max {
+            return Err(MutualError::NonceCountExhausted);
+        }
+        self.nc += 1;
+        Ok(VfyC {
+            parameters: self.parameters.clone(),
+            sid: self.sid.clone(),
+            nc: self.nc,
+            vkc: kam3::client_verifier(&self.kc1, &self.ks1, &self.z, self.nc, validation_value)
+                .to_vec(),
+        })
+    }
+
+    /// Checks the server's `Authentication-Info` header for the request with the given nonce
+    /// count.
+    pub fn verify(&self, vfy_s: &VfyS, nc: u32, validation_value: &str) -> Result<(), MutualError> {
+        let expected = kam3::server_verifier(&self.kc1, &self.ks1, &self.z, nc, validation_value);
+        if vfy_s.sid == self.sid && bool::from(expected.ct_eq(vfy_s.vks.as_slice())) {
+            Ok(())
+        } else {
+            Err(MutualError::InvalidServerVerifier)
+        }
+    }
+}
+
+/// The session secret is omitted.
+impl fmt::Debug for MutualClientSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MutualClientSession")
+            .field("parameters", &self.parameters)
+            .field("sid", &self.sid)
+            .field("nc", &self.nc)
+            .field("nc_max", &self.nc_max)
+            .finish_non_exhaustive()
+    }
+}
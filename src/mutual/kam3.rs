@@ -0,0 +1,238 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! The ISO-KAM3 key exchange over a discrete-logarithm group, as defined in
+//! [RFC 8121, section 3](https://tools.ietf.org/html/rfc8121#section-3).
+
+use super::MutualParameters;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
+use std::sync::OnceLock;
+
+mod test;
+
+/// The number of PBKDF2 iterations used to derive the password secret.
+const PASSWORD_ITERATIONS: u32 = 16384;
+
+/// The 2048-bit MODP group from [RFC 3526, section 3](https://tools.ietf.org/html/rfc3526#section-3).
+const MODP_2048: [&str; 8] = [
+    "FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74",
+    "020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F1437",
+    "4FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7ED",
+    "EE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF05",
+    "98DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB",
+    "9ED529077096966D670C354E4ABC9804F1746C08CA18217C32905E462E36CE3B",
+    "E39E772C180E86039B2783A2EC07A28FB5C55DF06F4C52C9DE2BCBF695581718",
+    "3995497CEA956AE515D2261898FA051015728E5A8AACAA68FFFFFFFFFFFFFFFF",
+];
+
+/// The size, in octets, of an encoded group element.
+pub(crate) const ELEMENT_SIZE: usize = 256;
+
+/// A prime-order subgroup of the multiplicative group modulo a safe prime.
+struct Group {
+    /// The modulus
+    q: BigUint,
+    /// The generator
+    g: BigUint,
+    /// The order of the subgroup generated by `g`, `(q - 1) / 2`
+    r: BigUint,
+}
+
+fn group() -> &'static Group {
+    static GROUP: OnceLock<Group> = OnceLock::new();
+    GROUP.get_or_init(|| {
+        let q = BigUint::parse_bytes(MODP_2048.concat().as_bytes(), 16)
+            .expect("The MODP group prime is valid hexadecimal");
+        let r = (&q - 1u32) >> 1;
+        Group {
+            q,
+            g: BigUint::from(2u32),
+            r,
+        }
+    })
+}
+
+/// `VI(i)`: a variable-length, big-endian base-128 integer, with the high bit set on all but the
+/// last octet.
+fn vi(mut i: u64) -> Vec<u8> {
+    let mut octets = vec![(i & 0x7f) as u8];
+    i >>= 7;
+    while i > 0 {
+        octets.push((i & 0x7f) as u8 | 0x80);
+        i >>= 7;
+    }
+    octets.reverse();
+    octets
+}
+
+/// `VS(s)`: a length-prefixed string.
+fn vs(s: &str) -> Vec<u8> {
+    let mut octets = vi(s.len() as u64);
+    octets.extend_from_slice(s.as_bytes());
+    octets
+}
+
+fn hash(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update(part);
+    }
+    hasher.finalize().into()
+}
+
+/// `OCTETS(x)`: a group element as a fixed-size, big-endian octet string.
+pub(crate) fn octets(x: &BigUint) -> Vec<u8> {
+    let bytes = x.to_bytes_be();
+    let mut octets = vec![0; ELEMENT_SIZE.saturating_sub(bytes.len())];
+    octets.extend_from_slice(&bytes);
+    octets
+}
+
+/// Decodes a group element, checking that it is in the prime-order subgroup (and is neither `1`
+/// nor `q - 1`).
+pub(crate) fn element(octets: &[u8]) -> Option<BigUint> {
+    let group = group();
+    if octets.len() != ELEMENT_SIZE {
+        return None;
+    }
+    let x = BigUint::from_bytes_be(octets);
+    let upper_bound = &group.q - 2u32;
+    if x < BigUint::from(2u32) || x > upper_bound || x.modpow(&group.r, &group.q) != 1u32.into() {
+        return None;
+    }
+    Some(x)
+}
+
+/// The secret `pi`, derived from the user's password.
+pub(crate) fn password_secret(
+    parameters: &MutualParameters,
+    username: &str,
+    password: &str,
+) -> BigUint {
+    let mut salt = vs(&parameters.algorithm.to_string());
+    salt.extend(vs(&parameters.auth_scope));
+    salt.extend(vs(&parameters.realm));
+    salt.extend(vs(username));
+    let mut secret = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), &salt, PASSWORD_ITERATIONS, &mut secret);
+    BigUint::from_bytes_be(&secret) % &group().r
+}
+
+/// The password verifier `J = g^pi`, which the server stores instead of the password.
+pub(crate) fn password_verifier(secret: &BigUint) -> BigUint {
+    let group = group();
+    group.g.modpow(secret, &group.q)
+}
+
+/// A random exponent in `[1, r - 1]`.
+pub(crate) fn random_exponent() -> BigUint {
+    let group = group();
+    let bytes: Vec<u8> = (0..ELEMENT_SIZE + 16).map(|_| rand::random()).collect();
+    BigUint::from_bytes_be(&bytes) % (&group.r - 1u32) + 1u32
+}
+
+/// `K_c1 = g^S_c1`
+pub(crate) fn client_key(client_secret: &BigUint) -> BigUint {
+    let group = group();
+    group.g.modpow(client_secret, &group.q)
+}
+
+/// `t_1 = INT(H(octet(1) | OCTETS(K_c1)))`
+fn t1(kc1: &BigUint) -> BigUint {
+    BigUint::from_bytes_be(&hash(&[&[1], &octets(kc1)]))
+}
+
+/// `t_2 = INT(H(octet(2) | OCTETS(K_c1) | OCTETS(K_s1)))`
+fn t2(kc1: &BigUint, ks1: &BigUint) -> BigUint {
+    BigUint::from_bytes_be(&hash(&[&[2], &octets(kc1), &octets(ks1)]))
+}
+
+/// `K_s1 = (J * K_c1^t_1)^S_s1`
+pub(crate) fn server_key(verifier: &BigUint, kc1: &BigUint, server_secret: &BigUint) -> BigUint {
+    let group = group();
+    (verifier * kc1.modpow(&t1(kc1), &group.q) % &group.q).modpow(server_secret, &group.q)
+}
+
+/// The client's session secret, `z = K_s1^((S_c1 + t_2) / (S_c1 * t_1 + pi) mod r)`. Returns
+/// `None` in the (negligibly likely) case that the denominator is zero.
+pub(crate) fn client_session_secret(
+    secret: &BigUint,
+    client_secret: &BigUint,
+    kc1: &BigUint,
+    ks1: &BigUint,
+) -> Option<BigUint> {
+    let group = group();
+    let numerator = (client_secret + t2(kc1, ks1)) % &group.r;
+    let denominator = (client_secret * t1(kc1) + secret) % &group.r;
+    let exponent = numerator * denominator.modinv(&group.r)? % &group.r;
+    Some(ks1.modpow(&exponent, &group.q))
+}
+
+/// The server's session secret, `z' = (K_c1 * g^t_2)^S_s1`.
+pub(crate) fn server_session_secret(
+    kc1: &BigUint,
+    ks1: &BigUint,
+    server_secret: &BigUint,
+) -> BigUint {
+    let group = group();
+    let base = kc1 * group.g.modpow(&t2(kc1, ks1), &group.q) % &group.q;
+    base.modpow(server_secret, &group.q)
+}
+
+fn verification(
+    label: u8,
+    kc1: &BigUint,
+    ks1: &BigUint,
+    z: &BigUint,
+    nc: u32,
+    validation_value: &str,
+) -> [u8; 32] {
+    hash(&[
+        &[label],
+        &octets(kc1),
+        &octets(ks1),
+        &octets(z),
+        &vi(nc.into()),
+        &vs(validation_value),
+    ])
+}
+
+/// `VK_c = H(octet(4) | OCTETS(K_c1) | OCTETS(K_s1) | OCTETS(z) | VI(nc) | VS(vh))`
+pub(crate) fn client_verifier(
+    kc1: &BigUint,
+    ks1: &BigUint,
+    z: &BigUint,
+    nc: u32,
+    validation_value: &str,
+) -> [u8; 32] {
+    verification(4, kc1, ks1, z, nc, validation_value)
+}
+
+/// `VK_s = H(octet(3) | OCTETS(K_c1) | OCTETS(K_s1) | OCTETS(z) | VI(nc) | VS(vh))`
+pub(crate) fn server_verifier(
+    kc1: &BigUint,
+    ks1: &BigUint,
+    z: &BigUint,
+    nc: u32,
+    validation_value: &str,
+) -> [u8; 32] {
+    verification(3, kc1, ks1, z, nc, validation_value)
+}
@@ -0,0 +1,48 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::{element, group, octets, vi, vs};
+
+#[test]
+fn test_vi() {
+    assert_eq!(vec![0x00], vi(0));
+    assert_eq!(vec![0x7f], vi(127));
+    assert_eq!(vec![0x81, 0x00], vi(128));
+    assert_eq!(vec![0x82, 0x80, 0x01], vi(32769));
+}
+
+#[test]
+fn test_vs() {
+    assert_eq!(b"\x03abc".to_vec(), vs("abc"));
+}
+
+#[test]
+fn test_element_rejects_values_outside_of_subgroup() {
+    let group = group();
+    assert_eq!(None, element(&octets(&1u32.into())));
+    assert_eq!(None, element(&octets(&(&group.q - 1u32))));
+    assert_eq!(None, element(&octets(&group.q)));
+    // 11 is a quadratic non-residue modulo the MODP prime, so it is not in the subgroup.
+    assert_eq!(None, element(&octets(&11u32.into())));
+    assert_eq!(None, element(&[2]));
+    assert_eq!(Some(group.g.clone()), element(&octets(&group.g)));
+}
@@ -0,0 +1,483 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! An implementation of the `Mutual` authentication scheme, as defined in
+//! [RFC 8120](https://tools.ietf.org/html/rfc8120), using the ISO-KAM3 key exchange from
+//! [RFC 8121](https://tools.ietf.org/html/rfc8121).
+//!
+//! Both sides prove knowledge of the password without revealing it, so the client also
+//! authenticates the server. The server only stores a verifier ([`PasswordVerifier`]). The
+//! exchange is:
+//!
+//! 1. `401-INIT`: the server sends a [`MutualChallenge::Init`].
+//! 2. `req-KEX-C1`: the client sends its key, [`KexC1`].
+//! 3. `401-KEX-S1`: the server sends its key and a session ID, [`KexS1`].
+//! 4. `req-VFY-C`: the client sends its verifier, [`VfyC`], along with the actual request.
+//! 5. `200-VFY-S`: the server sends its verifier, [`VfyS`], in the `Authentication-Info` header.
+//!
+//! Once a session is established, the client can send further requests with `req-VFY-C` and an
+//! incremented nonce count (`nc`).
+//!
+//! Only the `iso-kam3-dl-2048-sha256` algorithm is supported. This module requires the `mutual`
+//! feature.
+
+use crate::challenge::Challenge;
+use crate::parsing::{
+    DigestParameters, parse_parameters, raw_map_value, strip_scheme, to_header_value,
+    unraveled_map_value,
+};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use headers::authorization::Credentials;
+use http::HeaderValue;
+use num_bigint::BigUint;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+use unicase::UniCase;
+
+mod client;
+mod kam3;
+mod server;
+mod test;
+
+pub use client::{MutualClient, MutualClientSession};
+pub use server::{MutualOutcome, MutualServer};
+
+/// The authentication scheme name.
+pub const SCHEME: &str = "Mutual";
+
+const VERSION: &str = "1";
+
+/// Errors relating to the `Mutual` exchange.
+#[derive(Debug, Error, PartialEq)]
+pub enum MutualError {
+    /// A key sent by the other side is not a valid group element.
+    #[error("Invalid key")]
+    InvalidKey,
+    /// The parameters of a message do not match the ones of the exchange.
+    #[error("Parameters do not match")]
+    ParameterMismatch,
+    /// The server's verifier is invalid, i.e. the server does not know the user's password
+    /// verifier.
+    #[error("Invalid server verifier")]
+    InvalidServerVerifier,
+    /// The nonce count would exceed the maximum allowed by the server.
+    #[error("Nonce count exhausted")]
+    NonceCountExhausted,
+}
+
+/// Key exchange algorithms, the `algorithm` parameter.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum MutualAlgorithm {
+    /// `iso-kam3-dl-2048-sha256`: ISO-KAM3 over the 2048-bit MODP group, with SHA-256
+    IsoKam3Dl2048Sha256,
+}
+
+impl FromStr for MutualAlgorithm {
+    type Err = headers::Error;
+
+    fn from_str(s: &str) -> Result<MutualAlgorithm, headers::Error> {
+        match s {
+            "iso-kam3-dl-2048-sha256" => Ok(MutualAlgorithm::IsoKam3Dl2048Sha256),
+            _ => Err(headers::Error::invalid()),
+        }
+    }
+}
+
+impl fmt::Display for MutualAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            MutualAlgorithm::IsoKam3Dl2048Sha256 => write!(f, "iso-kam3-dl-2048-sha256"),
+        }
+    }
+}
+
+/// How the server is bound to the exchange, the `validation` parameter. The corresponding
+/// validation value (e.g. `https://example.com:443` for `host`) is supplied by the caller.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Validation {
+    /// `host`: the scheme, host, and port of the request URI
+    Host,
+    /// `tls-server-end-point`: the hash of the server's TLS certificate
+    TlsServerEndPoint,
+    /// `tls-unique`: the TLS Finished message
+    TlsUnique,
+}
+
+impl FromStr for Validation {
+    type Err = headers::Error;
+
+    fn from_str(s: &str) -> Result<Validation, headers::Error> {
+        match s {
+            "host" => Ok(Validation::Host),
+            "tls-server-end-point" => Ok(Validation::TlsServerEndPoint),
+            "tls-unique" => Ok(Validation::TlsUnique),
+            _ => Err(headers::Error::invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Validation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Validation::Host => write!(f, "host"),
+            Validation::TlsServerEndPoint => write!(f, "tls-server-end-point"),
+            Validation::TlsUnique => write!(f, "tls-unique"),
+        }
+    }
+}
+
+/// Why the server sent a `401-INIT` challenge, the `reason` parameter.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Reason {
+    /// `initial`: the client did not attempt to authenticate
+    Initial,
+    /// `stale-session`: the session ID or nonce count is unknown or expired
+    StaleSession,
+    /// `auth-failed`: the password is wrong, or the user does not exist
+    AuthFailed,
+    /// `reauth-needed`: the server wants the client to authenticate again
+    ReauthNeeded,
+    /// `invalid-parameters`: the parameters are malformed or do not match
+    InvalidParameters,
+    /// `internal-error`: the server could not process the request
+    InternalError,
+    /// `user-unknown`: the user does not exist
+    UserUnknown,
+    /// `invalid-credential`: the user's credential is not usable (e.g. expired)
+    InvalidCredential,
+    /// `authz-failed`: the user is authenticated, but not authorized
+    AuthzFailed,
+}
+
+impl FromStr for Reason {
+    type Err = headers::Error;
+
+    fn from_str(s: &str) -> Result<Reason, headers::Error> {
+        match s {
+            "initial" => Ok(Reason::Initial),
+            "stale-session" => Ok(Reason::StaleSession),
+            "auth-failed" => Ok(Reason::AuthFailed),
+            "reauth-needed" => Ok(Reason::ReauthNeeded),
+            "invalid-parameters" => Ok(Reason::InvalidParameters),
+            "internal-error" => Ok(Reason::InternalError),
+            "user-unknown" => Ok(Reason::UserUnknown),
+            "invalid-credential" => Ok(Reason::InvalidCredential),
+            "authz-failed" => Ok(Reason::AuthzFailed),
+            _ => Err(headers::Error::invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match *self {
+            Reason::Initial => "initial",
+            Reason::StaleSession => "stale-session",
+            Reason::AuthFailed => "auth-failed",
+            Reason::ReauthNeeded => "reauth-needed",
+            Reason::InvalidParameters => "invalid-parameters",
+            Reason::InternalError => "internal-error",
+            Reason::UserUnknown => "user-unknown",
+            Reason::InvalidCredential => "invalid-credential",
+            Reason::AuthzFailed => "authz-failed",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+/// The parameters shared by every message except `200-VFY-S`, which identify the exchange.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct MutualParameters {
+    /// The key exchange algorithm
+    pub algorithm: MutualAlgorithm,
+    /// How the server is bound to the exchange
+    pub validation: Validation,
+    /// The host (or domain) that the credentials are valid for
+    pub auth_scope: String,
+    /// The protection space
+    pub realm: String,
+}
+
+/// The password verifier `J`, which the server stores instead of the password. It is specific to
+/// the [`MutualParameters`] and username that it was derived with.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PasswordVerifier(BigUint);
+
+impl PasswordVerifier {
+    /// Derives the verifier from the user's password.
+    pub fn new(parameters: &MutualParameters, username: &str, password: &str) -> PasswordVerifier {
+        let secret = kam3::password_secret(parameters, username, password);
+        PasswordVerifier(kam3::password_verifier(&secret))
+    }
+
+    /// Decodes a verifier previously encoded with [`PasswordVerifier::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Option<PasswordVerifier> {
+        kam3::element(bytes).map(PasswordVerifier)
+    }
+
+    /// Encodes the verifier for storage, as a fixed-size octet string.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        kam3::octets(&self.0)
+    }
+}
+
+type ParameterMap = HashMap<UniCase<String>, String>;
+
+fn parse_version(map: &ParameterMap) -> Option<()> {
+    (unraveled_map_value(map, "version")? == VERSION).then_some(())
+}
+
+fn parse_common(map: &ParameterMap) -> Option<MutualParameters> {
+    parse_version(map)?;
+    Some(MutualParameters {
+        algorithm: unraveled_map_value(map, "algorithm")?.parse().ok()?,
+        validation: unraveled_map_value(map, "validation")?.parse().ok()?,
+        auth_scope: unraveled_map_value(map, "auth-scope")?,
+        realm: unraveled_map_value(map, "realm")?,
+    })
+}
+
+fn parse_number<T: FromStr>(map: &ParameterMap, key: &str) -> Option<T> {
+    let value = unraveled_map_value(map, key)?;
+    if !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+fn parse_base64(map: &ParameterMap, key: &str) -> Option<Vec<u8>> {
+    BASE64.decode(unraveled_map_value(map, key)?).ok()
+}
+
+fn append_common(parameters: &mut DigestParameters, common: &MutualParameters) {
+    parameters.append("version", VERSION, false);
+    parameters.append("algorithm", &common.algorithm.to_string(), false);
+    parameters.append("validation", &common.validation.to_string(), false);
+    parameters.append("auth-scope", &common.auth_scope, true);
+    parameters.append("realm", &common.realm, true);
+}
+
+fn with_scheme(parameters: DigestParameters) -> HeaderValue {
+    to_header_value(&format!("{} {}", SCHEME, parameters))
+}
+
+/// `req-KEX-C1`: the client's first key exchange message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KexC1 {
+    /// The parameters from the server's `401-INIT` challenge
+    pub parameters: MutualParameters,
+    /// The name of the user to authenticate as
+    pub user: String,
+    /// The client's key, `K_c1`
+    pub kc1: Vec<u8>,
+}
+
+/// `req-VFY-C`: the client's proof that it shares the session secret with the server.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VfyC {
+    /// The parameters of the exchange
+    pub parameters: MutualParameters,
+    /// The session ID from `401-KEX-S1`
+    pub sid: String,
+    /// The nonce count, which increases with each request in the session
+    pub nc: u32,
+    /// The client's verifier, `VK_c`
+    pub vkc: Vec<u8>,
+}
+
+/// `Mutual` credentials, sent by the client in the `Authorization` header.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MutualAuthorization {
+    /// `req-KEX-C1`
+    KexC1(KexC1),
+    /// `req-VFY-C`
+    VfyC(VfyC),
+}
+
+impl Credentials for MutualAuthorization {
+    const SCHEME: &'static str = SCHEME;
+
+    fn decode(value: &HeaderValue) -> Option<Self> {
+        let map = parse_parameters(strip_scheme(value.to_str().ok()?, SCHEME)?).ok()?;
+        let parameters = parse_common(&map)?;
+        if map.contains_key(&UniCase::new("kc1".to_owned())) {
+            Some(MutualAuthorization::KexC1(KexC1 {
+                parameters,
+                user: unraveled_map_value(&map, "user")?,
+                kc1: parse_base64(&map, "kc1")?,
+            }))
+        } else {
+            Some(MutualAuthorization::VfyC(VfyC {
+                parameters,
+                sid: unraveled_map_value(&map, "sid")?,
+                nc: parse_number(&map, "nc")?,
+                vkc: parse_base64(&map, "vkc")?,
+            }))
+        }
+    }
+
+    fn encode(&self) -> HeaderValue {
+        let mut parameters = DigestParameters::new();
+        match *self {
+            MutualAuthorization::KexC1(ref kex) => {
+                append_common(&mut parameters, &kex.parameters);
+                parameters.append("user", &kex.user, true);
+                parameters.append("kc1", &BASE64.encode(&kex.kc1), true);
+            }
+            MutualAuthorization::VfyC(ref vfy) => {
+                append_common(&mut parameters, &vfy.parameters);
+                parameters.append("sid", &vfy.sid, false);
+                parameters.append("nc", &vfy.nc.to_string(), false);
+                parameters.append("vkc", &BASE64.encode(&vfy.vkc), true);
+            }
+        }
+        with_scheme(parameters)
+    }
+}
+
+/// `401-KEX-S1`: the server's key exchange message.
+#[derive(Clone, Debug, PartialEq)]
+pub struct KexS1 {
+    /// The parameters from the client's `req-KEX-C1`
+    pub parameters: MutualParameters,
+    /// The session ID
+    pub sid: String,
+    /// The server's key, `K_s1`
+    pub ks1: Vec<u8>,
+    /// The highest nonce count that the server accepts in this session
+    pub nc_max: u32,
+    /// How many nonce counts below the highest one seen the server still accepts
+    pub nc_window: u32,
+    /// How long, in seconds, the session is valid for
+    pub time: u64,
+    /// Space-separated list of paths that the session may be used for
+    pub path: Option<String>,
+}
+
+/// A `Mutual` challenge, sent by the server in the `WWW-Authenticate` header.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MutualChallenge {
+    /// `401-INIT` (and `401-STALE`): asks the client to start a new exchange
+    Init {
+        /// The parameters of the exchange
+        parameters: MutualParameters,
+        /// Why authentication is required
+        reason: Reason,
+    },
+    /// `401-KEX-S1`
+    KexS1(KexS1),
+}
+
+impl Challenge for MutualChallenge {
+    const SCHEME: &'static str = SCHEME;
+
+    fn decode(value: &str) -> Option<Self> {
+        let map = parse_parameters(strip_scheme(value, SCHEME)?).ok()?;
+        let parameters = parse_common(&map)?;
+        if map.contains_key(&UniCase::new("ks1".to_owned())) {
+            Some(MutualChallenge::KexS1(KexS1 {
+                parameters,
+                sid: unraveled_map_value(&map, "sid")?,
+                ks1: parse_base64(&map, "ks1")?,
+                nc_max: parse_number(&map, "nc-max")?,
+                nc_window: parse_number(&map, "nc-window")?,
+                time: parse_number(&map, "time")?,
+                // The path is a list of URIs, which are already percent-encoded.
+                path: raw_map_value(&map, "path"),
+            }))
+        } else {
+            Some(MutualChallenge::Init {
+                parameters,
+                reason: unraveled_map_value(&map, "reason")?.parse().ok()?,
+            })
+        }
+    }
+
+    fn encode(&self) -> HeaderValue {
+        let mut parameters = DigestParameters::new();
+        match *self {
+            MutualChallenge::Init {
+                parameters: ref common,
+                reason,
+            } => {
+                append_common(&mut parameters, common);
+                parameters.append("reason", &reason.to_string(), false);
+            }
+            MutualChallenge::KexS1(ref kex) => {
+                append_common(&mut parameters, &kex.parameters);
+                parameters.append("sid", &kex.sid, false);
+                parameters.append("ks1", &BASE64.encode(&kex.ks1), true);
+                parameters.append("nc-max", &kex.nc_max.to_string(), false);
+                parameters.append("nc-window", &kex.nc_window.to_string(), false);
+                parameters.append("time", &kex.time.to_string(), false);
+                if let Some(ref path) = kex.path {
+                    parameters.append_raw("path", path, true);
+                }
+            }
+        }
+        with_scheme(parameters)
+    }
+}
+
+/// `200-VFY-S`: the server's proof that it shares the session secret with the client, sent in
+/// the `Authentication-Info` header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VfyS {
+    /// The session ID
+    pub sid: String,
+    /// The server's verifier, `VK_s`
+    pub vks: Vec<u8>,
+}
+
+impl headers::Header for VfyS {
+    fn name() -> &'static http::HeaderName {
+        static NAME: http::HeaderName = http::HeaderName::from_static("authentication-info");
+        &NAME
+    }
+
+    /// Decodes the header, with or without the leading `Mutual` scheme.
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or_else(headers::Error::invalid)?;
+        let value = value.to_str().map_err(|_| headers::Error::invalid())?;
+        let map = parse_parameters(strip_scheme(value, SCHEME).unwrap_or(value))?;
+        parse_version(&map).ok_or_else(headers::Error::invalid)?;
+        Ok(VfyS {
+            sid: unraveled_map_value(&map, "sid").ok_or_else(headers::Error::invalid)?,
+            vks: parse_base64(&map, "vks").ok_or_else(headers::Error::invalid)?,
+        })
+    }
+
+    fn encode<E>(&self, values: &mut E)
+    where
+        E: Extend<HeaderValue>,
+    {
+        let mut parameters = DigestParameters::new();
+        parameters.append("version", VERSION, false);
+        parameters.append("sid", &self.sid, false);
+        parameters.append("vks", &BASE64.encode(&self.vks), true);
+        values.extend(std::iter::once(with_scheme(parameters)));
+    }
+}
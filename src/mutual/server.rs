@@ -0,0 +1,298 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! The server side of the `Mutual` exchange, including the session table.
+
+use super::{
+    KexC1, KexS1, MutualAuthorization, MutualChallenge, MutualParameters, PasswordVerifier, Reason,
+    VfyC, VfyS, kam3,
+};
+use num_bigint::BigUint;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::time::{Duration, Instant};
+use subtle::ConstantTimeEq;
+
+/// An established session.
+struct Session {
+    username: String,
+    kc1: BigUint,
+    ks1: BigUint,
+    z: BigUint,
+    established: Instant,
+    highest_nc: u32,
+    seen_nc: HashSet<u32>,
+}
+
+impl Session {
+    /// Records the nonce count, returning whether it is within the window and unused.
+    fn use_nonce_count(&mut self, nc: u32, nc_max: u32, nc_window: u32) -> bool {
+        if nc == 0
+            || nc > nc_max
+            || nc.saturating_add(nc_window) <= self.highest_nc
+            || !self.seen_nc.insert(nc)
+        {
+            return false;
+        }
+        if nc > self.highest_nc {
+            self.highest_nc = nc;
+            let highest_nc = self.highest_nc;
+            self.seen_nc
+                .retain(|seen| seen.saturating_add(nc_window) > highest_nc);
+        }
+        true
+    }
+}
+
+/// What the server should respond with after receiving a [`MutualAuthorization`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum MutualOutcome {
+    /// Respond with `401 Unauthorized` and this `401-KEX-S1` challenge.
+    Continue(MutualChallenge),
+    /// The user is authenticated; process the request and respond with this
+    /// `Authentication-Info` header.
+    Authenticated {
+        /// The name of the authenticated user
+        username: String,
+        /// The `200-VFY-S` message
+        vfy_s: VfyS,
+    },
+    /// Respond with `401 Unauthorized` and this `401-INIT` challenge.
+    Rejected(MutualChallenge),
+}
+
+/// Tracks the sessions established for a protection space, keyed by their `sid`.
+///
+/// By default, sessions last 5 minutes and allow 1000 requests, and requests may arrive up to 128
+/// nonce counts out of order. At most 10,000 sessions are kept; once there are that many, the
+/// oldest ones are discarded.
+pub struct MutualServer {
+    parameters: MutualParameters,
+    nc_max: u32,
+    nc_window: u32,
+    lifetime: Duration,
+    max_sessions: usize,
+    sessions: HashMap<String, Session>,
+    order: VecDeque<String>,
+}
+
+impl MutualServer {
+    /// Creates a server for the given parameters.
+    pub fn new(parameters: MutualParameters) -> MutualServer {
+        MutualServer {
+            parameters,
+            nc_max: 1000,
+            nc_window: 128,
+            lifetime: Duration::from_secs(300),
+            max_sessions: 10_000,
+            sessions: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Sets the maximum nonce count, and how far out of order nonce counts may arrive.
+    pub fn with_nonce_counts(mut self, nc_max: u32, nc_window: u32) -> MutualServer {
+        self.nc_max = nc_max;
+        self.nc_window = nc_window;
+        self
+    }
+
+    /// Sets how long sessions last.
+    pub fn with_lifetime(mut self, lifetime: Duration) -> MutualServer {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// Sets how many sessions can be kept at once (at least one).
+    pub fn with_max_sessions(mut self, max_sessions: usize) -> MutualServer {
+        self.max_sessions = max_sessions.max(1);
+        self
+    }
+
+    /// The number of sessions, including any that have expired since the last request.
+    pub fn session_count(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// A `401-INIT` challenge with the given reason.
+    pub fn challenge(&self, reason: Reason) -> MutualChallenge {
+        MutualChallenge::Init {
+            parameters: self.parameters.clone(),
+            reason,
+        }
+    }
+
+    /// Ends a session, e.g. when the user logs out.
+    pub fn remove_session(&mut self, sid: &str) {
+        self.sessions.remove(sid);
+    }
+
+    /// Processes the client's credentials. `validation_value` is the value for the exchange's
+    /// [`super::Validation`] method, and `lookup` returns the password verifier for a username,
+    /// if the user exists.
+    ///
+    /// To avoid revealing which users exist, unknown users get a key derived from a random
+    /// verifier, and are only rejected (with `auth-failed`) after `req-VFY-C`.
+    pub fn respond<F>(
+        &mut self,
+        authorization: &MutualAuthorization,
+        validation_value: &str,
+        lookup: F,
+    ) -> MutualOutcome
+    where
+        F: FnOnce(&str) -> Option<PasswordVerifier>,
+    {
+        self.expire_sessions();
+        match *authorization {
+            MutualAuthorization::KexC1(ref kex) => self.key_exchange(kex, lookup),
+            MutualAuthorization::VfyC(ref vfy) => self.verify(vfy, validation_value),
+        }
+    }
+
+    /// Forgets sessions that have expired. They all have the same lifetime, so they expire in the
+    /// order that they were established.
+    fn expire_sessions(&mut self) {
+        while let Some(sid) = self.order.front() {
+            if self
+                .sessions
+                .get(sid)
+                .is_some_and(|session| session.established.elapsed() < self.lifetime)
+            {
+                break;
+            }
+            if let Some(sid) = self.order.pop_front() {
+                self.sessions.remove(&sid);
+            }
+        }
+    }
+
+    fn insert_session(&mut self, sid: String, session: Session) {
+        while self.sessions.len() >= self.max_sessions {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.sessions.remove(&oldest);
+        }
+        // Removed sessions are only dropped from the front of the queue, so compact it once it is
+        // mostly made up of them.
+        if self.order.len() >= 2 * self.max_sessions {
+            let sessions = &self.sessions;
+            self.order.retain(|sid| sessions.contains_key(sid));
+        }
+        self.order.push_back(sid.clone());
+        self.sessions.insert(sid, session);
+    }
+
+    fn key_exchange<F>(&mut self, kex: &KexC1, lookup: F) -> MutualOutcome
+    where
+        F: FnOnce(&str) -> Option<PasswordVerifier>,
+    {
+        if kex.parameters != self.parameters {
+            return MutualOutcome::Rejected(self.challenge(Reason::InvalidParameters));
+        }
+        let Some(kc1) = kam3::element(&kex.kc1) else {
+            return MutualOutcome::Rejected(self.challenge(Reason::InvalidParameters));
+        };
+        let verifier = match lookup(&kex.user) {
+            Some(PasswordVerifier(verifier)) => verifier,
+            None => kam3::password_verifier(&kam3::random_exponent()),
+        };
+
+        let server_secret = kam3::random_exponent();
+        let ks1 = kam3::server_key(&verifier, &kc1, &server_secret);
+        let z = kam3::server_session_secret(&kc1, &ks1, &server_secret);
+        let sid = base16ct::lower::encode_string(&rand::random::<[u8; 16]>());
+        let challenge = MutualChallenge::KexS1(KexS1 {
+            parameters: self.parameters.clone(),
+            sid: sid.clone(),
+            ks1: kam3::octets(&ks1),
+            nc_max: self.nc_max,
+            nc_window: self.nc_window,
+            time: self.lifetime.as_secs(),
+            path: None,
+        });
+        self.insert_session(
+            sid,
+            Session {
+                username: kex.user.clone(),
+                kc1,
+                ks1,
+                z,
+                established: Instant::now(),
+                highest_nc: 0,
+                seen_nc: HashSet::new(),
+            },
+        );
+        MutualOutcome::Continue(challenge)
+    }
+
+    fn verify(&mut self, vfy: &VfyC, validation_value: &str) -> MutualOutcome {
+        if vfy.parameters != self.parameters {
+            return MutualOutcome::Rejected(self.challenge(Reason::InvalidParameters));
+        }
+        let Some(session) = self.sessions.get_mut(&vfy.sid) else {
+            return MutualOutcome::Rejected(self.challenge(Reason::StaleSession));
+        };
+
+        let expected = kam3::client_verifier(
+            &session.kc1,
+            &session.ks1,
+            &session.z,
+            vfy.nc,
+            validation_value,
+        );
+        if !bool::from(expected.ct_eq(vfy.vkc.as_slice())) {
+            self.sessions.remove(&vfy.sid);
+            return MutualOutcome::Rejected(self.challenge(Reason::AuthFailed));
+        }
+        if !session.use_nonce_count(vfy.nc, self.nc_max, self.nc_window) {
+            return MutualOutcome::Rejected(self.challenge(Reason::StaleSession));
+        }
+
+        MutualOutcome::Authenticated {
+            username: session.username.clone(),
+            vfy_s: VfyS {
+                sid: vfy.sid.clone(),
+                vks: kam3::server_verifier(
+                    &session.kc1,
+                    &session.ks1,
+                    &session.z,
+                    vfy.nc,
+                    validation_value,
+                )
+                .to_vec(),
+            },
+        }
+    }
+}
+
+/// The sessions are omitted, since their secrets authenticate requests.
+impl fmt::Debug for MutualServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MutualServer")
+            .field("parameters", &self.parameters)
+            .field("nc_max", &self.nc_max)
+            .field("nc_window", &self.nc_window)
+            .field("lifetime", &self.lifetime)
+            .field("max_sessions", &self.max_sessions)
+            .field("sessions", &self.sessions.len())
+            .finish_non_exhaustive()
+    }
+}
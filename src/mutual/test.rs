@@ -0,0 +1,391 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::{
+    KexS1, MutualAlgorithm, MutualAuthorization, MutualChallenge, MutualClient, MutualError,
+    MutualOutcome, MutualParameters, MutualServer, PasswordVerifier, Reason, Validation, VfyC,
+    VfyS,
+};
+use crate::challenge::{Challenge, WwwAuthenticate};
+use headers::authorization::Credentials;
+use headers::{Header, HeaderMapExt, HeaderValue};
+use http::HeaderMap;
+
+const HOST: &str = "https://example.com:443";
+
+fn parameters() -> MutualParameters {
+    MutualParameters {
+        algorithm: MutualAlgorithm::IsoKam3Dl2048Sha256,
+        validation: Validation::Host,
+        auth_scope: "example.com".to_owned(),
+        realm: "testrealm".to_owned(),
+    }
+}
+
+fn lookup(username: &str) -> Option<PasswordVerifier> {
+    (username == "alice").then(|| PasswordVerifier::new(&parameters(), "alice", "wonderland"))
+}
+
+fn key_exchange(server: &mut MutualServer, client: &MutualClient) -> KexS1 {
+    let authorization = MutualAuthorization::KexC1(client.kex_c1());
+    match server.respond(&authorization, HOST, lookup) {
+        MutualOutcome::Continue(MutualChallenge::KexS1(kex_s1)) => kex_s1,
+        outcome => panic!("Unexpected outcome: {:?}", outcome),
+    }
+}
+
+fn verify(server: &mut MutualServer, vfy_c: &VfyC) -> MutualOutcome {
+    server.respond(&MutualAuthorization::VfyC(vfy_c.clone()), HOST, lookup)
+}
+
+#[test]
+fn test_exchange() {
+    let mut server = MutualServer::new(parameters());
+    let client = MutualClient::new(parameters(), "alice", "wonderland");
+    let kex_s1 = key_exchange(&mut server, &client);
+    let mut session = client
+        .establish(&kex_s1)
+        .expect("Could not establish session");
+    assert_eq!(kex_s1.sid, session.sid());
+
+    for nc in 1..=3 {
+        let vfy_c = session.vfy_c(HOST).expect("Could not create req-VFY-C");
+        assert_eq!(nc, vfy_c.nc);
+        match verify(&mut server, &vfy_c) {
+            MutualOutcome::Authenticated { username, vfy_s } => {
+                assert_eq!("alice", username);
+                assert_eq!(Ok(()), session.verify(&vfy_s, nc, HOST));
+                assert_eq!(
+                    Err(MutualError::InvalidServerVerifier),
+                    session.verify(&vfy_s, nc + 1, HOST)
+                );
+            }
+            outcome => panic!("Unexpected outcome: {:?}", outcome),
+        }
+    }
+}
+
+#[test]
+fn test_debug_omits_secrets() {
+    let mut server = MutualServer::new(parameters());
+    let client = MutualClient::new(parameters(), "alice", "wonderland");
+    let debug = format!("{:?}", client);
+    assert!(debug.contains("alice"), "{}", debug);
+    assert!(!debug.contains("secret"), "{}", debug);
+    assert!(!debug.contains("kc1"), "{}", debug);
+
+    let kex_s1 = key_exchange(&mut server, &client);
+    let session = client
+        .establish(&kex_s1)
+        .expect("Could not establish session");
+    let debug = format!("{:?}", session);
+    assert!(debug.contains(&kex_s1.sid), "{}", debug);
+    assert!(!debug.contains("z:"), "{}", debug);
+    let debug = format!("{:?}", server);
+    assert!(!debug.contains(&kex_s1.sid), "{}", debug);
+}
+
+#[test]
+fn test_wrong_password() {
+    let mut server = MutualServer::new(parameters());
+    let client = MutualClient::new(parameters(), "alice", "looking-glass");
+    let kex_s1 = key_exchange(&mut server, &client);
+    let mut session = client
+        .establish(&kex_s1)
+        .expect("Could not establish session");
+    let vfy_c = session.vfy_c(HOST).expect("Could not create req-VFY-C");
+    assert_eq!(
+        MutualOutcome::Rejected(server.challenge(Reason::AuthFailed)),
+        verify(&mut server, &vfy_c)
+    );
+    // The session is discarded after a failure.
+    let vfy_c = session.vfy_c(HOST).expect("Could not create req-VFY-C");
+    assert_eq!(
+        MutualOutcome::Rejected(server.challenge(Reason::StaleSession)),
+        verify(&mut server, &vfy_c)
+    )
+}
+
+#[test]
+fn test_unknown_user_fails_after_key_exchange() {
+    let mut server = MutualServer::new(parameters());
+    let client = MutualClient::new(parameters(), "mallory", "wonderland");
+    let kex_s1 = key_exchange(&mut server, &client);
+    let mut session = client
+        .establish(&kex_s1)
+        .expect("Could not establish session");
+    let vfy_c = session.vfy_c(HOST).expect("Could not create req-VFY-C");
+    assert_eq!(
+        MutualOutcome::Rejected(server.challenge(Reason::AuthFailed)),
+        verify(&mut server, &vfy_c)
+    )
+}
+
+#[test]
+fn test_wrong_validation_value() {
+    let mut server = MutualServer::new(parameters());
+    let client = MutualClient::new(parameters(), "alice", "wonderland");
+    let kex_s1 = key_exchange(&mut server, &client);
+    let mut session = client
+        .establish(&kex_s1)
+        .expect("Could not establish session");
+    let vfy_c = session
+        .vfy_c("https://evil.example:443")
+        .expect("Could not create req-VFY-C");
+    assert_eq!(
+        MutualOutcome::Rejected(server.challenge(Reason::AuthFailed)),
+        verify(&mut server, &vfy_c)
+    )
+}
+
+#[test]
+fn test_nonce_count_replay_and_window() {
+    let mut server = MutualServer::new(parameters()).with_nonce_counts(10, 2);
+    let client = MutualClient::new(parameters(), "alice", "wonderland");
+    let kex_s1 = key_exchange(&mut server, &client);
+    assert_eq!((10, 2), (kex_s1.nc_max, kex_s1.nc_window));
+    let mut session = client
+        .establish(&kex_s1)
+        .expect("Could not establish session");
+    let requests: Vec<VfyC> = (0..10)
+        .map(|_| session.vfy_c(HOST).expect("Could not create req-VFY-C"))
+        .collect();
+    assert_eq!(
+        Err(MutualError::NonceCountExhausted),
+        session.vfy_c(HOST).map(|_| ())
+    );
+
+    let stale = MutualOutcome::Rejected(server.challenge(Reason::StaleSession));
+    assert!(matches!(
+        verify(&mut server, &requests[3]),
+        MutualOutcome::Authenticated { .. }
+    ));
+    // Replayed
+    assert_eq!(stale, verify(&mut server, &requests[3]));
+    // Within the window
+    assert!(matches!(
+        verify(&mut server, &requests[2]),
+        MutualOutcome::Authenticated { .. }
+    ));
+    // Outside of the window
+    assert_eq!(stale, verify(&mut server, &requests[1]));
+}
+
+#[test]
+fn test_session_lifetime() {
+    let mut server = MutualServer::new(parameters()).with_lifetime(std::time::Duration::ZERO);
+    let client = MutualClient::new(parameters(), "alice", "wonderland");
+    let kex_s1 = key_exchange(&mut server, &client);
+    let mut session = client
+        .establish(&kex_s1)
+        .expect("Could not establish session");
+    let vfy_c = session.vfy_c(HOST).expect("Could not create req-VFY-C");
+    assert_eq!(
+        MutualOutcome::Rejected(server.challenge(Reason::StaleSession)),
+        verify(&mut server, &vfy_c)
+    )
+}
+
+#[test]
+fn test_discards_oldest_sessions() {
+    let mut server = MutualServer::new(parameters()).with_max_sessions(2);
+    let client = MutualClient::new(parameters(), "alice", "wonderland");
+    let mut sessions: Vec<_> = (0..3)
+        .map(|_| {
+            let kex_s1 = key_exchange(&mut server, &client);
+            client
+                .clone()
+                .establish(&kex_s1)
+                .expect("Could not establish session")
+        })
+        .collect();
+    assert_eq!(2, server.session_count());
+
+    let vfy_c = sessions[0].vfy_c(HOST).expect("Could not create req-VFY-C");
+    assert_eq!(
+        MutualOutcome::Rejected(server.challenge(Reason::StaleSession)),
+        verify(&mut server, &vfy_c)
+    );
+    for session in &mut sessions[1..] {
+        let vfy_c = session.vfy_c(HOST).expect("Could not create req-VFY-C");
+        match verify(&mut server, &vfy_c) {
+            MutualOutcome::Authenticated { username, .. } => assert_eq!("alice", username),
+            outcome => panic!("Unexpected outcome: {:?}", outcome),
+        }
+    }
+}
+
+#[test]
+fn test_mismatched_parameters_and_invalid_keys() {
+    let mut server = MutualServer::new(parameters());
+    let invalid = MutualOutcome::Rejected(server.challenge(Reason::InvalidParameters));
+
+    let mut other_realm = parameters();
+    other_realm.realm = "other".to_owned();
+    let client = MutualClient::new(other_realm, "alice", "wonderland");
+    let authorization = MutualAuthorization::KexC1(client.kex_c1());
+    assert_eq!(invalid, server.respond(&authorization, HOST, lookup));
+
+    let client = MutualClient::new(parameters(), "alice", "wonderland");
+    let mut kex_c1 = client.kex_c1();
+    kex_c1.kc1 = vec![0; 255];
+    kex_c1.kc1.push(1);
+    let authorization = MutualAuthorization::KexC1(kex_c1);
+    assert_eq!(invalid, server.respond(&authorization, HOST, lookup));
+
+    let mut kex_s1 = key_exchange(&mut server, &client);
+    kex_s1.ks1 = vec![0xff; 256];
+    assert_eq!(
+        Some(MutualError::InvalidKey),
+        client.clone().establish(&kex_s1).err()
+    );
+    kex_s1.parameters.auth_scope = "example.org".to_owned();
+    assert_eq!(
+        Some(MutualError::ParameterMismatch),
+        client.establish(&kex_s1).err()
+    )
+}
+
+#[test]
+fn test_password_verifier_bytes() {
+    let verifier = PasswordVerifier::new(&parameters(), "alice", "wonderland");
+    let bytes = verifier.to_bytes();
+    assert_eq!(256, bytes.len());
+    assert_eq!(Some(verifier), PasswordVerifier::from_bytes(&bytes));
+    assert_eq!(None, PasswordVerifier::from_bytes(&[0; 256]));
+}
+
+#[test]
+fn test_init_challenge_header() {
+    let value = HeaderValue::from_static(
+        "Mutual version=1, algorithm=iso-kam3-dl-2048-sha256, validation=host, \
+         auth-scope=\"example.com\", realm=\"testrealm\", reason=initial",
+    );
+    let header = WwwAuthenticate::<MutualChallenge>::decode(&mut std::iter::once(&value))
+        .expect("Could not decode header");
+    assert_eq!(
+        MutualChallenge::Init {
+            parameters: parameters(),
+            reason: Reason::Initial,
+        },
+        header.0
+    );
+    assert_eq!(value, header.0.encode())
+}
+
+#[test]
+fn test_invalid_challenge_headers() {
+    for value in [
+        "Mutual version=2, algorithm=iso-kam3-dl-2048-sha256, validation=host, \
+         auth-scope=\"example.com\", realm=\"testrealm\", reason=initial",
+        "Mutual version=1, algorithm=iso-kam3-ec-p256-sha256, validation=host, \
+         auth-scope=\"example.com\", realm=\"testrealm\", reason=initial",
+        "Mutual version=1, algorithm=iso-kam3-dl-2048-sha256, validation=host, \
+         realm=\"testrealm\", reason=initial",
+        "Mutual version=1, algorithm=iso-kam3-dl-2048-sha256, validation=host, \
+         auth-scope=\"example.com\", realm=\"testrealm\", reason=bored",
+        "Mutual version=1, algorithm=iso-kam3-dl-2048-sha256, validation=host, \
+         auth-scope=\"example.com\", realm=\"testrealm\", sid=abc, ks1=\"AA==\", nc-max=-1, \
+         nc-window=1, time=1",
+    ] {
+        assert_eq!(None, MutualChallenge::decode(value), "{value}");
+    }
+}
+
+#[test]
+fn test_kex_s1_header_round_trip() {
+    let challenge = MutualChallenge::KexS1(KexS1 {
+        parameters: parameters(),
+        sid: "0123456789abcdef".to_owned(),
+        ks1: vec![1, 2, 3],
+        nc_max: 1000,
+        nc_window: 128,
+        time: 300,
+        path: Some("/a /b".to_owned()),
+    });
+    let mut headers = HeaderMap::new();
+    headers.typed_insert(WwwAuthenticate(challenge.clone()));
+    assert_eq!(
+        "Mutual version=1, algorithm=iso-kam3-dl-2048-sha256, validation=host, \
+         auth-scope=\"example.com\", realm=\"testrealm\", sid=0123456789abcdef, ks1=\"AQID\", \
+         nc-max=1000, nc-window=128, time=300, path=\"/a /b\"",
+        headers[http::header::WWW_AUTHENTICATE]
+    );
+    assert_eq!(
+        Some(WwwAuthenticate(challenge)),
+        headers.typed_get::<WwwAuthenticate<MutualChallenge>>()
+    )
+}
+
+#[test]
+fn test_authorization_header_round_trip() {
+    let client = MutualClient::new(parameters(), "alice", "wonderland");
+    let kex_c1 = MutualAuthorization::KexC1(client.kex_c1());
+    assert_eq!(
+        Some(kex_c1.clone()),
+        MutualAuthorization::decode(&kex_c1.encode())
+    );
+
+    let vfy_c = MutualAuthorization::VfyC(VfyC {
+        parameters: parameters(),
+        sid: "0123456789abcdef".to_owned(),
+        nc: 1,
+        vkc: vec![4, 5, 6],
+    });
+    assert_eq!(
+        HeaderValue::from_static(
+            "Mutual version=1, algorithm=iso-kam3-dl-2048-sha256, validation=host, \
+             auth-scope=\"example.com\", realm=\"testrealm\", sid=0123456789abcdef, nc=1, \
+             vkc=\"BAUG\""
+        ),
+        vfy_c.encode()
+    );
+    assert_eq!(
+        Some(vfy_c.clone()),
+        MutualAuthorization::decode(&vfy_c.encode())
+    )
+}
+
+#[test]
+fn test_authentication_info_header() {
+    let vfy_s = VfyS {
+        sid: "0123456789abcdef".to_owned(),
+        vks: vec![7, 8, 9],
+    };
+    let mut values = vec![];
+    vfy_s.encode(&mut values);
+    assert_eq!(
+        vec![HeaderValue::from_static(
+            "Mutual version=1, sid=0123456789abcdef, vks=\"BwgJ\""
+        )],
+        values
+    );
+    assert_eq!(
+        Ok(vfy_s.clone()),
+        VfyS::decode(&mut values.iter()).map_err(|_| ())
+    );
+    let without_scheme = HeaderValue::from_static("version=1, sid=0123456789abcdef, vks=\"BwgJ\"");
+    assert_eq!(
+        Ok(vfy_s),
+        VfyS::decode(&mut std::iter::once(&without_scheme)).map_err(|_| ())
+    )
+}
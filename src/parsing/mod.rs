@@ -1,4 +1,4 @@
-// Copyright (c) 2015, 2016, 2017, 2020, 2025 Mark Lee
+// Copyright (c) 2015, 2016, 2017, 2020, 2025, 2026 Mark Lee
 //
 // Permission is hereby granted, free of charge, to any person obtaining a copy
 // of this software and associated documentation files (the "Software"), to deal
@@ -20,12 +20,16 @@
 
 //! Utility functions to parse headers.
 
-use crate::parsing::fromheaders::from_comma_delimited;
+use http::HeaderValue;
 use percent_encoding::percent_decode;
 use std::collections::HashMap;
+use std::fmt::Write;
+use std::iter::Peekable;
+use std::str::Chars;
 use unicase::UniCase;
 
 pub(crate) mod fromheaders;
+mod test;
 pub mod test_helper;
 
 /// Represents a parameter of a Digest Authorization header.
@@ -44,8 +48,24 @@ impl DigestParameters {
         Self(vec![])
     }
 
-    /// Append a header parameter to a serialized header.
+    /// Append a header parameter to a serialized header. The value is percent-encoded where
+    /// necessary (`%` and control characters other than horizontal tab), so that
+    /// [`unraveled_map_value`] decodes it back to the same string.
     pub fn append(&mut self, key: &str, value: &str, quoted: bool) {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            if c == '%' || (c.is_ascii_control() && c != '\t') {
+                let _ = write!(escaped, "%{:02X}", c as u32);
+            } else {
+                escaped.push(c);
+            }
+        }
+        self.append_raw(key, &escaped, quoted);
+    }
+
+    /// Append a header parameter to a serialized header, without percent-encoding its value. This
+    /// is for values that are read with [`raw_map_value`].
+    pub fn append_raw(&mut self, key: &str, value: &str, quoted: bool) {
         self.0.push(DigestParameter {
             key: key.to_string(),
             value: value.to_string(),
@@ -66,35 +86,259 @@ impl std::fmt::Display for DigestParameters {
             write!(f, "{}=", param.key)?;
             if param.quoted {
                 write!(f, "\"")?;
-            }
-            write!(f, "{}", param.value)?;
-            if param.quoted {
+                for c in param.value.chars() {
+                    if c == '"' || c == '\\' {
+                        write!(f, "\\")?;
+                    }
+                    write!(f, "{}", c)?;
+                }
                 write!(f, "\"")?;
+            } else {
+                write!(f, "{}", param.value)?;
             }
         }
         Ok(())
     }
 }
 
-pub fn parse_parameters(s: &str) -> HashMap<UniCase<String>, String> {
-    let parameters: Vec<String> =
-        from_comma_delimited(s).expect("Could not parse header parameters");
-    let mut param_map: HashMap<UniCase<String>, String> = HashMap::with_capacity(parameters.len());
-    for parameter in parameters {
-        let parts: Vec<&str> = parameter.splitn(2, '=').collect();
-        param_map.insert(
-            UniCase::new(parts[0].trim().to_owned()),
-            parts[1].trim().trim_matches('"').to_owned(),
-        );
+fn is_tchar(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c)
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+}
+
+fn parse_quoted_string(chars: &mut Peekable<Chars>) -> Result<String, headers::Error> {
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(value),
+            Some('\\') => value.push(chars.next().ok_or_else(headers::Error::invalid)?),
+            Some(c) => value.push(c),
+            None => return Err(headers::Error::invalid()),
+        }
+    }
+}
+
+/// Parses a comma-delimited list of authentication parameters, as defined in
+/// [RFC 7235, section 2.1](https://tools.ietf.org/html/rfc7235#section-2.1).
+///
+/// Parameter names are case-insensitive, and quoted values are unescaped. Unquoted values are
+/// accepted leniently (anything up to the next comma or whitespace), as some clients do not quote
+/// values that contain characters outside of the `token` grammar. Each parameter may only occur
+/// once.
+///
+/// ## ABNF
+///
+/// ```text
+/// auth-param = token BWS "=" BWS ( token / quoted-string )
+/// ```
+pub fn parse_parameters(s: &str) -> Result<HashMap<UniCase<String>, String>, headers::Error> {
+    let mut param_map: HashMap<UniCase<String>, String> = HashMap::new();
+    let mut chars = s.chars().peekable();
+    loop {
+        while chars
+            .next_if(|c| *c == ',' || *c == ' ' || *c == '\t')
+            .is_some()
+        {}
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|c| is_tchar(*c)) {
+            key.push(c);
+        }
+        skip_whitespace(&mut chars);
+        if key.is_empty() || chars.next() != Some('=') {
+            return Err(headers::Error::invalid());
+        }
+        skip_whitespace(&mut chars);
+
+        let value = if chars.next_if_eq(&'"').is_some() {
+            parse_quoted_string(&mut chars)?
+        } else {
+            let mut value = String::new();
+            while let Some(c) = chars.next_if(|c| *c != ',' && *c != ' ' && *c != '\t' && *c != '"')
+            {
+                value.push(c);
+            }
+            if value.is_empty() {
+                return Err(headers::Error::invalid());
+            }
+            value
+        };
+
+        skip_whitespace(&mut chars);
+        if chars.next_if(|c| *c != ',').is_some() {
+            return Err(headers::Error::invalid());
+        }
+        if param_map.insert(UniCase::new(key), value).is_some() {
+            return Err(headers::Error::invalid());
+        }
+    }
+
+    Ok(param_map)
+}
+
+fn skip_whitespace_at(bytes: &[u8], mut position: usize) -> usize {
+    while position < bytes.len() && (bytes[position] == b' ' || bytes[position] == b'\t') {
+        position += 1;
     }
+    position
+}
 
-    param_map
+fn is_token68_char(c: char) -> bool {
+    is_tchar(c) || c == '/'
 }
 
+/// Splits a header value that may carry several challenges (i.e., `WWW-Authenticate`) into the
+/// individual challenges, each starting with its scheme, as defined in
+/// [RFC 7235, section 4.1](https://tools.ietf.org/html/rfc7235#section-4.1).
+///
+/// ## ABNF
+///
+/// ```text
+/// WWW-Authenticate = 1#challenge
+/// challenge        = auth-scheme [ 1*SP ( token68 / #auth-param ) ]
+/// ```
+pub fn split_challenges(s: &str) -> Result<Vec<&str>, headers::Error> {
+    let bytes = s.as_bytes();
+    let mut challenges = vec![];
+    let mut challenge_start: Option<usize> = None;
+    let mut challenge_end = 0;
+    let mut seen_parameters = false;
+    let mut seen_comma = false;
+    let mut position = 0;
+    loop {
+        while position < bytes.len() && matches!(bytes[position], b' ' | b'\t' | b',') {
+            seen_comma |= bytes[position] == b',';
+            position += 1;
+        }
+        if position == bytes.len() {
+            break;
+        }
+
+        let token_start = position;
+        while position < bytes.len() && is_token68_char(bytes[position] as char) {
+            position += 1;
+        }
+        if position == token_start {
+            return Err(headers::Error::invalid());
+        }
+        let token_end = position;
+        let after_token = skip_whitespace_at(bytes, token_end);
+
+        if after_token < bytes.len() && bytes[after_token] == b'=' {
+            // Either an auth-param, or a token68 with padding.
+            if challenge_start.is_none() {
+                return Err(headers::Error::invalid());
+            }
+            let mut padding_end = after_token;
+            while padding_end < bytes.len() && bytes[padding_end] == b'=' {
+                padding_end += 1;
+            }
+            let after_padding = skip_whitespace_at(bytes, padding_end);
+            if !seen_parameters
+                && !seen_comma
+                && (after_padding == bytes.len() || bytes[after_padding] == b',')
+            {
+                position = padding_end;
+            } else {
+                position = skip_whitespace_at(bytes, after_token + 1);
+                if position < bytes.len() && bytes[position] == b'"' {
+                    position += 1;
+                    loop {
+                        match bytes.get(position) {
+                            Some(b'"') => break,
+                            Some(b'\\') => position += 2,
+                            Some(_) => position += 1,
+                            None => return Err(headers::Error::invalid()),
+                        }
+                    }
+                    position += 1;
+                    if position > bytes.len() {
+                        return Err(headers::Error::invalid());
+                    }
+                } else {
+                    while position < bytes.len()
+                        && !matches!(bytes[position], b',' | b' ' | b'\t' | b'"')
+                    {
+                        position += 1;
+                    }
+                }
+            }
+            seen_parameters = true;
+            challenge_end = position;
+        } else if challenge_start.is_some() && !seen_parameters && !seen_comma {
+            // A token68 without padding.
+            seen_parameters = true;
+            challenge_end = token_end;
+        } else {
+            // The scheme of a new challenge.
+            if let Some(start) = challenge_start {
+                challenges.push(&s[start..challenge_end]);
+            }
+            challenge_start = Some(token_start);
+            challenge_end = token_end;
+            seen_parameters = false;
+            seen_comma = false;
+        }
+    }
+
+    if let Some(start) = challenge_start {
+        challenges.push(&s[start..challenge_end]);
+    }
+    Ok(challenges)
+}
+
+/// Removes a (case-insensitive) authentication scheme from the start of a header value, returning
+/// the rest of the value. Returns `None` if the value uses a different scheme.
+pub fn strip_scheme<'a>(value: &'a str, scheme: &str) -> Option<&'a str> {
+    let rest = value.get(scheme.len()..)?;
+    if !value[..scheme.len()].eq_ignore_ascii_case(scheme) {
+        return None;
+    }
+    if rest.is_empty() {
+        Some(rest)
+    } else if rest.starts_with(' ') {
+        Some(rest.trim_start_matches(' '))
+    } else {
+        None
+    }
+}
+
+/// Converts a serialized header into a `HeaderValue`, percent-encoding any characters that are
+/// not allowed in one (i.e., control characters other than horizontal tab). Values appended with
+/// [`DigestParameters::append`] are already percent-encoded, so this only affects raw values
+/// (e.g. request URIs, which cannot contain control characters when valid).
+pub fn to_header_value(serialized: &str) -> HeaderValue {
+    let mut escaped = String::with_capacity(serialized.len());
+    for c in serialized.chars() {
+        if c.is_ascii_control() && c != '\t' {
+            let _ = write!(escaped, "%{:02X}", c as u32);
+        } else {
+            escaped.push(c);
+        }
+    }
+    // Every remaining character is valid in a header value, so this never falls back.
+    HeaderValue::from_str(&escaped).unwrap_or_else(|_| HeaderValue::from_static(""))
+}
+
+/// Looks up a parameter value by its case-insensitive name, and percent-decodes it. Returns `None`
+/// if the decoded value is not valid UTF-8.
 pub fn unraveled_map_value(map: &HashMap<UniCase<String>, String>, key: &str) -> Option<String> {
-    let value = map.get(&UniCase::new(key.to_owned()))?;
+    let value = raw_map_value(map, key)?;
     match percent_decode(value.as_bytes()).decode_utf8() {
         Ok(string) => Some(string.into_owned()),
         Err(_) => None,
     }
 }
+
+/// Looks up a parameter value by its case-insensitive name, without percent-decoding it. This is
+/// for values that are percent-encoded in their own right, such as RFC 5987 extended values and
+/// request URIs.
+pub fn raw_map_value(map: &HashMap<UniCase<String>, String>, key: &str) -> Option<String> {
+    map.get(&UniCase::new(key.to_owned())).cloned()
+}
@@ -0,0 +1,74 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::{split_challenges, strip_scheme};
+
+#[test]
+fn test_split_challenges() {
+    // From: RFC 7235, Section 4.1
+    let value = "Newauth realm=\"apps\", type=1, title=\"Login to \\\"apps\\\"\", \
+                 Basic realm=\"simple\"";
+    assert_eq!(
+        Ok(vec![
+            "Newauth realm=\"apps\", type=1, title=\"Login to \\\"apps\\\"\"",
+            "Basic realm=\"simple\""
+        ]),
+        split_challenges(value).map_err(|_| ())
+    );
+}
+
+#[test]
+fn test_split_challenges_with_token68_and_bare_schemes() {
+    let value = "Basic, Bearer abc/def==, Negotiate YII+a=, Digest realm=\"a, b\"";
+    assert_eq!(
+        Ok(vec![
+            "Basic",
+            "Bearer abc/def==",
+            "Negotiate YII+a=",
+            "Digest realm=\"a, b\""
+        ]),
+        split_challenges(value).map_err(|_| ())
+    );
+}
+
+#[test]
+fn test_split_challenges_with_unterminated_quoted_string() {
+    assert!(split_challenges("Digest realm=\"abc").is_err());
+    assert!(split_challenges("Digest realm=\"abc\\").is_err());
+}
+
+#[test]
+fn test_split_challenges_without_scheme() {
+    assert!(split_challenges("realm=\"abc\"").is_err());
+}
+
+#[test]
+fn test_strip_scheme() {
+    assert_eq!(
+        Some("realm=\"a\""),
+        strip_scheme("digest  realm=\"a\"", "Digest")
+    );
+    assert_eq!(Some(""), strip_scheme("Bearer", "Bearer"));
+    assert_eq!(None, strip_scheme("Bearerx", "Bearer"));
+    assert_eq!(None, strip_scheme("Basic abc", "Bearer"));
+    assert_eq!(None, strip_scheme("Dig", "Digest"));
+}
@@ -0,0 +1,124 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! The client side of the SCRAM exchange.
+
+use super::{
+    ClientFinalMessage, ClientFirstMessage, MINIMUM_ITERATIONS, ScramError, ServerFinalMessage,
+    ServerFirstMessage, auth_message, client_key, hmac, random_string, salted_password, xor,
+};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use subtle::ConstantTimeEq;
+
+/// The client's state before it receives the [`ServerFirstMessage`].
+#[derive(Clone)]
+pub struct ClientSession {
+    password: String,
+    first: ClientFirstMessage,
+}
+
+impl ClientSession {
+    /// Starts an exchange with a random client nonce.
+    pub fn new(username: &str, password: &str) -> ClientSession {
+        Self::with_nonce(username, password, &random_string(18))
+    }
+
+    /// Starts an exchange with the given client nonce, which must be printable ASCII without
+    /// commas.
+    pub fn with_nonce(username: &str, password: &str, nonce: &str) -> ClientSession {
+        ClientSession {
+            password: password.to_owned(),
+            first: ClientFirstMessage::new(username, nonce),
+        }
+    }
+
+    /// The message to send in the first `Authorization` header.
+    pub fn client_first(&self) -> &ClientFirstMessage {
+        &self.first
+    }
+
+    /// Computes the client proof for the server's challenge. Returns the message to send in the
+    /// second `Authorization` header, and what is needed to verify the server's final message.
+    pub fn client_final(
+        self,
+        server_first: &ServerFirstMessage,
+    ) -> Result<(ClientFinalMessage, ServerSignatureVerifier), ScramError> {
+        if server_first.nonce.len() <= self.first.nonce.len()
+            || !server_first.nonce.starts_with(&self.first.nonce)
+        {
+            return Err(ScramError::NonceMismatch);
+        }
+        if server_first.iterations < MINIMUM_ITERATIONS {
+            return Err(ScramError::TooFewIterations(server_first.iterations));
+        }
+
+        let salted = salted_password(&self.password, &server_first.salt, server_first.iterations);
+        let client_key = client_key(&salted);
+        let stored_key: [u8; 32] = Sha256::digest(client_key).into();
+        let mut client_final = ClientFinalMessage {
+            channel_binding: self.first.gs2_header().as_bytes().to_vec(),
+            nonce: server_first.nonce.clone(),
+            proof: vec![],
+        };
+        let auth_message = auth_message(&self.first, server_first, &client_final);
+        let client_signature = hmac(&stored_key, auth_message.as_bytes());
+        client_final.proof = xor(&client_key, &client_signature);
+
+        let server_key = hmac(&salted, b"Server Key");
+        Ok((
+            client_final,
+            ServerSignatureVerifier {
+                server_signature: hmac(&server_key, auth_message.as_bytes()),
+            },
+        ))
+    }
+}
+
+/// The password is omitted.
+impl fmt::Debug for ClientSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientSession")
+            .field("first", &self.first)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Verifies the [`ServerFinalMessage`], which completes the exchange.
+#[derive(Clone, Debug)]
+pub struct ServerSignatureVerifier {
+    server_signature: [u8; 32],
+}
+
+impl ServerSignatureVerifier {
+    /// Checks that the server knows the user's verifier.
+    pub fn verify(&self, server_final: &ServerFinalMessage) -> Result<(), ScramError> {
+        match *server_final {
+            ServerFinalMessage::Verifier(ref signature) => {
+                if bool::from(self.server_signature.ct_eq(signature.as_slice())) {
+                    Ok(())
+                } else {
+                    Err(ScramError::InvalidServerSignature)
+                }
+            }
+            ServerFinalMessage::Error(ref error) => Err(ScramError::ServerError(error.clone())),
+        }
+    }
+}
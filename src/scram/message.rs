@@ -0,0 +1,333 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! The SCRAM messages exchanged in the `data` parameter, as defined in
+//! [RFC 5802, section 7](https://tools.ietf.org/html/rfc5802#section-7).
+
+use super::ScramError;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use std::fmt;
+use std::str::FromStr;
+
+/// The GS2 header used when channel binding is not supported, which is always the case for HTTP.
+const GS2_HEADER: &str = "n,,";
+
+fn escape_saslname(name: &str) -> String {
+    name.replace('=', "=3D").replace(',', "=2C")
+}
+
+fn unescape_saslname(name: &str) -> Result<String, ScramError> {
+    let mut unescaped = String::with_capacity(name.len());
+    let mut rest = name;
+    while let Some(position) = rest.find('=') {
+        unescaped.push_str(&rest[..position]);
+        rest = &rest[position..];
+        if rest.starts_with("=2C") {
+            unescaped.push(',');
+        } else if rest.starts_with("=3D") {
+            unescaped.push('=');
+        } else {
+            return Err(ScramError::InvalidMessage);
+        }
+        rest = &rest[3..];
+    }
+    unescaped.push_str(rest);
+    Ok(unescaped)
+}
+
+fn is_valid_nonce(nonce: &str) -> bool {
+    !nonce.is_empty()
+        && nonce
+            .bytes()
+            .all(|b| (0x21..0x7f).contains(&b) && b != b',')
+}
+
+/// Splits a message into its attributes, checking that each one has the form `x=value`.
+fn attributes(message: &str) -> Result<Vec<(char, &str)>, ScramError> {
+    message
+        .split(',')
+        .map(|attribute| {
+            let mut chars = attribute.chars();
+            match (chars.next(), chars.next()) {
+                (Some(name), Some('=')) if name.is_ascii_alphabetic() => {
+                    Ok((name, &attribute[2..]))
+                }
+                _ => Err(ScramError::InvalidMessage),
+            }
+        })
+        .collect()
+}
+
+/// Returns the value of the attribute at `index`, which must have the given name.
+fn attribute<'a>(
+    attributes: &[(char, &'a str)],
+    index: usize,
+    name: char,
+) -> Result<&'a str, ScramError> {
+    match attributes.get(index) {
+        Some((actual, value)) if *actual == name => Ok(value),
+        _ => Err(ScramError::InvalidMessage),
+    }
+}
+
+fn decode_base64(value: &str) -> Result<Vec<u8>, ScramError> {
+    BASE64.decode(value).map_err(|_| ScramError::InvalidMessage)
+}
+
+/// The first message sent by the client.
+///
+/// ## ABNF
+///
+/// ```text
+/// client-first-message      = gs2-header client-first-message-bare
+/// gs2-header                = gs2-cbind-flag "," [ authzid ] ","
+/// client-first-message-bare = [reserved-mext ","] username "," nonce ["," extensions]
+/// ```
+///
+/// An authzid is rejected with [`ScramError::AuthzidNotSupported`], since the user would be
+/// authenticated without checking whether they may act as someone else.
+///
+/// The message is signed as it was received, including any extensions, which are otherwise
+/// ignored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClientFirstMessage {
+    gs2_header: String,
+    bare: String,
+    /// The name of the user to authenticate as
+    pub username: String,
+    /// The client's part of the nonce
+    pub nonce: String,
+}
+
+impl ClientFirstMessage {
+    /// Creates a message with the given username and client nonce.
+    pub fn new(username: &str, nonce: &str) -> ClientFirstMessage {
+        ClientFirstMessage {
+            gs2_header: GS2_HEADER.to_owned(),
+            bare: format!("n={},r={}", escape_saslname(username), nonce),
+            username: username.to_owned(),
+            nonce: nonce.to_owned(),
+        }
+    }
+
+    /// The GS2 header, which the client echoes (base64-encoded) in the `c` attribute of its final
+    /// message.
+    pub fn gs2_header(&self) -> &str {
+        &self.gs2_header
+    }
+
+    /// The message without the GS2 header, exactly as it was sent, which is part of the signed
+    /// `AuthMessage`.
+    pub fn bare(&self) -> &str {
+        &self.bare
+    }
+}
+
+impl FromStr for ClientFirstMessage {
+    type Err = ScramError;
+
+    fn from_str(s: &str) -> Result<ClientFirstMessage, ScramError> {
+        let (flag, rest) = s.split_once(',').ok_or(ScramError::InvalidMessage)?;
+        match flag {
+            "n" | "y" => (),
+            _ if flag.starts_with("p=") => return Err(ScramError::ChannelBindingNotSupported),
+            _ => return Err(ScramError::InvalidMessage),
+        }
+        let (authzid, bare) = rest.split_once(',').ok_or(ScramError::InvalidMessage)?;
+        if authzid.starts_with("a=") {
+            return Err(ScramError::AuthzidNotSupported);
+        } else if !authzid.is_empty() {
+            return Err(ScramError::InvalidMessage);
+        }
+
+        let attributes = attributes(bare)?;
+        let username = unescape_saslname(attribute(&attributes, 0, 'n')?)?;
+        let nonce = attribute(&attributes, 1, 'r')?;
+        if username.is_empty() || !is_valid_nonce(nonce) {
+            return Err(ScramError::InvalidMessage);
+        }
+
+        Ok(ClientFirstMessage {
+            gs2_header: s[..s.len() - bare.len()].to_owned(),
+            bare: bare.to_owned(),
+            username,
+            nonce: nonce.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for ClientFirstMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.gs2_header, self.bare())
+    }
+}
+
+/// The first message sent by the server.
+///
+/// ## ABNF
+///
+/// ```text
+/// server-first-message = [reserved-mext ","] nonce "," salt "," iteration-count ["," extensions]
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServerFirstMessage {
+    /// The client's nonce followed by the server's part of the nonce
+    pub nonce: String,
+    /// The salt used to derive the salted password
+    pub salt: Vec<u8>,
+    /// The number of PBKDF2 iterations used to derive the salted password
+    pub iterations: u32,
+}
+
+impl FromStr for ServerFirstMessage {
+    type Err = ScramError;
+
+    fn from_str(s: &str) -> Result<ServerFirstMessage, ScramError> {
+        let attributes = attributes(s)?;
+        let nonce = attribute(&attributes, 0, 'r')?;
+        if !is_valid_nonce(nonce) {
+            return Err(ScramError::InvalidMessage);
+        }
+        let salt = decode_base64(attribute(&attributes, 1, 's')?)?;
+        let iterations = attribute(&attributes, 2, 'i')?;
+        if !iterations.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ScramError::InvalidMessage);
+        }
+
+        Ok(ServerFirstMessage {
+            nonce: nonce.to_owned(),
+            salt,
+            iterations: iterations.parse().map_err(|_| ScramError::InvalidMessage)?,
+        })
+    }
+}
+
+impl fmt::Display for ServerFirstMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "r={},s={},i={}",
+            self.nonce,
+            BASE64.encode(&self.salt),
+            self.iterations
+        )
+    }
+}
+
+/// The final message sent by the client.
+///
+/// ## ABNF
+///
+/// ```text
+/// client-final-message-without-proof = channel-binding "," nonce ["," extensions]
+/// client-final-message               = client-final-message-without-proof "," proof
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClientFinalMessage {
+    /// The GS2 header from the client's first message (there is no channel binding data)
+    pub channel_binding: Vec<u8>,
+    /// The combined nonce from the server's first message
+    pub nonce: String,
+    /// `ClientKey XOR ClientSignature`
+    pub proof: Vec<u8>,
+}
+
+impl ClientFinalMessage {
+    /// The message without the proof, which is part of the signed `AuthMessage`.
+    pub fn without_proof(&self) -> String {
+        format!(
+            "c={},r={}",
+            BASE64.encode(&self.channel_binding),
+            self.nonce
+        )
+    }
+}
+
+impl FromStr for ClientFinalMessage {
+    type Err = ScramError;
+
+    fn from_str(s: &str) -> Result<ClientFinalMessage, ScramError> {
+        let attributes = attributes(s)?;
+        let channel_binding = decode_base64(attribute(&attributes, 0, 'c')?)?;
+        let nonce = attribute(&attributes, 1, 'r')?;
+        if !is_valid_nonce(nonce) {
+            return Err(ScramError::InvalidMessage);
+        }
+        let proof = decode_base64(attribute(&attributes, attributes.len() - 1, 'p')?)?;
+
+        Ok(ClientFinalMessage {
+            channel_binding,
+            nonce: nonce.to_owned(),
+            proof,
+        })
+    }
+}
+
+impl fmt::Display for ClientFinalMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{},p={}",
+            self.without_proof(),
+            BASE64.encode(&self.proof)
+        )
+    }
+}
+
+/// The final message sent by the server.
+///
+/// ## ABNF
+///
+/// ```text
+/// server-final-message = (server-error / verifier) ["," extensions]
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum ServerFinalMessage {
+    /// `v` - the server signature, proving that the server also knows the user's credentials.
+    Verifier(Vec<u8>),
+    /// `e` - the reason that authentication failed, e.g. `invalid-proof`.
+    Error(String),
+}
+
+impl FromStr for ServerFinalMessage {
+    type Err = ScramError;
+
+    fn from_str(s: &str) -> Result<ServerFinalMessage, ScramError> {
+        match attributes(s)?.first() {
+            Some(('v', verifier)) => Ok(ServerFinalMessage::Verifier(decode_base64(verifier)?)),
+            Some(('e', error)) if !error.is_empty() => {
+                Ok(ServerFinalMessage::Error((*error).to_owned()))
+            }
+            _ => Err(ScramError::InvalidMessage),
+        }
+    }
+}
+
+impl fmt::Display for ServerFinalMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ServerFinalMessage::Verifier(ref verifier) => {
+                write!(f, "v={}", BASE64.encode(verifier))
+            }
+            ServerFinalMessage::Error(ref error) => write!(f, "e={}", error),
+        }
+    }
+}
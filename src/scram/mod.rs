@@ -0,0 +1,368 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! An implementation of the `SCRAM-SHA-256` authentication scheme, as defined in
+//! [RFC 7804](https://tools.ietf.org/html/rfc7804).
+//!
+//! Unlike Digest, the server only stores a salted, iterated verifier ([`ScramCredentials`]) which
+//! cannot be used to impersonate the user. Authentication takes two round trips, tied together by
+//! the `sid` parameter:
+//!
+//! 1. The client sends a [`ClientFirstMessage`] in the `data` parameter of the `Authorization`
+//!    header.
+//! 2. The server responds with a [`ServerFirstMessage`] and a session ID in the
+//!    `WWW-Authenticate` header ([`ScramChallenge`]).
+//! 3. The client sends a [`ClientFinalMessage`], which proves that it knows the password.
+//! 4. The server responds with a [`ServerFinalMessage`] in the `Authentication-Info` header
+//!    ([`ScramAuthenticationInfo`]), which proves that it knows the verifier.
+//!
+//! Usernames and passwords are used as given; callers should normalize them (e.g. to Unicode
+//! NFC) before using them here.
+
+use crate::challenge::Challenge;
+use crate::parsing::{
+    DigestParameters, parse_parameters, strip_scheme, to_header_value, unraveled_map_value,
+};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use headers::authorization::Credentials;
+use hmac::{Hmac, Mac};
+use http::HeaderValue;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+use unicase::UniCase;
+
+mod client;
+mod message;
+mod server;
+mod test;
+
+pub use client::{ClientSession, ServerSignatureVerifier};
+pub use message::{ClientFinalMessage, ClientFirstMessage, ServerFinalMessage, ServerFirstMessage};
+pub use server::{ScramOutcome, ScramServer, ServerSession};
+
+/// The authentication scheme name.
+pub const SCHEME: &str = "SCRAM-SHA-256";
+
+/// The minimum iteration count, as recommended by
+/// [RFC 7677, section 4](https://tools.ietf.org/html/rfc7677#section-4).
+pub const MINIMUM_ITERATIONS: u32 = 4096;
+
+/// Errors relating to the SCRAM exchange.
+#[derive(Debug, Error, PartialEq)]
+pub enum ScramError {
+    /// A SCRAM message could not be parsed.
+    #[error("Malformed SCRAM message")]
+    InvalidMessage,
+    /// The client requested channel binding, which is not available over HTTP.
+    #[error("Channel binding is not supported")]
+    ChannelBindingNotSupported,
+    /// The client asked to act as another user (with an authzid), which is not supported.
+    #[error("Authorization identities are not supported")]
+    AuthzidNotSupported,
+    /// The channel binding in the client's final message does not match its first message.
+    #[error("Channel binding does not match")]
+    ChannelBindingMismatch,
+    /// The nonce does not extend (or match) the one from the previous message.
+    #[error("Nonce does not match")]
+    NonceMismatch,
+    /// The iteration count is lower than [`MINIMUM_ITERATIONS`].
+    #[error("Iteration count {0} is too low")]
+    TooFewIterations(u32),
+    /// The client's proof is invalid, i.e. the password is wrong.
+    #[error("Invalid client proof")]
+    InvalidProof,
+    /// The server's signature is invalid, i.e. the server does not know the verifier.
+    #[error("Invalid server signature")]
+    InvalidServerSignature,
+    /// The server reported an error in its final message.
+    #[error("Server error: {0}")]
+    ServerError(String),
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().into()
+}
+
+fn salted_password(password: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+    let mut salted = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut salted);
+    salted
+}
+
+fn client_key(salted_password: &[u8]) -> [u8; 32] {
+    hmac(salted_password, b"Client Key")
+}
+
+fn auth_message(
+    client_first: &ClientFirstMessage,
+    server_first: &ServerFirstMessage,
+    client_final: &ClientFinalMessage,
+) -> String {
+    format!(
+        "{},{},{}",
+        client_first.bare(),
+        server_first,
+        client_final.without_proof()
+    )
+}
+
+fn xor(left: &[u8; 32], right: &[u8]) -> Vec<u8> {
+    left.iter().zip(right).map(|(l, r)| l ^ r).collect()
+}
+
+fn random_string(length: usize) -> String {
+    let bytes: Vec<u8> = (0..length).map(|_| rand::random()).collect();
+    BASE64.encode(bytes)
+}
+
+/// The verifier that the server stores for each user, instead of the password.
+///
+/// The [`fmt::Display`]/[`FromStr`] format is the one defined in
+/// [RFC 5803](https://tools.ietf.org/html/rfc5803):
+/// `SCRAM-SHA-256$<iterations>:<salt>$<StoredKey>:<ServerKey>`, with base64-encoded values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScramCredentials {
+    /// The salt used to derive the salted password
+    pub salt: Vec<u8>,
+    /// The number of PBKDF2 iterations used to derive the salted password
+    pub iterations: u32,
+    /// `H(ClientKey)`
+    pub stored_key: [u8; 32],
+    /// `HMAC(SaltedPassword, "Server Key")`
+    pub server_key: [u8; 32],
+}
+
+impl ScramCredentials {
+    /// Derives the verifier from a password, salt, and iteration count.
+    pub fn new(password: &str, salt: &[u8], iterations: u32) -> Result<Self, ScramError> {
+        if iterations < MINIMUM_ITERATIONS {
+            return Err(ScramError::TooFewIterations(iterations));
+        }
+        let salted = salted_password(password, salt, iterations);
+        Ok(ScramCredentials {
+            salt: salt.to_vec(),
+            iterations,
+            stored_key: Sha256::digest(client_key(&salted)).into(),
+            server_key: hmac(&salted, b"Server Key"),
+        })
+    }
+
+    /// Derives the verifier from a password, using a random 16-byte salt.
+    pub fn generate(password: &str, iterations: u32) -> Result<Self, ScramError> {
+        let salt: [u8; 16] = rand::random();
+        Self::new(password, &salt, iterations)
+    }
+}
+
+impl FromStr for ScramCredentials {
+    type Err = ScramError;
+
+    fn from_str(s: &str) -> Result<ScramCredentials, ScramError> {
+        let rest = s
+            .strip_prefix(SCHEME)
+            .and_then(|rest| rest.strip_prefix('$'))
+            .ok_or(ScramError::InvalidMessage)?;
+        let (parameters, keys) = rest.split_once('$').ok_or(ScramError::InvalidMessage)?;
+        let (iterations, salt) = parameters
+            .split_once(':')
+            .ok_or(ScramError::InvalidMessage)?;
+        let (stored_key, server_key) = keys.split_once(':').ok_or(ScramError::InvalidMessage)?;
+        let decode_key = |key: &str| -> Result<[u8; 32], ScramError> {
+            BASE64
+                .decode(key)
+                .ok()
+                .and_then(|key| key.try_into().ok())
+                .ok_or(ScramError::InvalidMessage)
+        };
+
+        Ok(ScramCredentials {
+            salt: BASE64
+                .decode(salt)
+                .map_err(|_| ScramError::InvalidMessage)?,
+            iterations: iterations.parse().map_err(|_| ScramError::InvalidMessage)?,
+            stored_key: decode_key(stored_key)?,
+            server_key: decode_key(server_key)?,
+        })
+    }
+}
+
+impl fmt::Display for ScramCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}${}:{}${}:{}",
+            SCHEME,
+            self.iterations,
+            BASE64.encode(&self.salt),
+            BASE64.encode(self.stored_key),
+            BASE64.encode(self.server_key)
+        )
+    }
+}
+
+fn decode_data(parameters: &HashMap<UniCase<String>, String>) -> Option<Option<String>> {
+    match unraveled_map_value(parameters, "data") {
+        Some(data) => {
+            let decoded = BASE64.decode(data).ok()?;
+            Some(Some(String::from_utf8(decoded).ok()?))
+        }
+        None => Some(None),
+    }
+}
+
+fn append_parameters(
+    parameters: &mut DigestParameters,
+    realm: &Option<String>,
+    sid: &Option<String>,
+    data: &Option<String>,
+) {
+    if let Some(realm) = realm {
+        parameters.append("realm", realm, true);
+    }
+    if let Some(sid) = sid {
+        parameters.append("sid", sid, false);
+    }
+    if let Some(data) = data {
+        parameters.append("data", &BASE64.encode(data), false);
+    }
+}
+
+fn encode_with_scheme(parameters: DigestParameters) -> HeaderValue {
+    let parameters = parameters.to_string();
+    if parameters.is_empty() {
+        to_header_value(SCHEME)
+    } else {
+        to_header_value(&format!("{} {}", SCHEME, parameters))
+    }
+}
+
+/// `SCRAM-SHA-256` credentials, sent by the client in the `Authorization` header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScramAuthorization {
+    /// The protection space, sent with the client's first message
+    pub realm: Option<String>,
+    /// The session ID from the server's challenge, sent with the client's final message
+    pub sid: Option<String>,
+    /// The (base64-decoded) SCRAM message
+    pub data: String,
+}
+
+impl Credentials for ScramAuthorization {
+    const SCHEME: &'static str = SCHEME;
+
+    fn decode(value: &HeaderValue) -> Option<Self> {
+        let parameters = parse_parameters(strip_scheme(value.to_str().ok()?, SCHEME)?).ok()?;
+        Some(ScramAuthorization {
+            realm: unraveled_map_value(&parameters, "realm"),
+            sid: unraveled_map_value(&parameters, "sid"),
+            data: decode_data(&parameters)??,
+        })
+    }
+
+    fn encode(&self) -> HeaderValue {
+        let mut parameters = DigestParameters::new();
+        append_parameters(
+            &mut parameters,
+            &self.realm,
+            &self.sid,
+            &Some(self.data.clone()),
+        );
+        encode_with_scheme(parameters)
+    }
+}
+
+/// A `SCRAM-SHA-256` challenge, sent by the server in the `WWW-Authenticate` header.
+///
+/// The initial challenge only carries the `realm`; the challenge in response to the client's
+/// first message carries the `sid` and the [`ServerFirstMessage`] in `data`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScramChallenge {
+    /// The protection space
+    pub realm: Option<String>,
+    /// The session ID, which the client must echo in its final message
+    pub sid: Option<String>,
+    /// The (base64-decoded) SCRAM message
+    pub data: Option<String>,
+}
+
+impl Challenge for ScramChallenge {
+    const SCHEME: &'static str = SCHEME;
+
+    fn decode(value: &str) -> Option<Self> {
+        let parameters = parse_parameters(strip_scheme(value, SCHEME)?).ok()?;
+        Some(ScramChallenge {
+            realm: unraveled_map_value(&parameters, "realm"),
+            sid: unraveled_map_value(&parameters, "sid"),
+            data: decode_data(&parameters)?,
+        })
+    }
+
+    fn encode(&self) -> HeaderValue {
+        let mut parameters = DigestParameters::new();
+        append_parameters(&mut parameters, &self.realm, &self.sid, &self.data);
+        encode_with_scheme(parameters)
+    }
+}
+
+/// The `Authentication-Info` header sent by the server once the client is authenticated, which
+/// carries the [`ServerFinalMessage`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScramAuthenticationInfo {
+    /// The session ID
+    pub sid: Option<String>,
+    /// The (base64-decoded) SCRAM message
+    pub data: String,
+}
+
+impl headers::Header for ScramAuthenticationInfo {
+    fn name() -> &'static http::HeaderName {
+        static NAME: http::HeaderName = http::HeaderName::from_static("authentication-info");
+        &NAME
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or_else(headers::Error::invalid)?;
+        let parameters = parse_parameters(value.to_str().map_err(|_| headers::Error::invalid())?)?;
+        Ok(ScramAuthenticationInfo {
+            sid: unraveled_map_value(&parameters, "sid"),
+            data: decode_data(&parameters)
+                .flatten()
+                .ok_or_else(headers::Error::invalid)?,
+        })
+    }
+
+    fn encode<E>(&self, values: &mut E)
+    where
+        E: Extend<HeaderValue>,
+    {
+        let mut parameters = DigestParameters::new();
+        append_parameters(&mut parameters, &None, &self.sid, &Some(self.data.clone()));
+        values.extend(std::iter::once(to_header_value(&parameters.to_string())));
+    }
+}
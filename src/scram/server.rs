@@ -0,0 +1,309 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! The server side of the SCRAM exchange.
+
+use super::{
+    ClientFinalMessage, ClientFirstMessage, MINIMUM_ITERATIONS, ScramAuthenticationInfo,
+    ScramAuthorization, ScramChallenge, ScramCredentials, ScramError, ServerFinalMessage,
+    ServerFirstMessage, auth_message, hmac, random_string, xor,
+};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::time::{Duration, Instant};
+use subtle::ConstantTimeEq;
+
+/// The server's state between its first message and the client's final message.
+#[derive(Clone, Debug)]
+pub struct ServerSession {
+    client_first: ClientFirstMessage,
+    server_first: ServerFirstMessage,
+    credentials: ScramCredentials,
+}
+
+impl ServerSession {
+    /// Responds to the client's first message with a random server nonce.
+    pub fn new(client_first: ClientFirstMessage, credentials: ScramCredentials) -> ServerSession {
+        Self::with_nonce(client_first, credentials, &random_string(18))
+    }
+
+    /// Responds to the client's first message with the given server nonce, which must be
+    /// printable ASCII without commas.
+    pub fn with_nonce(
+        client_first: ClientFirstMessage,
+        credentials: ScramCredentials,
+        nonce: &str,
+    ) -> ServerSession {
+        let server_first = ServerFirstMessage {
+            nonce: format!("{}{}", client_first.nonce, nonce),
+            salt: credentials.salt.clone(),
+            iterations: credentials.iterations,
+        };
+        ServerSession {
+            client_first,
+            server_first,
+            credentials,
+        }
+    }
+
+    /// The name of the user being authenticated.
+    pub fn username(&self) -> &str {
+        &self.client_first.username
+    }
+
+    /// The message to send in the `WWW-Authenticate` header.
+    pub fn server_first(&self) -> &ServerFirstMessage {
+        &self.server_first
+    }
+
+    /// Verifies the client's proof. On success, returns the message to send in the
+    /// `Authentication-Info` header.
+    pub fn server_final(
+        &self,
+        client_final: &ClientFinalMessage,
+    ) -> Result<ServerFinalMessage, ScramError> {
+        if client_final.channel_binding != self.client_first.gs2_header().as_bytes() {
+            return Err(ScramError::ChannelBindingMismatch);
+        }
+        if client_final.nonce != self.server_first.nonce {
+            return Err(ScramError::NonceMismatch);
+        }
+
+        let auth_message = auth_message(&self.client_first, &self.server_first, client_final);
+        let client_signature = hmac(&self.credentials.stored_key, auth_message.as_bytes());
+        let client_key = xor(&client_signature, &client_final.proof);
+        let stored_key = Sha256::digest(&client_key);
+        if client_final.proof.len() != client_signature.len()
+            || !bool::from(stored_key.ct_eq(&self.credentials.stored_key))
+        {
+            return Err(ScramError::InvalidProof);
+        }
+
+        Ok(ServerFinalMessage::Verifier(
+            hmac(&self.credentials.server_key, auth_message.as_bytes()).to_vec(),
+        ))
+    }
+}
+
+/// What the server should respond with after receiving a [`ScramAuthorization`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScramOutcome {
+    /// Respond with `401 Unauthorized` and this challenge, which continues the exchange.
+    Continue(ScramChallenge),
+    /// The user is authenticated; respond with this `Authentication-Info` header.
+    Authenticated {
+        /// The name of the authenticated user
+        username: String,
+        /// The header carrying the server's signature
+        authentication_info: ScramAuthenticationInfo,
+    },
+    /// Respond with `401 Unauthorized` and this challenge, which restarts the exchange.
+    Rejected(ScramChallenge),
+}
+
+/// Tracks the exchanges in progress for a realm, keyed by their `sid`.
+///
+/// Exchanges that the client abandons are discarded after a timeout (60 seconds by default). At
+/// most 10,000 exchanges are tracked by default; when that many are in progress, the oldest one is
+/// discarded.
+///
+/// Users that do not exist are sent a salt and iteration count as if they did, as suggested by
+/// [RFC 5802, section 5.1](https://tools.ietf.org/html/rfc5802#section-5.1), so that the exchange
+/// only fails once the client's proof is checked. The salt is derived from the username with a
+/// secret key, so it is the same every time.
+pub struct ScramServer {
+    realm: String,
+    timeout: Duration,
+    max_sessions: usize,
+    secret: [u8; 32],
+    iterations: u32,
+    sessions: HashMap<String, (Instant, ServerSession)>,
+    order: VecDeque<String>,
+}
+
+impl ScramServer {
+    /// Creates a server for the given realm, with a random secret key.
+    pub fn new(realm: &str) -> ScramServer {
+        ScramServer {
+            realm: realm.to_owned(),
+            timeout: Duration::from_secs(60),
+            max_sessions: 10_000,
+            secret: rand::random(),
+            iterations: MINIMUM_ITERATIONS,
+            sessions: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Sets how long the server waits for the client's final message.
+    pub fn with_timeout(mut self, timeout: Duration) -> ScramServer {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets how many exchanges can be in progress at once (at least one).
+    pub fn with_max_sessions(mut self, max_sessions: usize) -> ScramServer {
+        self.max_sessions = max_sessions.max(1);
+        self
+    }
+
+    /// Sets the key that the salts of unknown users are derived from. It should be kept across
+    /// restarts and shared by every server for the realm, so that the salts do not change.
+    pub fn with_secret(mut self, secret: [u8; 32]) -> ScramServer {
+        self.secret = secret;
+        self
+    }
+
+    /// Sets the iteration count sent to unknown users ([`MINIMUM_ITERATIONS`] by default), which
+    /// should be the one that the credentials of existing users were derived with.
+    pub fn with_iterations(mut self, iterations: u32) -> ScramServer {
+        self.iterations = iterations;
+        self
+    }
+
+    /// Credentials for a user that does not exist, which no proof matches.
+    fn unknown_user(&self, username: &str) -> ScramCredentials {
+        let derive =
+            |label: &str| hmac(&self.secret, format!("{}\0{}", label, username).as_bytes());
+        ScramCredentials {
+            salt: derive("salt")[..16].to_vec(),
+            iterations: self.iterations,
+            stored_key: derive("stored key"),
+            server_key: derive("server key"),
+        }
+    }
+
+    /// Discards the exchanges that have timed out. Every exchange has the same timeout, so they
+    /// time out in the order that they were started.
+    fn expire_sessions(&mut self) {
+        while let Some(sid) = self.order.front() {
+            if self
+                .sessions
+                .get(sid)
+                .is_some_and(|(started, _)| started.elapsed() < self.timeout)
+            {
+                break;
+            }
+            if let Some(sid) = self.order.pop_front() {
+                self.sessions.remove(&sid);
+            }
+        }
+    }
+
+    fn insert_session(&mut self, sid: String, session: ServerSession) {
+        while self.sessions.len() >= self.max_sessions {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.sessions.remove(&oldest);
+        }
+        // Finished exchanges are only removed from the front of the queue, so compact it once
+        // it is mostly made up of them.
+        if self.order.len() >= 2 * self.max_sessions {
+            let sessions = &self.sessions;
+            self.order.retain(|sid| sessions.contains_key(sid));
+        }
+        self.order.push_back(sid.clone());
+        self.sessions.insert(sid, (Instant::now(), session));
+    }
+
+    /// The initial challenge, sent when the client has not authenticated.
+    pub fn challenge(&self) -> ScramChallenge {
+        ScramChallenge {
+            realm: Some(self.realm.clone()),
+            sid: None,
+            data: None,
+        }
+    }
+
+    /// Processes the client's credentials. `lookup` returns the verifier for a username, if the
+    /// user exists.
+    pub fn respond<F>(&mut self, authorization: &ScramAuthorization, lookup: F) -> ScramOutcome
+    where
+        F: FnOnce(&str) -> Option<ScramCredentials>,
+    {
+        self.expire_sessions();
+        match authorization.sid {
+            None => self.start(authorization, lookup),
+            Some(ref sid) => self.finish(sid, authorization),
+        }
+    }
+
+    fn start<F>(&mut self, authorization: &ScramAuthorization, lookup: F) -> ScramOutcome
+    where
+        F: FnOnce(&str) -> Option<ScramCredentials>,
+    {
+        if authorization
+            .realm
+            .as_ref()
+            .is_some_and(|realm| *realm != self.realm)
+        {
+            return ScramOutcome::Rejected(self.challenge());
+        }
+        let Ok(client_first) = authorization.data.parse::<ClientFirstMessage>() else {
+            return ScramOutcome::Rejected(self.challenge());
+        };
+        let credentials = lookup(&client_first.username)
+            .unwrap_or_else(|| self.unknown_user(&client_first.username));
+
+        let session = ServerSession::new(client_first, credentials);
+        let sid = base16ct::lower::encode_string(&rand::random::<[u8; 16]>());
+        let challenge = ScramChallenge {
+            realm: None,
+            sid: Some(sid.clone()),
+            data: Some(session.server_first().to_string()),
+        };
+        self.insert_session(sid, session);
+        ScramOutcome::Continue(challenge)
+    }
+
+    fn finish(&mut self, sid: &str, authorization: &ScramAuthorization) -> ScramOutcome {
+        let Some((_, session)) = self.sessions.remove(sid) else {
+            return ScramOutcome::Rejected(self.challenge());
+        };
+        let server_final = authorization
+            .data
+            .parse::<ClientFinalMessage>()
+            .and_then(|client_final| session.server_final(&client_final));
+        match server_final {
+            Ok(server_final) => ScramOutcome::Authenticated {
+                username: session.username().to_owned(),
+                authentication_info: ScramAuthenticationInfo {
+                    sid: Some(sid.to_owned()),
+                    data: server_final.to_string(),
+                },
+            },
+            Err(_) => ScramOutcome::Rejected(self.challenge()),
+        }
+    }
+}
+
+/// The secret key and the exchanges in progress are omitted.
+impl fmt::Debug for ScramServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScramServer")
+            .field("realm", &self.realm)
+            .field("timeout", &self.timeout)
+            .field("max_sessions", &self.max_sessions)
+            .field("iterations", &self.iterations)
+            .finish_non_exhaustive()
+    }
+}
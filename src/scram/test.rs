@@ -0,0 +1,602 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::{
+    ClientFinalMessage, ClientFirstMessage, ClientSession, ScramAuthenticationInfo,
+    ScramAuthorization, ScramChallenge, ScramCredentials, ScramError, ScramOutcome, ScramServer,
+    ServerFinalMessage, ServerFirstMessage, ServerSession, client_key, hmac, salted_password, xor,
+};
+use crate::challenge::{Challenge, WwwAuthenticate};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use headers::authorization::Credentials;
+use headers::{Authorization, Header, HeaderMapExt, HeaderValue};
+use http::HeaderMap;
+use sha2::{Digest, Sha256};
+use std::str::FromStr;
+
+// Test vectors from RFC 7677, Section 3. The RFC 7804 examples use the same exchange, but their
+// base64-encoded `data` values include a trailing newline, so they are re-encoded here.
+const CLIENT_NONCE: &str = "rOprNGfwEbeRWgbNEkqO";
+const SERVER_NONCE: &str = "%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0";
+const SALT: &str = "W22ZaJ0SNY7soEsUEjb6gQ==";
+const CLIENT_FIRST: &str = "n,,n=user,r=rOprNGfwEbeRWgbNEkqO";
+const SERVER_FIRST: &str =
+    "r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,s=W22ZaJ0SNY7soEsUEjb6gQ==,i=4096";
+const CLIENT_FINAL: &str = "c=biws,r=rOprNGfwEbeRWgbNEkqO%hvYDpWUa2RaTCAfuxFIlj)hNlF$k0,\
+                            p=dHzbZapWIk4jUhN+Ute9ytag9zjfMHgsqmmiz7AndVQ=";
+const SERVER_FINAL: &str = "v=6rriTRBi23WpRR/wtup+mMhUZUn/dB5nLTJRsjl95G4=";
+
+fn rfc7677_credentials() -> ScramCredentials {
+    let salt = BASE64.decode(SALT).expect("Could not decode salt");
+    ScramCredentials::new("pencil", &salt, 4096).expect("Could not derive credentials")
+}
+
+#[test]
+fn test_client_first_message() {
+    let session = ClientSession::with_nonce("user", "pencil", CLIENT_NONCE);
+    assert_eq!(CLIENT_FIRST, session.client_first().to_string());
+    assert_eq!(
+        Ok(session.client_first().clone()),
+        ClientFirstMessage::from_str(CLIENT_FIRST)
+    )
+}
+
+#[test]
+fn test_client_session_debug_omits_password() {
+    let session = ClientSession::with_nonce("user", "pencil", CLIENT_NONCE);
+    let debug = format!("{:?}", session);
+    assert!(debug.contains(CLIENT_NONCE), "{}", debug);
+    assert!(!debug.contains("pencil"), "{}", debug);
+}
+
+#[test]
+fn test_rfc7677_exchange() {
+    let client = ClientSession::with_nonce("user", "pencil", CLIENT_NONCE);
+    let client_first =
+        ClientFirstMessage::from_str(&client.client_first().to_string()).expect("client-first");
+    let server = ServerSession::with_nonce(client_first, rfc7677_credentials(), SERVER_NONCE);
+    assert_eq!(SERVER_FIRST, server.server_first().to_string());
+
+    let server_first = ServerFirstMessage::from_str(SERVER_FIRST).expect("server-first");
+    let (client_final, verifier) = client
+        .client_final(&server_first)
+        .expect("Could not compute client proof");
+    assert_eq!(CLIENT_FINAL, client_final.to_string());
+
+    let client_final = ClientFinalMessage::from_str(CLIENT_FINAL).expect("client-final");
+    let server_final = server
+        .server_final(&client_final)
+        .expect("Could not verify client proof");
+    assert_eq!(SERVER_FINAL, server_final.to_string());
+
+    let server_final = ServerFinalMessage::from_str(SERVER_FINAL).expect("server-final");
+    assert_eq!(Ok(()), verifier.verify(&server_final))
+}
+
+#[test]
+fn test_wrong_password() {
+    let client = ClientSession::with_nonce("user", "pen", CLIENT_NONCE);
+    let server = ServerSession::with_nonce(
+        client.client_first().clone(),
+        rfc7677_credentials(),
+        SERVER_NONCE,
+    );
+    let (client_final, _) = client
+        .client_final(server.server_first())
+        .expect("Could not compute client proof");
+    assert_eq!(
+        Err(ScramError::InvalidProof),
+        server.server_final(&client_final)
+    )
+}
+
+#[test]
+fn test_invalid_server_signature() {
+    let client = ClientSession::with_nonce("user", "pencil", CLIENT_NONCE);
+    let server_first = ServerFirstMessage::from_str(SERVER_FIRST).expect("server-first");
+    let (_, verifier) = client
+        .client_final(&server_first)
+        .expect("Could not compute client proof");
+    assert_eq!(
+        Err(ScramError::InvalidServerSignature),
+        verifier.verify(&ServerFinalMessage::Verifier(vec![0; 32]))
+    );
+    assert_eq!(
+        Err(ScramError::ServerError("invalid-proof".to_owned())),
+        verifier.verify(&ServerFinalMessage::Error("invalid-proof".to_owned()))
+    )
+}
+
+#[test]
+fn test_client_rejects_unextended_nonce() {
+    let client = ClientSession::with_nonce("user", "pencil", CLIENT_NONCE);
+    let mut server_first = ServerFirstMessage::from_str(SERVER_FIRST).expect("server-first");
+    server_first.nonce = "something-else".to_owned();
+    assert_eq!(
+        Some(ScramError::NonceMismatch),
+        client.clone().client_final(&server_first).err()
+    );
+    server_first.nonce = CLIENT_NONCE.to_owned();
+    assert_eq!(
+        Some(ScramError::NonceMismatch),
+        client.client_final(&server_first).err()
+    )
+}
+
+#[test]
+fn test_client_rejects_too_few_iterations() {
+    let client = ClientSession::with_nonce("user", "pencil", CLIENT_NONCE);
+    let mut server_first = ServerFirstMessage::from_str(SERVER_FIRST).expect("server-first");
+    server_first.iterations = 1;
+    assert_eq!(
+        Some(ScramError::TooFewIterations(1)),
+        client.client_final(&server_first).err()
+    )
+}
+
+#[test]
+fn test_server_rejects_mismatched_nonce_and_channel_binding() {
+    let client_first = ClientFirstMessage::from_str(CLIENT_FIRST).expect("client-first");
+    let server = ServerSession::with_nonce(client_first, rfc7677_credentials(), SERVER_NONCE);
+    let mut client_final = ClientFinalMessage::from_str(CLIENT_FINAL).expect("client-final");
+    client_final.nonce = CLIENT_NONCE.to_owned();
+    assert_eq!(
+        Err(ScramError::NonceMismatch),
+        server.server_final(&client_final)
+    );
+    let mut client_final = ClientFinalMessage::from_str(CLIENT_FINAL).expect("client-final");
+    client_final.channel_binding = b"y,,".to_vec();
+    assert_eq!(
+        Err(ScramError::ChannelBindingMismatch),
+        server.server_final(&client_final)
+    )
+}
+
+#[test]
+fn test_username_escaping() {
+    let message = ClientFirstMessage::new("a,b=c", "nonce");
+    assert_eq!("n,,n=a=2Cb=3Dc,r=nonce", message.to_string());
+    assert_eq!(
+        Ok(message),
+        ClientFirstMessage::from_str("n,,n=a=2Cb=3Dc,r=nonce")
+    );
+    assert_eq!(
+        Err(ScramError::InvalidMessage),
+        ClientFirstMessage::from_str("n,,n=a=2Xb,r=nonce")
+    )
+}
+
+#[test]
+fn test_invalid_client_first_messages() {
+    assert_eq!(
+        Err(ScramError::ChannelBindingNotSupported),
+        ClientFirstMessage::from_str("p=tls-unique,,n=user,r=nonce")
+    );
+    for message in [
+        "",
+        "n,,",
+        "x,,n=user,r=nonce",
+        "n,b=c,n=user,r=nonce",
+        "n,,m=ext,n=user,r=nonce",
+        "n,,r=nonce,n=user",
+        "n,,n=,r=nonce",
+        "n,,n=user,r=",
+        "n,,n=user,r=no nce",
+    ] {
+        assert_eq!(
+            Err(ScramError::InvalidMessage),
+            ClientFirstMessage::from_str(message),
+            "{message}"
+        );
+    }
+}
+
+#[test]
+fn test_client_first_message_with_extension() {
+    let message =
+        ClientFirstMessage::from_str("y,,n=user,r=nonce,x=ext").expect("Could not parse message");
+    assert_eq!("user", message.username);
+    assert_eq!("y,,", message.gs2_header());
+    assert_eq!("n=user,r=nonce,x=ext", message.bare())
+}
+
+#[test]
+fn test_client_first_message_with_authzid() {
+    assert_eq!(
+        Err(ScramError::AuthzidNotSupported),
+        ClientFirstMessage::from_str("n,a=admin,n=user,r=nonce")
+    );
+
+    let mut server = ScramServer::new("testrealm@example.com");
+    let authorization = ScramAuthorization {
+        realm: Some("testrealm@example.com".to_owned()),
+        sid: None,
+        data: "n,a=admin,n=user,r=nonce".to_owned(),
+    };
+    assert_eq!(
+        ScramOutcome::Rejected(server.challenge()),
+        server.respond(&authorization, lookup)
+    )
+}
+
+#[test]
+fn test_server_exchange_with_extension() {
+    let mut server = ScramServer::new("testrealm@example.com");
+    let client_first = format!("n,,n=user,r={},x=ext", CLIENT_NONCE);
+    let authorization = ScramAuthorization {
+        realm: Some("testrealm@example.com".to_owned()),
+        sid: None,
+        data: client_first.clone(),
+    };
+    let challenge = match server.respond(&authorization, lookup) {
+        ScramOutcome::Continue(challenge) => challenge,
+        outcome => panic!("Unexpected outcome: {:?}", outcome),
+    };
+    let server_first_data = challenge.data.expect("data");
+    let server_first =
+        ServerFirstMessage::from_str(&server_first_data).expect("Could not parse server-first");
+
+    // The AuthMessage covers the client-first-message-bare as sent, including the extension.
+    let salted = salted_password("pencil", &server_first.salt, server_first.iterations);
+    let client_key = client_key(&salted);
+    let stored_key: [u8; 32] = Sha256::digest(client_key).into();
+    let without_proof = format!("c=biws,r={}", server_first.nonce);
+    let auth_message = format!(
+        "{},{},{}",
+        &client_first["n,,".len()..],
+        server_first_data,
+        without_proof
+    );
+    let proof = xor(&client_key, &hmac(&stored_key, auth_message.as_bytes()));
+    let authorization = ScramAuthorization {
+        realm: None,
+        sid: challenge.sid,
+        data: format!("{},p={}", without_proof, BASE64.encode(proof)),
+    };
+    match server.respond(&authorization, lookup) {
+        ScramOutcome::Authenticated { username, .. } => assert_eq!("user", username),
+        outcome => panic!("Unexpected outcome: {:?}", outcome),
+    }
+}
+
+#[test]
+fn test_invalid_server_messages() {
+    for message in [
+        "s=c2FsdA==,r=nonce,i=4096",
+        "r=nonce,s=!,i=4096",
+        "r=nonce,s=c2FsdA==,i=-1",
+    ] {
+        assert_eq!(
+            Err(ScramError::InvalidMessage),
+            ServerFirstMessage::from_str(message),
+            "{message}"
+        );
+    }
+    for message in ["", "x=abc", "e=", "v=!"] {
+        assert_eq!(
+            Err(ScramError::InvalidMessage),
+            ServerFinalMessage::from_str(message),
+            "{message}"
+        );
+    }
+}
+
+#[test]
+fn test_invalid_client_final_messages() {
+    for message in [
+        "c=biws,r=nonce",
+        "r=nonce,c=biws,p=cHJvb2Y=",
+        "c=biws,r=nonce,p=!",
+    ] {
+        assert_eq!(
+            Err(ScramError::InvalidMessage),
+            ClientFinalMessage::from_str(message),
+            "{message}"
+        );
+    }
+}
+
+#[test]
+fn test_credentials_format() {
+    let credentials = rfc7677_credentials();
+    let serialized = credentials.to_string();
+    assert_eq!(
+        "SCRAM-SHA-256$4096:W22ZaJ0SNY7soEsUEjb6gQ==$WG5d8oPm3OtcPnkdi4Uo7BkeZkBFzpcXkuLmtbsT4qY=:\
+         wfPLwcE6nTWhTAmQ7tl2KeoiWGPlZqQxSrmfPwDl2dU=",
+        serialized
+    );
+    assert_eq!(Ok(credentials), ScramCredentials::from_str(&serialized));
+    assert_eq!(
+        Err(ScramError::InvalidMessage),
+        ScramCredentials::from_str("SCRAM-SHA-1$4096:c2FsdA==$a2V5:a2V5")
+    )
+}
+
+#[test]
+fn test_credentials_require_minimum_iterations() {
+    assert_eq!(
+        Err(ScramError::TooFewIterations(1024)),
+        ScramCredentials::new("pencil", b"salt", 1024)
+    )
+}
+
+#[test]
+fn test_authorization_header() {
+    let value = HeaderValue::from_static(
+        "SCRAM-SHA-256 realm=\"testrealm@example.com\", \
+         data=biwsbj11c2VyLHI9ck9wck5HZndFYmVSV2diTkVrcU8=",
+    );
+    let authorization = ScramAuthorization::decode(&value).expect("Could not decode header");
+    assert_eq!(
+        ScramAuthorization {
+            realm: Some("testrealm@example.com".to_owned()),
+            sid: None,
+            data: CLIENT_FIRST.to_owned(),
+        },
+        authorization
+    );
+    assert_eq!(value, authorization.encode())
+}
+
+#[test]
+fn test_authorization_header_requires_data() {
+    for value in [
+        "SCRAM-SHA-256 realm=\"testrealm@example.com\"",
+        "SCRAM-SHA-256 data=!!!",
+        "SCRAM-SHA-1 data=biws",
+    ] {
+        assert_eq!(
+            None,
+            ScramAuthorization::decode(&HeaderValue::from_static(value)),
+            "{value}"
+        );
+    }
+}
+
+#[test]
+fn test_challenge_header() {
+    let value = HeaderValue::from_static(
+        "Digest realm=\"realm1@example.com\", SCRAM-SHA-256 sid=AAAABBBBCCCCDDDD, \
+         data=cj1yT3ByTkdmd0ViZVJXZ2JORWtxTyVodllEcFdVYTJSYVRDQWZ1eEZJbGopaE5sRiRrMCxzPVcyMlph\
+         SjBTTlk3c29Fc1VFamI2Z1E9PSxpPTQwOTY=",
+    );
+    let challenge = WwwAuthenticate::<ScramChallenge>::decode(&mut std::iter::once(&value))
+        .expect("Could not decode header")
+        .0;
+    assert_eq!(
+        ScramChallenge {
+            realm: None,
+            sid: Some("AAAABBBBCCCCDDDD".to_owned()),
+            data: Some(SERVER_FIRST.to_owned()),
+        },
+        challenge
+    );
+    assert_eq!(
+        Some(challenge.clone()),
+        ScramChallenge::decode(challenge.encode().to_str().expect("to_str"))
+    )
+}
+
+#[test]
+fn test_authentication_info_header() {
+    let info = ScramAuthenticationInfo {
+        sid: Some("AAAABBBBCCCCDDDD".to_owned()),
+        data: SERVER_FINAL.to_owned(),
+    };
+    let mut headers = HeaderMap::new();
+    headers.typed_insert(info.clone());
+    assert_eq!(
+        "sid=AAAABBBBCCCCDDDD, data=dj02cnJpVFJCaTIzV3BSUi93dHVwK21NaFVaVW4vZEI1bkxUSlJzamw5NUc0PQ==",
+        headers["authentication-info"]
+    );
+    assert_eq!(Some(info), headers.typed_get::<ScramAuthenticationInfo>())
+}
+
+fn lookup(username: &str) -> Option<ScramCredentials> {
+    (username == "user").then(rfc7677_credentials)
+}
+
+fn first_round_trip(server: &mut ScramServer, client: &ClientSession) -> ScramChallenge {
+    let authorization = ScramAuthorization {
+        realm: Some("testrealm@example.com".to_owned()),
+        sid: None,
+        data: client.client_first().to_string(),
+    };
+    match server.respond(&authorization, lookup) {
+        ScramOutcome::Continue(challenge) => challenge,
+        outcome => panic!("Unexpected outcome: {:?}", outcome),
+    }
+}
+
+#[test]
+fn test_server_exchange() {
+    let mut server = ScramServer::new("testrealm@example.com");
+    assert_eq!(
+        Some("testrealm@example.com".to_owned()),
+        server.challenge().realm
+    );
+    let client = ClientSession::new("user", "pencil");
+    let challenge = first_round_trip(&mut server, &client);
+    let server_first = ServerFirstMessage::from_str(&challenge.data.expect("data"))
+        .expect("Could not parse server-first");
+    let (client_final, verifier) = client
+        .client_final(&server_first)
+        .expect("Could not compute client proof");
+    let authorization = Authorization(ScramAuthorization {
+        realm: None,
+        sid: challenge.sid.clone(),
+        data: client_final.to_string(),
+    });
+
+    match server.respond(&authorization.0, lookup) {
+        ScramOutcome::Authenticated {
+            username,
+            authentication_info,
+        } => {
+            assert_eq!("user", username);
+            assert_eq!(challenge.sid, authentication_info.sid);
+            let server_final = ServerFinalMessage::from_str(&authentication_info.data)
+                .expect("Could not parse server-final");
+            assert_eq!(Ok(()), verifier.verify(&server_final));
+        }
+        outcome => panic!("Unexpected outcome: {:?}", outcome),
+    }
+
+    // The session cannot be replayed.
+    assert_eq!(
+        ScramOutcome::Rejected(server.challenge()),
+        server.respond(&authorization.0, lookup)
+    )
+}
+
+#[test]
+fn test_server_rejects_wrong_password() {
+    let mut server = ScramServer::new("testrealm@example.com");
+    let client = ClientSession::new("user", "pen");
+    let challenge = first_round_trip(&mut server, &client);
+    let server_first = ServerFirstMessage::from_str(&challenge.data.expect("data"))
+        .expect("Could not parse server-first");
+    let (client_final, _) = client
+        .client_final(&server_first)
+        .expect("Could not compute client proof");
+    let authorization = ScramAuthorization {
+        realm: None,
+        sid: challenge.sid,
+        data: client_final.to_string(),
+    };
+    assert_eq!(
+        ScramOutcome::Rejected(server.challenge()),
+        server.respond(&authorization, lookup)
+    )
+}
+
+#[test]
+fn test_server_rejects_unknown_realms_and_sessions() {
+    let mut server = ScramServer::new("testrealm@example.com");
+    for authorization in [
+        ScramAuthorization {
+            realm: Some("other".to_owned()),
+            sid: None,
+            data: CLIENT_FIRST.to_owned(),
+        },
+        ScramAuthorization {
+            realm: None,
+            sid: Some("unknown".to_owned()),
+            data: CLIENT_FINAL.to_owned(),
+        },
+    ] {
+        assert_eq!(
+            ScramOutcome::Rejected(server.challenge()),
+            server.respond(&authorization, lookup)
+        );
+    }
+}
+
+fn final_authorization(client: ClientSession, challenge: ScramChallenge) -> ScramAuthorization {
+    let server_first = ServerFirstMessage::from_str(&challenge.data.expect("data"))
+        .expect("Could not parse server-first");
+    let (client_final, _) = client
+        .client_final(&server_first)
+        .expect("Could not compute client proof");
+    ScramAuthorization {
+        realm: None,
+        sid: challenge.sid,
+        data: client_final.to_string(),
+    }
+}
+
+#[test]
+fn test_server_continues_for_unknown_users() {
+    let secret = [7; 32];
+    let mut server = ScramServer::new("testrealm@example.com")
+        .with_secret(secret)
+        .with_iterations(8192);
+    let salt = |server: &mut ScramServer, username: &str| {
+        let challenge = first_round_trip(server, &ClientSession::new(username, "pencil"));
+        let server_first = ServerFirstMessage::from_str(&challenge.data.expect("data"))
+            .expect("Could not parse server-first");
+        assert_eq!(8192, server_first.iterations);
+        server_first.salt
+    };
+    let nobody = salt(&mut server, "nobody");
+    assert_eq!(16, nobody.len());
+    assert_eq!(nobody, salt(&mut server, "nobody"));
+    assert_ne!(nobody, salt(&mut server, "somebody"));
+    let mut other = ScramServer::new("testrealm@example.com")
+        .with_secret(secret)
+        .with_iterations(8192);
+    assert_eq!(nobody, salt(&mut other, "nobody"));
+
+    let client = ClientSession::new("nobody", "pencil");
+    let challenge = first_round_trip(&mut server, &client);
+    let authorization = final_authorization(client, challenge);
+    assert_eq!(
+        ScramOutcome::Rejected(server.challenge()),
+        server.respond(&authorization, lookup)
+    );
+}
+
+#[test]
+fn test_server_discards_oldest_sessions() {
+    let mut server = ScramServer::new("testrealm@example.com").with_max_sessions(2);
+    let client = ClientSession::new("user", "pencil");
+    let challenges: Vec<_> = (0..3)
+        .map(|_| first_round_trip(&mut server, &client))
+        .collect();
+    let mut authorizations = challenges
+        .into_iter()
+        .map(|challenge| final_authorization(client.clone(), challenge));
+    let oldest = authorizations.next().expect("No authorization");
+    assert_eq!(
+        ScramOutcome::Rejected(server.challenge()),
+        server.respond(&oldest, lookup)
+    );
+    for authorization in authorizations {
+        match server.respond(&authorization, lookup) {
+            ScramOutcome::Authenticated { username, .. } => assert_eq!("user", username),
+            outcome => panic!("Unexpected outcome: {:?}", outcome),
+        }
+    }
+}
+
+#[test]
+fn test_server_expires_sessions() {
+    let mut server =
+        ScramServer::new("testrealm@example.com").with_timeout(std::time::Duration::ZERO);
+    let client = ClientSession::new("user", "pencil");
+    let challenge = first_round_trip(&mut server, &client);
+    let server_first = ServerFirstMessage::from_str(&challenge.data.expect("data"))
+        .expect("Could not parse server-first");
+    let (client_final, _) = client
+        .client_final(&server_first)
+        .expect("Could not compute client proof");
+    let authorization = ScramAuthorization {
+        realm: None,
+        sid: challenge.sid,
+        data: client_final.to_string(),
+    };
+    assert_eq!(
+        ScramOutcome::Rejected(server.challenge()),
+        server.respond(&authorization, lookup)
+    )
+}
@@ -0,0 +1,70 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! `serde` support for the types whose serialized form is their string form in a header.
+
+use crate::parsing::fromheaders::{Charset, ExtendedValue};
+use crate::types::{HashAlgorithm, NonceCount, Qop};
+use serde::de::{self, Deserialize, Deserializer, Unexpected};
+use serde::ser::{Serialize, Serializer};
+use std::str::FromStr;
+
+macro_rules! string_form {
+    ($type:ty, $expecting:expr) => {
+        impl Serialize for $type {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $type {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                <$type>::from_str(&value)
+                    .map_err(|_| de::Error::invalid_value(Unexpected::Str(&value), &$expecting))
+            }
+        }
+    };
+}
+
+string_form!(HashAlgorithm, "a Digest algorithm, e.g. \"SHA-256-sess\"");
+string_form!(Qop, "\"auth\" or \"auth-int\"");
+string_form!(
+    NonceCount,
+    "8 lowercase hexadecimal digits, e.g. \"00000001\""
+);
+string_form!(
+    ExtendedValue,
+    "an RFC 5987 extended value, e.g. \"UTF-8''J%C3%A4s%C3%B8n\""
+);
+
+impl Serialize for Charset {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Unregistered charsets are accepted, so that every serialized `Charset` can be read back.
+impl<'de> Deserialize<'de> for Charset {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Ok(Charset::from_str(&value).unwrap_or_else(|_| Charset::ext(&value)))
+    }
+}
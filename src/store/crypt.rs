@@ -0,0 +1,271 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! The `crypt(3)`-style password hashing algorithms used in `htpasswd` files that are not provided
+//! by other crates: MD5-crypt (including Apache's `$apr1$` variant) and SHA-crypt.
+
+use digest::{Digest, Output};
+use md5::Md5;
+use sha2::{Sha256, Sha512};
+
+mod test;
+
+const ALPHABET: &[u8; 64] = b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// The salt alphabet, which is the same as the one used for the hash.
+pub(crate) fn is_salt_char(c: char) -> bool {
+    c.is_ascii() && ALPHABET.contains(&(c as u8))
+}
+
+/// Generates a random salt of the given length.
+pub(crate) fn generate_salt(length: usize) -> String {
+    (0..length)
+        .map(|_| ALPHABET[rand::random_range(0..ALPHABET.len())] as char)
+        .collect()
+}
+
+/// Encodes the bytes at the given indices (most significant first) as `length` characters, least
+/// significant six bits first.
+fn encode_24bit(output: &mut String, hash: &[u8], indices: [Option<usize>; 3], length: usize) {
+    let mut word = indices.iter().fold(0u32, |word, index| {
+        (word << 8) | u32::from(index.map_or(0, |i| hash[i]))
+    });
+    for _ in 0..length {
+        output.push(ALPHABET[(word & 0x3f) as usize] as char);
+        word >>= 6;
+    }
+}
+
+fn encode_groups(hash: &[u8], groups: &[[usize; 3]], tail: ([Option<usize>; 3], usize)) -> String {
+    let mut output = String::new();
+    for group in groups {
+        encode_24bit(&mut output, hash, group.map(Some), 4);
+    }
+    encode_24bit(&mut output, hash, tail.0, tail.1);
+    output
+}
+
+/// MD5-crypt, as implemented by FreeBSD (`$1$`) and by Apache (`$apr1$`), which only differ in the
+/// magic string. Only the first 8 characters of the salt are used.
+pub(crate) fn md5_crypt(magic: &str, password: &[u8], salt: &str) -> String {
+    let salt = &salt.as_bytes()[..salt.len().min(8)];
+
+    let alternate = Md5::new()
+        .chain_update(password)
+        .chain_update(salt)
+        .chain_update(password)
+        .finalize();
+    let mut context = Md5::new()
+        .chain_update(password)
+        .chain_update(magic)
+        .chain_update(salt);
+    for chunk in password.chunks(16) {
+        context.update(&alternate[..chunk.len()]);
+    }
+    let mut length = password.len();
+    while length > 0 {
+        if length & 1 == 1 {
+            context.update([0]);
+        } else {
+            context.update(&password[..1]);
+        }
+        length >>= 1;
+    }
+    let mut hash = context.finalize();
+
+    for round in 0..1000 {
+        let mut context = Md5::new();
+        if round & 1 == 1 {
+            context.update(password);
+        } else {
+            context.update(hash);
+        }
+        if round % 3 != 0 {
+            context.update(salt);
+        }
+        if round % 7 != 0 {
+            context.update(password);
+        }
+        if round & 1 == 1 {
+            context.update(hash);
+        } else {
+            context.update(password);
+        }
+        hash = context.finalize();
+    }
+
+    let encoded = encode_groups(
+        &hash,
+        &[[0, 6, 12], [1, 7, 13], [2, 8, 14], [3, 9, 15], [4, 10, 5]],
+        ([None, None, Some(11)], 2),
+    );
+    format!("{}{}${}", magic, String::from_utf8_lossy(salt), encoded)
+}
+
+/// The default number of SHA-crypt rounds.
+pub(crate) const SHA_CRYPT_DEFAULT_ROUNDS: u32 = 5000;
+const SHA_CRYPT_MIN_ROUNDS: u32 = 1000;
+const SHA_CRYPT_MAX_ROUNDS: u32 = 999_999_999;
+
+/// Repeats `bytes` until it is `length` bytes long.
+fn repeat_to_length(bytes: &[u8], length: usize) -> Vec<u8> {
+    bytes.iter().copied().cycle().take(length).collect()
+}
+
+fn sha_crypt_hash<D: Digest + Clone>(password: &[u8], salt: &[u8], rounds: u32) -> Output<D> {
+    let alternate = D::new()
+        .chain_update(password)
+        .chain_update(salt)
+        .chain_update(password)
+        .finalize();
+    let mut context = D::new().chain_update(password).chain_update(salt);
+    for chunk in password.chunks(alternate.len()) {
+        context.update(&alternate[..chunk.len()]);
+    }
+    let mut length = password.len();
+    while length > 0 {
+        if length & 1 == 1 {
+            context.update(&alternate);
+        } else {
+            context.update(password);
+        }
+        length >>= 1;
+    }
+    let mut hash = context.finalize();
+
+    let mut password_context = D::new();
+    for _ in 0..password.len() {
+        password_context.update(password);
+    }
+    let p_bytes = repeat_to_length(&password_context.finalize(), password.len());
+    let mut salt_context = D::new();
+    for _ in 0..16 + usize::from(hash[0]) {
+        salt_context.update(salt);
+    }
+    let s_bytes = repeat_to_length(&salt_context.finalize(), salt.len());
+
+    for round in 0..rounds {
+        let mut context = D::new();
+        if round & 1 == 1 {
+            context.update(&p_bytes);
+        } else {
+            context.update(&hash);
+        }
+        if round % 3 != 0 {
+            context.update(&s_bytes);
+        }
+        if round % 7 != 0 {
+            context.update(&p_bytes);
+        }
+        if round & 1 == 1 {
+            context.update(&hash);
+        } else {
+            context.update(&p_bytes);
+        }
+        hash = context.finalize();
+    }
+    hash
+}
+
+/// The SHA-2 variant to use for SHA-crypt.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum ShaCryptVariant {
+    /// `$5$`
+    Sha256,
+    /// `$6$`
+    Sha512,
+}
+
+/// SHA-crypt, as specified by [Ulrich Drepper](https://www.akkadia.org/drepper/SHA-crypt.txt).
+/// Only the first 16 characters of the salt are used. If `rounds` is `None`, the default number
+/// of rounds is used, and omitted from the output.
+pub(crate) fn sha_crypt(
+    variant: ShaCryptVariant,
+    password: &[u8],
+    salt: &str,
+    rounds: Option<u32>,
+) -> String {
+    let salt = &salt.as_bytes()[..salt.len().min(16)];
+    let effective_rounds = rounds
+        .unwrap_or(SHA_CRYPT_DEFAULT_ROUNDS)
+        .clamp(SHA_CRYPT_MIN_ROUNDS, SHA_CRYPT_MAX_ROUNDS);
+    let (magic, encoded) = match variant {
+        ShaCryptVariant::Sha256 => (
+            "$5$",
+            encode_groups(
+                &sha_crypt_hash::<Sha256>(password, salt, effective_rounds),
+                &[
+                    [0, 10, 20],
+                    [21, 1, 11],
+                    [12, 22, 2],
+                    [3, 13, 23],
+                    [24, 4, 14],
+                    [15, 25, 5],
+                    [6, 16, 26],
+                    [27, 7, 17],
+                    [18, 28, 8],
+                    [9, 19, 29],
+                ],
+                ([None, Some(31), Some(30)], 3),
+            ),
+        ),
+        ShaCryptVariant::Sha512 => (
+            "$6$",
+            encode_groups(
+                &sha_crypt_hash::<Sha512>(password, salt, effective_rounds),
+                &[
+                    [0, 21, 42],
+                    [22, 43, 1],
+                    [44, 2, 23],
+                    [3, 24, 45],
+                    [25, 46, 4],
+                    [47, 5, 26],
+                    [6, 27, 48],
+                    [28, 49, 7],
+                    [50, 8, 29],
+                    [9, 30, 51],
+                    [31, 52, 10],
+                    [53, 11, 32],
+                    [12, 33, 54],
+                    [34, 55, 13],
+                    [56, 14, 35],
+                    [15, 36, 57],
+                    [37, 58, 16],
+                    [59, 17, 38],
+                    [18, 39, 60],
+                    [40, 61, 19],
+                    [62, 20, 41],
+                ],
+                ([None, None, Some(63)], 2),
+            ),
+        ),
+    };
+    let rounds = match rounds {
+        Some(_) => format!("rounds={}$", effective_rounds),
+        None => String::new(),
+    };
+    format!(
+        "{}{}{}${}",
+        magic,
+        rounds,
+        String::from_utf8_lossy(salt),
+        encoded
+    )
+}
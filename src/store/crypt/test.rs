@@ -0,0 +1,69 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::{ShaCryptVariant, md5_crypt, sha_crypt};
+
+#[test]
+fn test_md5_crypt() {
+    assert_eq!(
+        "$1$saltstri$YMyguxXMBpd2TEZ.vS/3q1",
+        md5_crypt("$1$", b"Hello world!", "saltstring")
+    );
+    assert_eq!(
+        "$apr1$r31....$kMmt8Ia8qcWk4vKKEhpgx1",
+        md5_crypt("$apr1$", b"password", "r31....")
+    );
+    assert_eq!(
+        "$apr1$$jYtXyIcDHukcQfHjblNhq/",
+        md5_crypt("$apr1$", b"x", "")
+    );
+}
+
+#[test]
+fn test_sha_crypt() {
+    assert_eq!(
+        "$5$saltstring$5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5",
+        sha_crypt(ShaCryptVariant::Sha256, b"Hello world!", "saltstring", None)
+    );
+    assert_eq!(
+        "$6$saltstring$svn8UoSVapNtMuq1ukKS4tPQd8iKwSMHWjl/O817G3uBnIFNjnQJuesI68u4OTLiBFdcbYEdFCoEOfaS35inz1",
+        sha_crypt(ShaCryptVariant::Sha512, b"Hello world!", "saltstring", None)
+    );
+    assert_eq!(
+        "$5$rounds=10000$saltstringsaltst$3xv.VbSHBb41AL9AvLeujZkZRBAwqFMz2.opqey6IcA",
+        sha_crypt(
+            ShaCryptVariant::Sha256,
+            b"Hello world!",
+            "saltstringsaltstring",
+            Some(10000)
+        )
+    );
+    assert_eq!(
+        "$6$rounds=1400$anotherlongsalts$POfYwTEok97VWcjxIiSOjiykti.o/pQs.wPvMxQ6Fm7I6IoYN3CmLs66x9t0oSwbtEW7o7UmJEiDwGqd8p4ur1",
+        sha_crypt(
+            ShaCryptVariant::Sha512,
+            b"a very much longer text to encrypt.  This one even stretches over morethan one line.",
+            "anotherlongsaltstring",
+            Some(1400)
+        )
+    );
+}
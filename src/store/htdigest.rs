@@ -0,0 +1,226 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! A credential store backed by an Apache `htdigest`-style password file.
+//!
+//! Each line of the file is of the form `username:realm:HA1`, where `HA1` is the hexadecimal
+//! digest generated by [`Digest::simple_hashed_a1`].
+
+use super::{CredentialRecord, CredentialStore, DigestRecord, StoreError};
+use crate::digest::{Digest, Username};
+use crate::types::HashAlgorithm;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+mod test;
+
+/// A single `htdigest` entry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HtdigestRecord {
+    /// The name of the user.
+    pub username: String,
+    /// The authentication realm.
+    pub realm: String,
+    /// The hexadecimal digest of the user's A1 value.
+    pub hashed_a1: String,
+}
+
+impl HtdigestRecord {
+    /// Creates an entry from a plaintext password, hashing it with the given algorithm.
+    pub fn new(username: &str, realm: &str, password: String, algorithm: &HashAlgorithm) -> Self {
+        let hashed_a1 = Digest::simple_hashed_a1(
+            algorithm,
+            Username::Plain(username.to_owned()),
+            realm.to_owned(),
+            password,
+        );
+        HtdigestRecord {
+            username: username.to_owned(),
+            realm: realm.to_owned(),
+            hashed_a1,
+        }
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.splitn(3, ':');
+        let username = parts.next()?;
+        let realm = parts.next()?;
+        let hashed_a1 = parts.next()?;
+        if username.is_empty() || hashed_a1.is_empty() || hashed_a1.contains(':') {
+            return None;
+        }
+
+        Some(HtdigestRecord {
+            username: username.to_owned(),
+            realm: realm.to_owned(),
+            hashed_a1: hashed_a1.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for HtdigestRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.username, self.realm, self.hashed_a1)
+    }
+}
+
+impl CredentialRecord for HtdigestRecord {
+    fn username(&self) -> &str {
+        &self.username
+    }
+
+    fn realm(&self) -> &str {
+        &self.realm
+    }
+}
+
+/// `htdigest` files do not record which algorithm an entry was hashed with, so the digest is
+/// returned for any algorithm with the same output length.
+impl DigestRecord for HtdigestRecord {
+    fn hashed_a1(&self, algorithm: &HashAlgorithm) -> Option<&str> {
+        (self.hashed_a1.len() == algorithm.hex_digest_len()).then_some(&self.hashed_a1)
+    }
+}
+
+/// An in-memory copy of an `htdigest` file.
+///
+/// Entries keep the order that they were read or inserted in. A user/realm combination that is
+/// listed more than once is treated as an error when loading, rather than silently picking one.
+#[derive(Clone, Debug, Default)]
+pub struct Htdigest {
+    path: Option<PathBuf>,
+    records: Vec<HtdigestRecord>,
+    index: HashMap<(String, String), usize>,
+}
+
+impl Htdigest {
+    /// Creates an empty store that is not backed by a file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a store from an `htdigest` file. The path is remembered so that the store can be
+    /// reloaded later.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, StoreError> {
+        let path = path.as_ref().to_path_buf();
+        let mut store = Self::from_reader(BufReader::new(File::open(&path)?))?;
+        store.path = Some(path);
+        Ok(store)
+    }
+
+    /// Parses a store from any buffered reader. Blank lines are ignored.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self, StoreError> {
+        let mut store = Self::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record =
+                HtdigestRecord::parse(line).ok_or(StoreError::MalformedEntry(number + 1))?;
+            if store.get(&record.username, &record.realm).is_some() {
+                return Err(StoreError::DuplicateEntry {
+                    line: number + 1,
+                    username: record.username,
+                    realm: record.realm,
+                });
+            }
+            store.insert(record);
+        }
+
+        Ok(store)
+    }
+
+    /// Re-reads the file that the store was opened from.
+    ///
+    /// The in-memory entries are only replaced if the whole file parses successfully.
+    pub fn reload(&mut self) -> Result<(), StoreError> {
+        let path = self.path.as_ref().ok_or(StoreError::NotFileBacked)?;
+        let reloaded = Self::open(path)?;
+        *self = reloaded;
+        Ok(())
+    }
+
+    /// The path of the file that the store was opened from, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Adds an entry, replacing (and returning) any existing entry for the same user and realm.
+    pub fn insert(&mut self, record: HtdigestRecord) -> Option<HtdigestRecord> {
+        let key = (record.username.clone(), record.realm.clone());
+        if let Some(&position) = self.index.get(&key) {
+            Some(std::mem::replace(&mut self.records[position], record))
+        } else {
+            self.index.insert(key, self.records.len());
+            self.records.push(record);
+            None
+        }
+    }
+
+    /// Removes (and returns) the entry for a given user and realm.
+    pub fn remove(&mut self, username: &str, realm: &str) -> Option<HtdigestRecord> {
+        let position = self
+            .index
+            .remove(&(username.to_owned(), realm.to_owned()))?;
+        let record = self.records.remove(position);
+        for later in self.index.values_mut() {
+            if *later > position {
+                *later -= 1;
+            }
+        }
+        Some(record)
+    }
+
+    /// The number of entries in the store.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether the store has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Serializes every entry, one per line, in `htdigest` format.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for record in &self.records {
+            writeln!(writer, "{}", record)?;
+        }
+        Ok(())
+    }
+}
+
+impl CredentialStore for Htdigest {
+    type Record = HtdigestRecord;
+
+    fn get(&self, username: &str, realm: &str) -> Option<&HtdigestRecord> {
+        let position = self.index.get(&(username.to_owned(), realm.to_owned()))?;
+        self.records.get(*position)
+    }
+
+    fn records(&self) -> impl Iterator<Item = &HtdigestRecord> {
+        self.records.iter()
+    }
+}
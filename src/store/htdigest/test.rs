@@ -0,0 +1,110 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::{Htdigest, HtdigestRecord};
+use crate::store::{CredentialStore, StoreError};
+use crate::types::HashAlgorithm;
+use std::io::Write;
+
+const FILE: &str = "Mufasa:testrealm@host.com:939e7578ed9e3c518a452acee763bce9\n\
+                    \n\
+                    Scar:testrealm@host.com:00000000000000000000000000000000\n";
+
+#[test]
+fn test_from_reader() -> Result<(), StoreError> {
+    let store = Htdigest::from_reader(FILE.as_bytes())?;
+    assert_eq!(2, store.len());
+    let record = store
+        .get("Mufasa", "testrealm@host.com")
+        .expect("Could not find Mufasa");
+    assert_eq!("939e7578ed9e3c518a452acee763bce9", record.hashed_a1);
+    assert!(store.get("Mufasa", "otherrealm").is_none());
+    Ok(())
+}
+
+#[test]
+fn test_from_reader_with_malformed_entry() {
+    let result = Htdigest::from_reader("Mufasa:testrealm@host.com\n".as_bytes());
+    assert!(matches!(result, Err(StoreError::MalformedEntry(1))));
+}
+
+#[test]
+fn test_from_reader_with_duplicate_entry() {
+    let data = format!("{}Mufasa:testrealm@host.com:abcdef\n", FILE);
+    let result = Htdigest::from_reader(data.as_bytes());
+    assert!(matches!(
+        result,
+        Err(StoreError::DuplicateEntry { line: 4, .. })
+    ));
+}
+
+#[test]
+fn test_new_record() {
+    let record = HtdigestRecord::new(
+        "Mufasa",
+        "testrealm@host.com",
+        "Circle Of Life".to_owned(),
+        &HashAlgorithm::Md5,
+    );
+    assert_eq!("939e7578ed9e3c518a452acee763bce9", record.hashed_a1);
+}
+
+#[test]
+fn test_insert_remove_and_write() -> Result<(), StoreError> {
+    let mut store = Htdigest::from_reader(FILE.as_bytes())?;
+    let replaced = store.insert(HtdigestRecord {
+        username: "Mufasa".to_owned(),
+        realm: "testrealm@host.com".to_owned(),
+        hashed_a1: "abcdef".to_owned(),
+    });
+    assert!(replaced.is_some());
+    assert!(store.remove("Scar", "testrealm@host.com").is_some());
+    assert!(store.remove("Scar", "testrealm@host.com").is_none());
+
+    let mut output = vec![];
+    store.write(&mut output)?;
+    assert_eq!(b"Mufasa:testrealm@host.com:abcdef\n".to_vec(), output);
+    Ok(())
+}
+
+#[test]
+fn test_reload() -> Result<(), StoreError> {
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(FILE.as_bytes())?;
+    let mut store = Htdigest::open(file.path())?;
+    assert_eq!(2, store.len());
+
+    writeln!(file, "Simba:testrealm@host.com:abcdef")?;
+    store.reload()?;
+    assert_eq!(3, store.len());
+
+    writeln!(file, "Simba:testrealm@host.com:abcdef")?;
+    assert!(store.reload().is_err());
+    assert_eq!(3, store.len());
+    Ok(())
+}
+
+#[test]
+fn test_reload_without_file() {
+    let mut store = Htdigest::new();
+    assert!(matches!(store.reload(), Err(StoreError::NotFileBacked)));
+}
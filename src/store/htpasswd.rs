@@ -0,0 +1,386 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! A credential store backed by an Apache `htpasswd`-style password file, for use with Basic
+//! authentication.
+//!
+//! Each line of the file is of the form `username:hash`. The following hash formats are
+//! recognized:
+//!
+//! * `$2y$` (as well as `$2a$` and `$2b$`): bcrypt
+//! * `$5$` and `$6$`: SHA-crypt, using SHA-256 and SHA-512 respectively
+//! * `$apr1$`: Apache's variant of MD5-crypt
+//! * `{SHA}`: the base64-encoded SHA-1 digest of the password, without a salt
+//! * anything else: the plaintext password
+//!
+//! Entries that look like traditional DES `crypt(3)` hashes (13 characters of the `crypt`
+//! alphabet) or that use another `$`-prefixed format are not supported, and never match any
+//! password. They are *not* treated as plaintext, since that would let anyone who has read the
+//! file log in with the hash itself.
+//!
+//! This module requires the `htpasswd` feature.
+
+use super::crypt::{ShaCryptVariant, generate_salt, is_salt_char, md5_crypt, sha_crypt};
+use super::{CredentialRecord, CredentialStore, PasswordRecord, StoreError};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use digest::Digest;
+use sha1::Sha1;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use subtle::ConstantTimeEq;
+
+mod test;
+
+/// The password hashing schemes that can be used in an `htpasswd` file.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PasswordScheme {
+    /// bcrypt (`$2y$`), with the given cost (4-31).
+    Bcrypt {
+        /// The base-2 logarithm of the number of iterations.
+        cost: u32,
+    },
+    /// SHA-crypt using SHA-256 (`$5$`).
+    Sha256Crypt {
+        /// The number of rounds, if not the default of 5000.
+        rounds: Option<u32>,
+    },
+    /// SHA-crypt using SHA-512 (`$6$`).
+    Sha512Crypt {
+        /// The number of rounds, if not the default of 5000.
+        rounds: Option<u32>,
+    },
+    /// Apache's variant of MD5-crypt (`$apr1$`).
+    Apr1,
+    /// Unsalted SHA-1 (`{SHA}`). Insecure; only use for compatibility.
+    Sha1,
+    /// The plaintext password. Insecure; only use for compatibility.
+    Plaintext,
+}
+
+impl PasswordScheme {
+    /// Determines which scheme a hash uses. Returns `None` for unsupported formats, including
+    /// anything else that looks like a hash (i.e. starts with `$`, or with a `{…}` scheme name),
+    /// so that it is never mistaken for a plaintext password.
+    pub fn detect(hash: &str) -> Option<PasswordScheme> {
+        if let Some(rest) = ["$2y$", "$2a$", "$2b$"]
+            .iter()
+            .find_map(|prefix| hash.strip_prefix(prefix))
+        {
+            let cost = rest.split('$').next()?.parse().ok()?;
+            Some(PasswordScheme::Bcrypt { cost })
+        } else if let Some(rest) = hash.strip_prefix("$5$") {
+            Some(PasswordScheme::Sha256Crypt {
+                rounds: sha_crypt_settings(rest)?.0,
+            })
+        } else if let Some(rest) = hash.strip_prefix("$6$") {
+            Some(PasswordScheme::Sha512Crypt {
+                rounds: sha_crypt_settings(rest)?.0,
+            })
+        } else if hash.starts_with("$apr1$") {
+            Some(PasswordScheme::Apr1)
+        } else if hash.starts_with("{SHA}") {
+            Some(PasswordScheme::Sha1)
+        } else if hash.starts_with('$')
+            || (hash.starts_with('{') && hash.contains('}'))
+            || (hash.len() == 13 && hash.chars().all(is_salt_char))
+        {
+            None
+        } else {
+            Some(PasswordScheme::Plaintext)
+        }
+    }
+
+    /// Hashes a password, using a random salt.
+    pub fn hash(&self, password: &str) -> Result<String, StoreError> {
+        Ok(match *self {
+            PasswordScheme::Bcrypt { cost } => bcrypt::hash_with_result(password, cost)
+                .map_err(|_| StoreError::UnsupportedHash)?
+                .format_for_version(bcrypt::Version::TwoY),
+            PasswordScheme::Sha256Crypt { rounds } => sha_crypt(
+                ShaCryptVariant::Sha256,
+                password.as_bytes(),
+                &generate_salt(16),
+                rounds,
+            ),
+            PasswordScheme::Sha512Crypt { rounds } => sha_crypt(
+                ShaCryptVariant::Sha512,
+                password.as_bytes(),
+                &generate_salt(16),
+                rounds,
+            ),
+            PasswordScheme::Apr1 => md5_crypt("$apr1$", password.as_bytes(), &generate_salt(8)),
+            PasswordScheme::Sha1 => {
+                format!(
+                    "{{SHA}}{}",
+                    BASE64.encode(Sha1::digest(password.as_bytes()))
+                )
+            }
+            PasswordScheme::Plaintext => {
+                if PasswordScheme::detect(password) != Some(PasswordScheme::Plaintext) {
+                    return Err(StoreError::UnsupportedHash);
+                }
+                password.to_owned()
+            }
+        })
+    }
+}
+
+/// Splits the part of a SHA-crypt hash after the magic string into the number of rounds (if
+/// specified) and the salt.
+fn sha_crypt_settings(settings: &str) -> Option<(Option<u32>, &str)> {
+    let (rounds, rest) = match settings.strip_prefix("rounds=") {
+        Some(rest) => {
+            let (rounds, rest) = rest.split_once('$')?;
+            (Some(rounds.parse().ok()?), rest)
+        }
+        None => (None, settings),
+    };
+    Some((rounds, rest.split('$').next()?))
+}
+
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.as_bytes().ct_eq(b.as_bytes()).into()
+}
+
+/// A single `htpasswd` entry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HtpasswdRecord {
+    /// The name of the user.
+    pub username: String,
+    /// The hashed (or plaintext) password.
+    pub hash: String,
+}
+
+impl HtpasswdRecord {
+    /// Creates an entry from a plaintext password, hashing it with the given scheme.
+    pub fn new(
+        username: &str,
+        password: &str,
+        scheme: &PasswordScheme,
+    ) -> Result<Self, StoreError> {
+        Ok(HtpasswdRecord {
+            username: username.to_owned(),
+            hash: scheme.hash(password)?,
+        })
+    }
+
+    /// The scheme that the password was hashed with, or `None` if it is not supported.
+    pub fn scheme(&self) -> Option<PasswordScheme> {
+        PasswordScheme::detect(&self.hash)
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let (username, hash) = line.split_once(':')?;
+        if username.is_empty() || hash.is_empty() {
+            return None;
+        }
+
+        Some(HtpasswdRecord {
+            username: username.to_owned(),
+            hash: hash.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for HtpasswdRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.username, self.hash)
+    }
+}
+
+/// `htpasswd` files are not specific to a realm, so the realm is always empty.
+impl CredentialRecord for HtpasswdRecord {
+    fn username(&self) -> &str {
+        &self.username
+    }
+
+    fn realm(&self) -> &str {
+        ""
+    }
+}
+
+impl PasswordRecord for HtpasswdRecord {
+    fn verify_password(&self, password: &str) -> bool {
+        let password_bytes = password.as_bytes();
+        match self.scheme() {
+            Some(PasswordScheme::Bcrypt { .. }) => {
+                bcrypt::verify(password, &self.hash).unwrap_or(false)
+            }
+            Some(PasswordScheme::Sha256Crypt { .. }) | Some(PasswordScheme::Sha512Crypt { .. }) => {
+                let (variant, settings) = match self.hash.strip_prefix("$5$") {
+                    Some(settings) => (ShaCryptVariant::Sha256, settings),
+                    None => (ShaCryptVariant::Sha512, &self.hash[3..]),
+                };
+                sha_crypt_settings(settings).is_some_and(|(rounds, salt)| {
+                    constant_time_eq(
+                        &sha_crypt(variant, password_bytes, salt, rounds),
+                        &self.hash,
+                    )
+                })
+            }
+            Some(PasswordScheme::Apr1) => {
+                let salt = self.hash["$apr1$".len()..].split('$').next().unwrap_or("");
+                constant_time_eq(&md5_crypt("$apr1$", password_bytes, salt), &self.hash)
+            }
+            Some(PasswordScheme::Sha1) => constant_time_eq(
+                &BASE64.encode(Sha1::digest(password_bytes)),
+                &self.hash["{SHA}".len()..],
+            ),
+            Some(PasswordScheme::Plaintext) => constant_time_eq(password, &self.hash),
+            None => false,
+        }
+    }
+}
+
+/// An in-memory copy of an `htpasswd` file.
+///
+/// Entries keep the order that they were read or inserted in. A user that is listed more than
+/// once is treated as an error when loading, rather than silently picking one.
+#[derive(Clone, Debug, Default)]
+pub struct Htpasswd {
+    path: Option<PathBuf>,
+    records: Vec<HtpasswdRecord>,
+    index: HashMap<String, usize>,
+}
+
+impl Htpasswd {
+    /// Creates an empty store that is not backed by a file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads a store from an `htpasswd` file. The path is remembered so that the store can be
+    /// reloaded later.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, StoreError> {
+        let path = path.as_ref().to_path_buf();
+        let mut store = Self::from_reader(BufReader::new(File::open(&path)?))?;
+        store.path = Some(path);
+        Ok(store)
+    }
+
+    /// Parses a store from any buffered reader. Blank lines are ignored.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self, StoreError> {
+        let mut store = Self::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record =
+                HtpasswdRecord::parse(line).ok_or(StoreError::MalformedEntry(number + 1))?;
+            if store.index.contains_key(&record.username) {
+                return Err(StoreError::DuplicateEntry {
+                    line: number + 1,
+                    username: record.username,
+                    realm: String::new(),
+                });
+            }
+            store.insert(record);
+        }
+
+        Ok(store)
+    }
+
+    /// Re-reads the file that the store was opened from.
+    ///
+    /// The in-memory entries are only replaced if the whole file parses successfully.
+    pub fn reload(&mut self) -> Result<(), StoreError> {
+        let path = self.path.as_ref().ok_or(StoreError::NotFileBacked)?;
+        let reloaded = Self::open(path)?;
+        *self = reloaded;
+        Ok(())
+    }
+
+    /// The path of the file that the store was opened from, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Looks up the entry for a given user.
+    pub fn get_user(&self, username: &str) -> Option<&HtpasswdRecord> {
+        self.records.get(*self.index.get(username)?)
+    }
+
+    /// Adds an entry, replacing (and returning) any existing entry for the same user.
+    pub fn insert(&mut self, record: HtpasswdRecord) -> Option<HtpasswdRecord> {
+        if let Some(&position) = self.index.get(&record.username) {
+            Some(std::mem::replace(&mut self.records[position], record))
+        } else {
+            self.index
+                .insert(record.username.clone(), self.records.len());
+            self.records.push(record);
+            None
+        }
+    }
+
+    /// Removes (and returns) the entry for a given user.
+    pub fn remove(&mut self, username: &str) -> Option<HtpasswdRecord> {
+        let position = self.index.remove(username)?;
+        let record = self.records.remove(position);
+        for later in self.index.values_mut() {
+            if *later > position {
+                *later -= 1;
+            }
+        }
+        Some(record)
+    }
+
+    /// Verifies a user's password. Returns `false` if the user does not exist.
+    pub fn verify(&self, username: &str, password: &str) -> bool {
+        self.get_user(username)
+            .is_some_and(|record| record.verify_password(password))
+    }
+
+    /// The number of entries in the store.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether the store has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Serializes every entry, one per line, in `htpasswd` format.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for record in &self.records {
+            writeln!(writer, "{}", record)?;
+        }
+        Ok(())
+    }
+}
+
+/// The realm is ignored when looking up entries.
+impl CredentialStore for Htpasswd {
+    type Record = HtpasswdRecord;
+
+    fn get(&self, username: &str, _realm: &str) -> Option<&HtpasswdRecord> {
+        self.get_user(username)
+    }
+
+    fn records(&self) -> impl Iterator<Item = &HtpasswdRecord> {
+        self.records.iter()
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::{Htpasswd, HtpasswdRecord, PasswordScheme};
+use crate::store::{CredentialStore, PasswordRecord, StoreError};
+use std::io::Write;
+
+const FILE: &str = "bcrypt:$2y$05$CCCCCCCCCCCCCCCCCCCCC.E5YPO9kmyuRGyh0XouQYb4YMJKvyOeW\n\
+                    sha256:$5$saltstring$5B8vYYiY.CVt1RlTTf8KbXBH3hsxY/GNooZaBBGWEc5\n\
+                    \n\
+                    sha512:$6$rounds=1400$anotherlongsalts$POfYwTEok97VWcjxIiSOjiykti.o/pQs.wPvMxQ6Fm7I6IoYN3CmLs66x9t0oSwbtEW7o7UmJEiDwGqd8p4ur1\n\
+                    apr1:$apr1$r31....$kMmt8Ia8qcWk4vKKEhpgx1\n\
+                    sha1:{SHA}W6ph5Mm5Pz8GgiULbPgzG37mj9g=\n\
+                    plain:Circle Of Life\n\
+                    des:abJnggxhB/yWI\n\
+                    md5:$1$saltstri$YMyguxXMBpd2TEZ.vS/3q1\n";
+
+#[test]
+fn test_from_reader_and_verify() -> Result<(), StoreError> {
+    let store = Htpasswd::from_reader(FILE.as_bytes())?;
+    assert_eq!(8, store.len());
+    let long_text = "a very much longer text to encrypt.  This one even stretches over \
+                     morethan one line.";
+    for (username, password) in [
+        ("bcrypt", "U*U"),
+        ("sha256", "Hello world!"),
+        ("sha512", long_text),
+        ("apr1", "password"),
+        ("sha1", "password"),
+        ("plain", "Circle Of Life"),
+    ] {
+        assert!(store.verify(username, password), "{}", username);
+        assert!(!store.verify(username, "wrong"), "{}", username);
+    }
+    assert!(!store.verify("nobody", "password"));
+    assert!(!store.verify("des", "abJnggxhB/yWI"));
+    assert!(!store.verify("md5", "$1$saltstri$YMyguxXMBpd2TEZ.vS/3q1"));
+    Ok(())
+}
+
+#[test]
+fn test_detect_scheme() -> Result<(), StoreError> {
+    let store = Htpasswd::from_reader(FILE.as_bytes())?;
+    let scheme = |username| store.get(username, "any realm").and_then(|r| r.scheme());
+    assert_eq!(Some(PasswordScheme::Bcrypt { cost: 5 }), scheme("bcrypt"));
+    assert_eq!(
+        Some(PasswordScheme::Sha256Crypt { rounds: None }),
+        scheme("sha256")
+    );
+    assert_eq!(
+        Some(PasswordScheme::Sha512Crypt { rounds: Some(1400) }),
+        scheme("sha512")
+    );
+    assert_eq!(Some(PasswordScheme::Apr1), scheme("apr1"));
+    assert_eq!(Some(PasswordScheme::Sha1), scheme("sha1"));
+    assert_eq!(Some(PasswordScheme::Plaintext), scheme("plain"));
+    assert_eq!(None, scheme("des"));
+    assert_eq!(None, scheme("md5"));
+    assert_eq!(None, PasswordScheme::detect("{SSHA}c2FsdGVkIGhhc2g="));
+    assert_eq!(
+        None,
+        PasswordScheme::detect("{MD5}ICy5YqxZB1uWSwcVLSNLcA==")
+    );
+    assert_eq!(
+        Some(PasswordScheme::Plaintext),
+        PasswordScheme::detect("{not a scheme")
+    );
+    Ok(())
+}
+
+#[test]
+fn test_new_record() -> Result<(), StoreError> {
+    for scheme in [
+        PasswordScheme::Bcrypt { cost: 4 },
+        PasswordScheme::Sha256Crypt { rounds: None },
+        PasswordScheme::Sha512Crypt { rounds: Some(1000) },
+        PasswordScheme::Apr1,
+        PasswordScheme::Sha1,
+        PasswordScheme::Plaintext,
+    ] {
+        let record = HtpasswdRecord::new("Mufasa", "Circle Of Life", &scheme)?;
+        assert_eq!(Some(scheme), record.scheme());
+        assert!(record.verify_password("Circle Of Life"), "{:?}", scheme);
+        assert!(!record.verify_password("Circle of Life"), "{:?}", scheme);
+    }
+    for password in ["$apr1$", "{SSHA}"] {
+        assert!(matches!(
+            HtpasswdRecord::new("Mufasa", password, &PasswordScheme::Plaintext),
+            Err(StoreError::UnsupportedHash)
+        ));
+    }
+    assert!(matches!(
+        HtpasswdRecord::new("Mufasa", "x", &PasswordScheme::Bcrypt { cost: 3 }),
+        Err(StoreError::UnsupportedHash)
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_from_reader_with_malformed_entry() {
+    for data in ["Mufasa\n", ":hash\n", "Mufasa:\n"] {
+        let result = Htpasswd::from_reader(data.as_bytes());
+        assert!(matches!(result, Err(StoreError::MalformedEntry(1))));
+    }
+}
+
+#[test]
+fn test_from_reader_with_duplicate_entry() {
+    let data = format!("{}plain:other\n", FILE);
+    let result = Htpasswd::from_reader(data.as_bytes());
+    assert!(matches!(
+        result,
+        Err(StoreError::DuplicateEntry { line: 10, .. })
+    ));
+}
+
+#[test]
+fn test_insert_remove_and_write() -> Result<(), StoreError> {
+    let mut store = Htpasswd::from_reader(FILE.as_bytes())?;
+    let replaced = store.insert(HtpasswdRecord {
+        username: "bcrypt".to_owned(),
+        hash: "{SHA}W6ph5Mm5Pz8GgiULbPgzG37mj9g=".to_owned(),
+    });
+    assert!(replaced.is_some());
+    assert!(store.verify("bcrypt", "password"));
+    for username in ["sha256", "sha512", "apr1", "sha1", "plain", "des", "md5"] {
+        assert!(store.remove(username).is_some());
+    }
+    assert!(store.remove("md5").is_none());
+
+    let mut output = vec![];
+    store.write(&mut output)?;
+    assert_eq!(
+        b"bcrypt:{SHA}W6ph5Mm5Pz8GgiULbPgzG37mj9g=\n".to_vec(),
+        output
+    );
+    Ok(())
+}
+
+#[test]
+fn test_reload() -> Result<(), StoreError> {
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(FILE.as_bytes())?;
+    let mut store = Htpasswd::open(file.path())?;
+    assert_eq!(8, store.len());
+
+    writeln!(file, "Simba:Hakuna Matata")?;
+    store.reload()?;
+    assert_eq!(9, store.len());
+
+    writeln!(file, "Simba:Hakuna Matata")?;
+    assert!(store.reload().is_err());
+    assert_eq!(9, store.len());
+    Ok(())
+}
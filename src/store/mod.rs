@@ -0,0 +1,100 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Credential stores, used to look up the secrets associated with a user.
+
+use crate::types::HashAlgorithm;
+use std::io;
+use thiserror::Error;
+
+#[cfg(feature = "htpasswd")]
+mod crypt;
+pub mod htdigest;
+#[cfg(feature = "htpasswd")]
+pub mod htpasswd;
+pub mod multi_digest;
+
+/// A user entry held by a credential store.
+pub trait CredentialRecord {
+    /// The name of the user, as stored (i.e., never a userhash).
+    fn username(&self) -> &str;
+    /// The authentication realm that the entry belongs to.
+    fn realm(&self) -> &str;
+}
+
+/// A user entry that holds the hexadecimal digest of the user's A1 value (see
+/// [`Digest::simple_hashed_a1`](crate::digest::Digest::simple_hashed_a1)), for use with Digest
+/// authentication.
+pub trait DigestRecord: CredentialRecord {
+    /// The digest for the given algorithm (or its non-session variant), if the entry has one.
+    fn hashed_a1(&self, algorithm: &HashAlgorithm) -> Option<&str>;
+
+    /// The precomputed userhash for the given algorithm (or its non-session variant), if the entry
+    /// has one. Otherwise, the userhash is computed from the username when needed.
+    fn userhash(&self, _algorithm: &HashAlgorithm) -> Option<&str> {
+        None
+    }
+}
+
+/// A user entry that can verify a plaintext password, for use with Basic authentication.
+pub trait PasswordRecord: CredentialRecord {
+    /// Whether the password matches the one held by the entry.
+    fn verify_password(&self, password: &str) -> bool;
+}
+
+/// A collection of user entries, keyed by username and realm.
+pub trait CredentialStore {
+    /// The type of entry held by the store.
+    type Record: CredentialRecord;
+
+    /// Looks up the entry for a given user in a given realm.
+    fn get(&self, username: &str, realm: &str) -> Option<&Self::Record>;
+
+    /// Iterates over every entry in the store.
+    fn records(&self) -> impl Iterator<Item = &Self::Record>;
+}
+
+/// Errors relating to loading or saving a credential store.
+#[derive(Debug, Error)]
+pub enum StoreError {
+    /// The underlying file could not be read or written.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// A line in the file could not be parsed.
+    #[error("Malformed entry on line {0}")]
+    MalformedEntry(usize),
+    /// A user/realm combination was listed more than once.
+    #[error("Duplicate entry for user {username:?} in realm {realm:?} on line {line}")]
+    DuplicateEntry {
+        /// The line number of the second occurrence.
+        line: usize,
+        /// The name of the duplicated user.
+        username: String,
+        /// The realm of the duplicated user.
+        realm: String,
+    },
+    /// The store can only be reloaded if it was opened from a file.
+    #[error("Credential store is not backed by a file")]
+    NotFileBacked,
+    /// A password could not be hashed with the requested scheme, e.g. because a parameter was
+    /// out of range.
+    #[error("Unsupported password hash")]
+    UnsupportedHash,
+}
@@ -0,0 +1,381 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! A credential store that holds a digest of each user's A1 value for several hash algorithms,
+//! so that a server can offer every one of them in its `Digest` challenges without storing
+//! plaintext passwords.
+//!
+//! Each line of the file is of the form:
+//!
+//! ```text
+//! username:realm:ALGORITHM=HA1[,ALGORITHM=HA1...][:ALGORITHM=USERHASH[,ALGORITHM=USERHASH...]]
+//! ```
+//!
+//! where `ALGORITHM` is one of `MD5`, `SHA-256` or `SHA-512-256` (the `-sess` variants share the
+//! digest of their base algorithm), `HA1` is the hexadecimal digest generated by
+//! [`Digest::simple_hashed_a1`], and the optional `USERHASH` values are the ones generated by
+//! [`Digest::userhash`], precomputed so that userhashes can be resolved without hashing every
+//! username. For example:
+//!
+//! ```text
+//! Mufasa:testrealm@host.com:MD5=939e7578ed9e3c518a452acee763bce9
+//! ```
+//!
+//! Existing `htdigest` files can be converted with [`MultiDigest::migrate`]. Since the other
+//! digests cannot be computed without the password, migrated entries only hold the original
+//! digest until the password is next set.
+
+use super::htdigest::Htdigest;
+use super::{CredentialRecord, CredentialStore, DigestRecord, StoreError};
+use crate::digest::{Digest, Username};
+use crate::types::HashAlgorithm;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+mod test;
+
+/// The algorithms that digests can be stored for, in the order that they are serialized.
+pub const ALGORITHMS: [HashAlgorithm; 3] = [
+    HashAlgorithm::Md5,
+    HashAlgorithm::Sha256,
+    HashAlgorithm::Sha512256,
+];
+
+fn parse_digests(field: &str) -> Option<HashMap<HashAlgorithm, String>> {
+    let mut digests = HashMap::new();
+    for item in field.split(',') {
+        let (algorithm, digest) = item.split_once('=')?;
+        let algorithm: HashAlgorithm = algorithm.parse().ok()?;
+        if algorithm != algorithm.base()
+            || digest.len() != algorithm.hex_digest_len()
+            || !digest.bytes().all(|b| b.is_ascii_hexdigit())
+            || digests
+                .insert(algorithm, digest.to_ascii_lowercase())
+                .is_some()
+        {
+            return None;
+        }
+    }
+    Some(digests)
+}
+
+fn format_digests(f: &mut fmt::Formatter, digests: &HashMap<HashAlgorithm, String>) -> fmt::Result {
+    let mut separator = "";
+    for algorithm in &ALGORITHMS {
+        if let Some(digest) = digests.get(algorithm) {
+            write!(f, "{}{}={}", separator, algorithm, digest)?;
+            separator = ",";
+        }
+    }
+    Ok(())
+}
+
+/// A single entry, holding digests for one or more algorithms.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MultiDigestRecord {
+    /// The name of the user.
+    pub username: String,
+    /// The authentication realm.
+    pub realm: String,
+    hashed_a1: HashMap<HashAlgorithm, String>,
+    userhashes: HashMap<HashAlgorithm, String>,
+}
+
+impl MultiDigestRecord {
+    /// Creates an entry from a plaintext password, hashing it with each of the given algorithms.
+    /// If `userhash` is true, the userhash for each algorithm is stored as well.
+    pub fn new<'a, I>(
+        username: &str,
+        realm: &str,
+        password: &str,
+        algorithms: I,
+        userhash: bool,
+    ) -> Self
+    where
+        I: IntoIterator<Item = &'a HashAlgorithm>,
+    {
+        let mut record = MultiDigestRecord {
+            username: username.to_owned(),
+            realm: realm.to_owned(),
+            hashed_a1: HashMap::new(),
+            userhashes: HashMap::new(),
+        };
+        for algorithm in algorithms {
+            let algorithm = algorithm.base();
+            let hashed_a1 = Digest::simple_hashed_a1(
+                &algorithm,
+                Username::Plain(username.to_owned()),
+                realm.to_owned(),
+                password.to_owned(),
+            );
+            record.hashed_a1.insert(algorithm.clone(), hashed_a1);
+            if userhash {
+                record.add_userhash(&algorithm);
+            }
+        }
+        record
+    }
+
+    /// Stores the digest for an algorithm, e.g. one taken from an `htdigest` file. Returns
+    /// `false` (and leaves the entry unchanged) if the digest has the wrong length.
+    pub fn set_hashed_a1(&mut self, algorithm: &HashAlgorithm, hashed_a1: &str) -> bool {
+        if hashed_a1.len() != algorithm.hex_digest_len() {
+            return false;
+        }
+        self.hashed_a1
+            .insert(algorithm.base(), hashed_a1.to_ascii_lowercase());
+        true
+    }
+
+    /// Computes and stores the userhash for an algorithm.
+    pub fn add_userhash(&mut self, algorithm: &HashAlgorithm) {
+        let algorithm = algorithm.base();
+        let userhash = Digest::userhash(
+            &algorithm,
+            self.username.as_bytes().to_vec(),
+            self.realm.clone(),
+        );
+        self.userhashes.insert(algorithm, userhash);
+    }
+
+    /// The algorithms that the entry holds digests for.
+    pub fn algorithms(&self) -> impl Iterator<Item = &HashAlgorithm> {
+        ALGORITHMS
+            .iter()
+            .filter(|algorithm| self.hashed_a1.contains_key(algorithm))
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.splitn(4, ':');
+        let username = parts.next()?;
+        let realm = parts.next()?;
+        let hashed_a1 = parse_digests(parts.next()?)?;
+        let userhashes = match parts.next() {
+            Some(userhashes) => parse_digests(userhashes)?,
+            None => HashMap::new(),
+        };
+        if username.is_empty() {
+            return None;
+        }
+
+        Some(MultiDigestRecord {
+            username: username.to_owned(),
+            realm: realm.to_owned(),
+            hashed_a1,
+            userhashes,
+        })
+    }
+}
+
+impl fmt::Display for MultiDigestRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:", self.username, self.realm)?;
+        format_digests(f, &self.hashed_a1)?;
+        if !self.userhashes.is_empty() {
+            write!(f, ":")?;
+            format_digests(f, &self.userhashes)?;
+        }
+        Ok(())
+    }
+}
+
+impl CredentialRecord for MultiDigestRecord {
+    fn username(&self) -> &str {
+        &self.username
+    }
+
+    fn realm(&self) -> &str {
+        &self.realm
+    }
+}
+
+impl DigestRecord for MultiDigestRecord {
+    fn hashed_a1(&self, algorithm: &HashAlgorithm) -> Option<&str> {
+        self.hashed_a1.get(&algorithm.base()).map(String::as_str)
+    }
+
+    fn userhash(&self, algorithm: &HashAlgorithm) -> Option<&str> {
+        self.userhashes.get(&algorithm.base()).map(String::as_str)
+    }
+}
+
+/// An in-memory copy of a multi-algorithm digest file.
+///
+/// Entries keep the order that they were read or inserted in. A user/realm combination that is
+/// listed more than once is treated as an error when loading, rather than silently picking one.
+#[derive(Clone, Debug, Default)]
+pub struct MultiDigest {
+    path: Option<PathBuf>,
+    records: Vec<MultiDigestRecord>,
+    index: HashMap<(String, String), usize>,
+}
+
+impl MultiDigest {
+    /// Creates an empty store that is not backed by a file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Converts the entries of an `htdigest` store, all of which must have been hashed with
+    /// `algorithm`. If `userhash` is true, userhashes are stored for that algorithm.
+    ///
+    /// Returns [`StoreError::MalformedEntry`] (with the 1-based position of the entry) if a digest
+    /// has the wrong length for the algorithm.
+    pub fn migrate(
+        htdigest: &Htdigest,
+        algorithm: &HashAlgorithm,
+        userhash: bool,
+    ) -> Result<Self, StoreError> {
+        let mut store = Self::new();
+        for (position, entry) in htdigest.records().enumerate() {
+            let mut record = MultiDigestRecord::new(&entry.username, &entry.realm, "", [], false);
+            if !record.set_hashed_a1(algorithm, &entry.hashed_a1) {
+                return Err(StoreError::MalformedEntry(position + 1));
+            }
+            if userhash {
+                record.add_userhash(algorithm);
+            }
+            store.insert(record);
+        }
+        Ok(store)
+    }
+
+    /// Loads a store from a file. The path is remembered so that the store can be reloaded later.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, StoreError> {
+        let path = path.as_ref().to_path_buf();
+        let mut store = Self::from_reader(BufReader::new(File::open(&path)?))?;
+        store.path = Some(path);
+        Ok(store)
+    }
+
+    /// Parses a store from any buffered reader. Blank lines are ignored.
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self, StoreError> {
+        let mut store = Self::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record =
+                MultiDigestRecord::parse(line).ok_or(StoreError::MalformedEntry(number + 1))?;
+            if store.get(&record.username, &record.realm).is_some() {
+                return Err(StoreError::DuplicateEntry {
+                    line: number + 1,
+                    username: record.username,
+                    realm: record.realm,
+                });
+            }
+            store.insert(record);
+        }
+
+        Ok(store)
+    }
+
+    /// Re-reads the file that the store was opened from.
+    ///
+    /// The in-memory entries are only replaced if the whole file parses successfully.
+    pub fn reload(&mut self) -> Result<(), StoreError> {
+        let path = self.path.as_ref().ok_or(StoreError::NotFileBacked)?;
+        let reloaded = Self::open(path)?;
+        *self = reloaded;
+        Ok(())
+    }
+
+    /// The path of the file that the store was opened from, if any.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Adds an entry, replacing (and returning) any existing entry for the same user and realm.
+    pub fn insert(&mut self, record: MultiDigestRecord) -> Option<MultiDigestRecord> {
+        let key = (record.username.clone(), record.realm.clone());
+        if let Some(&position) = self.index.get(&key) {
+            Some(std::mem::replace(&mut self.records[position], record))
+        } else {
+            self.index.insert(key, self.records.len());
+            self.records.push(record);
+            None
+        }
+    }
+
+    /// Removes (and returns) the entry for a given user and realm.
+    pub fn remove(&mut self, username: &str, realm: &str) -> Option<MultiDigestRecord> {
+        let position = self
+            .index
+            .remove(&(username.to_owned(), realm.to_owned()))?;
+        let record = self.records.remove(position);
+        for later in self.index.values_mut() {
+            if *later > position {
+                *later -= 1;
+            }
+        }
+        Some(record)
+    }
+
+    /// The algorithms that every entry in a realm holds digests for, i.e. the ones that can be
+    /// offered in challenges for that realm.
+    pub fn common_algorithms(&self, realm: &str) -> Vec<HashAlgorithm> {
+        ALGORITHMS
+            .iter()
+            .filter(|algorithm| {
+                self.records
+                    .iter()
+                    .filter(|record| record.realm == realm)
+                    .all(|record| record.hashed_a1.contains_key(algorithm))
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// The number of entries in the store.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether the store has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Serializes every entry, one per line.
+    pub fn write<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for record in &self.records {
+            writeln!(writer, "{}", record)?;
+        }
+        Ok(())
+    }
+}
+
+impl CredentialStore for MultiDigest {
+    type Record = MultiDigestRecord;
+
+    fn get(&self, username: &str, realm: &str) -> Option<&MultiDigestRecord> {
+        let position = self.index.get(&(username.to_owned(), realm.to_owned()))?;
+        self.records.get(*position)
+    }
+
+    fn records(&self) -> impl Iterator<Item = &MultiDigestRecord> {
+        self.records.iter()
+    }
+}
@@ -0,0 +1,174 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(test)]
+
+use super::{MultiDigest, MultiDigestRecord};
+use crate::store::htdigest::Htdigest;
+use crate::store::{CredentialStore, DigestRecord, StoreError};
+use crate::types::HashAlgorithm;
+use std::io::Write;
+
+const MD5_HA1: &str = "939e7578ed9e3c518a452acee763bce9";
+const SHA256_HA1: &str = "3ba6cd94661c5ef34598040c868f13b8775df29109986be50ad35ae537dd3aa4";
+const FILE: &str = "Mufasa:testrealm@host.com:MD5=939e7578ed9e3c518a452acee763bce9\n\
+                    \n\
+                    Simba:testrealm@host.com:SHA-512-256=0000000000000000000000000000000000000000000000000000000000000000,MD5=00000000000000000000000000000000:MD5=00000000000000000000000000000000\n";
+
+#[test]
+fn test_new_record() {
+    let algorithms = [HashAlgorithm::Md5, HashAlgorithm::Sha256Session];
+    let record = MultiDigestRecord::new(
+        "Mufasa",
+        "testrealm@host.com",
+        "Circle Of Life",
+        &algorithms,
+        true,
+    );
+    assert_eq!(Some(MD5_HA1), record.hashed_a1(&HashAlgorithm::Md5Session));
+    assert_eq!(Some(SHA256_HA1), record.hashed_a1(&HashAlgorithm::Sha256));
+    assert_eq!(None, record.hashed_a1(&HashAlgorithm::Sha512256));
+    assert_eq!(
+        vec![&HashAlgorithm::Md5, &HashAlgorithm::Sha256],
+        record.algorithms().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        Some("429d18b3ed40026c70f22a7c7a0e84db5dcd3989eb4402cac5a5d97d9fffc758"),
+        record.userhash(&HashAlgorithm::Sha256)
+    );
+
+    let serialized = record.to_string();
+    assert_eq!(
+        format!(
+            "Mufasa:testrealm@host.com:MD5={},SHA-256={}:MD5={},SHA-256={}",
+            MD5_HA1,
+            SHA256_HA1,
+            record.userhash(&HashAlgorithm::Md5).unwrap(),
+            record.userhash(&HashAlgorithm::Sha256).unwrap()
+        ),
+        serialized
+    );
+    assert_eq!(Some(record), MultiDigestRecord::parse(&serialized));
+}
+
+#[test]
+fn test_from_reader() -> Result<(), StoreError> {
+    let store = MultiDigest::from_reader(FILE.as_bytes())?;
+    assert_eq!(2, store.len());
+    let simba = store
+        .get("Simba", "testrealm@host.com")
+        .expect("Could not find Simba");
+    assert_eq!(
+        vec![&HashAlgorithm::Md5, &HashAlgorithm::Sha512256],
+        simba.algorithms().collect::<Vec<_>>()
+    );
+    assert!(simba.userhash(&HashAlgorithm::Md5).is_some());
+    assert_eq!(
+        vec![HashAlgorithm::Md5],
+        store.common_algorithms("testrealm@host.com")
+    );
+
+    let mut output = vec![];
+    store.write(&mut output)?;
+    assert_eq!(
+        "Mufasa:testrealm@host.com:MD5=939e7578ed9e3c518a452acee763bce9\n\
+         Simba:testrealm@host.com:MD5=00000000000000000000000000000000,SHA-512-256=0000000000000000000000000000000000000000000000000000000000000000:MD5=00000000000000000000000000000000\n",
+        String::from_utf8_lossy(&output)
+    );
+    Ok(())
+}
+
+#[test]
+fn test_from_reader_with_malformed_entry() {
+    for line in [
+        "Mufasa:testrealm@host.com",
+        "Mufasa:testrealm@host.com:939e7578ed9e3c518a452acee763bce9",
+        "Mufasa:testrealm@host.com:MD5=939e",
+        "Mufasa:testrealm@host.com:MD5-sess=939e7578ed9e3c518a452acee763bce9",
+        "Mufasa:testrealm@host.com:MD5=939e7578ed9e3c518a452acee763bcez",
+        "Mufasa:testrealm@host.com:MD5=939e7578ed9e3c518a452acee763bce9,MD5=939e7578ed9e3c518a452acee763bce9",
+        "Mufasa:testrealm@host.com:MD5=939e7578ed9e3c518a452acee763bce9:",
+        ":testrealm@host.com:MD5=939e7578ed9e3c518a452acee763bce9",
+    ] {
+        let result = MultiDigest::from_reader(line.as_bytes());
+        assert!(
+            matches!(result, Err(StoreError::MalformedEntry(1))),
+            "{}",
+            line
+        );
+    }
+}
+
+#[test]
+fn test_from_reader_with_duplicate_entry() {
+    let data = format!("{}Mufasa:testrealm@host.com:MD5={}\n", FILE, MD5_HA1);
+    let result = MultiDigest::from_reader(data.as_bytes());
+    assert!(matches!(
+        result,
+        Err(StoreError::DuplicateEntry { line: 4, .. })
+    ));
+}
+
+#[test]
+fn test_migrate() -> Result<(), StoreError> {
+    let htdigest =
+        Htdigest::from_reader(format!("Mufasa:testrealm@host.com:{}\n", MD5_HA1).as_bytes())?;
+    let store = MultiDigest::migrate(&htdigest, &HashAlgorithm::Md5, true)?;
+    let record = store
+        .get("Mufasa", "testrealm@host.com")
+        .expect("Could not find Mufasa");
+    assert_eq!(Some(MD5_HA1), record.hashed_a1(&HashAlgorithm::Md5));
+    assert_eq!(None, record.hashed_a1(&HashAlgorithm::Sha256));
+    assert!(record.userhash(&HashAlgorithm::Md5).is_some());
+
+    assert!(matches!(
+        MultiDigest::migrate(&htdigest, &HashAlgorithm::Sha256, false),
+        Err(StoreError::MalformedEntry(1))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_insert_remove_and_reload() -> Result<(), StoreError> {
+    let mut file = tempfile::NamedTempFile::new()?;
+    file.write_all(FILE.as_bytes())?;
+    let mut store = MultiDigest::open(file.path())?;
+    assert_eq!(2, store.len());
+
+    let record = MultiDigestRecord::new(
+        "Mufasa",
+        "testrealm@host.com",
+        "Circle Of Life",
+        &[HashAlgorithm::Sha256],
+        false,
+    );
+    assert!(store.insert(record).is_some());
+    assert!(store.remove("Simba", "testrealm@host.com").is_some());
+    assert!(store.remove("Simba", "testrealm@host.com").is_none());
+    assert_eq!(
+        vec![HashAlgorithm::Sha256],
+        store.common_algorithms("testrealm@host.com")
+    );
+
+    writeln!(file, "Nala:testrealm@host.com:MD5={}", MD5_HA1)?;
+    store.reload()?;
+    assert_eq!(3, store.len());
+    Ok(())
+}
@@ -1,4 +1,4 @@
-// Copyright (c) 2015, 2016, 2020, 2025 Mark Lee
+// Copyright (c) 2015, 2016, 2020, 2025, 2026 Mark Lee
 //
 // Permission is hereby granted, free of charge, to any person obtaining a copy
 // of this software and associated documentation files (the "Software"), to deal
@@ -29,7 +29,7 @@ use thiserror::Error;
 use unicase::UniCase;
 
 /// Allowable hash algorithms for the `algorithm` parameter.
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum HashAlgorithm {
     /// `MD5`
     Md5,
@@ -89,6 +89,23 @@ impl fmt::Display for HashAlgorithm {
 }
 
 impl HashAlgorithm {
+    /// The non-session variant of the algorithm, i.e., the one that shares its hash function.
+    pub fn base(&self) -> HashAlgorithm {
+        match self {
+            Self::Md5 | Self::Md5Session => Self::Md5,
+            Self::Sha256 | Self::Sha256Session => Self::Sha256,
+            Self::Sha512256 | Self::Sha512256Session => Self::Sha512256,
+        }
+    }
+
+    /// The length of the hexadecimal digests generated by [`HashAlgorithm::hex_digest`].
+    pub fn hex_digest_len(&self) -> usize {
+        match self.base() {
+            Self::Md5 => 32,
+            _ => 64,
+        }
+    }
+
     /// Generate a hexadecimal representation of the output of a cryptographic hash function, given
     /// `data` and the algorithm.
     pub fn hex_digest(&self, data: &[u8]) -> String {
@@ -110,17 +127,26 @@ pub struct NonceCount(pub u32);
 
 impl FromStr for NonceCount {
     type Err = AuthorizationError;
+
+    /// Parses a nonce count, as defined in
+    /// [RFC 7616, section 3.4](https://tools.ietf.org/html/rfc7616#section-3.4).
+    ///
+    /// ## ABNF
+    ///
+    /// ```text
+    /// nc-value = 8LHEX
+    /// LHEX     = "0" / "1" / "2" / "3" /
+    ///            "4" / "5" / "6" / "7" /
+    ///            "8" / "9" / "a" / "b" /
+    ///            "c" / "d" / "e" / "f"
+    /// ```
     fn from_str(s: &str) -> Result<NonceCount, AuthorizationError> {
-        match base16ct::mixed::decode_vec(s) {
-            Ok(bytes) => {
-                let mut count: u32 = 0;
-                count |= (bytes[0] as u32) << 24;
-                count |= (bytes[1] as u32) << 16;
-                count |= (bytes[2] as u32) << 8;
-                count |= bytes[3] as u32;
-                Ok(NonceCount(count))
-            }
-            _ => Err(AuthorizationError::ParseNonceCount),
+        if s.len() != 8 || !s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+            return Err(AuthorizationError::ParseNonceCount);
+        }
+        match u32::from_str_radix(s, 16) {
+            Ok(count) => Ok(NonceCount(count)),
+            Err(_) => Err(AuthorizationError::ParseNonceCount),
         }
     }
 }
@@ -133,6 +159,14 @@ impl fmt::Display for NonceCount {
 }
 
 impl NonceCount {
+    /// The nonce count that a client should send with its next request using the same nonce.
+    ///
+    /// Returns `None` once the count cannot be incremented any further, at which point the client
+    /// needs to be issued a new nonce.
+    pub fn next(&self) -> Option<NonceCount> {
+        self.0.checked_add(1).map(NonceCount)
+    }
+
     /// Extracts an `NonceCount` object from a map of header parameters.
     /// Returns an error if the value is not a valid nonce count.
     pub fn from_parameters(
@@ -197,7 +231,9 @@ impl Qop {
 
 #[cfg(test)]
 mod tests {
-    use super::HashAlgorithm;
+    use super::{AuthorizationError, HashAlgorithm, NonceCount};
+    use proptest::prelude::*;
+    use std::str::FromStr;
 
     #[test]
     fn hash_algorithm_hex_digest_md5() {
@@ -226,4 +262,65 @@ mod tests {
             String::from("c672b8d1ef56ed28ab87c3622c5114069bdd3ad7b8f9737498d0c01ecef0967a")
         )
     }
+
+    #[test]
+    fn nonce_count_from_str() {
+        assert_eq!(Some(NonceCount(1)), NonceCount::from_str("00000001").ok());
+        assert_eq!(
+            Some(NonceCount(0xdeadbeef)),
+            NonceCount::from_str("deadbeef").ok()
+        );
+    }
+
+    #[test]
+    fn nonce_count_from_str_with_wrong_length() {
+        for value in ["", "00", "0000001", "000000001", "0000000001"] {
+            assert!(matches!(
+                NonceCount::from_str(value),
+                Err(AuthorizationError::ParseNonceCount)
+            ));
+        }
+    }
+
+    #[test]
+    fn nonce_count_from_str_with_uppercase_hex() {
+        assert!(NonceCount::from_str("DEADBEEF").is_err());
+    }
+
+    #[test]
+    fn nonce_count_next() {
+        assert_eq!(Some(NonceCount(2)), NonceCount(1).next());
+        assert_eq!(None, NonceCount(u32::MAX).next());
+    }
+
+    proptest! {
+        #[test]
+        fn nonce_count_round_trip(count: u32) {
+            let formatted = NonceCount(count).to_string();
+            prop_assert_eq!(8, formatted.len());
+            prop_assert_eq!(NonceCount(count), NonceCount::from_str(&formatted)?);
+        }
+
+        #[test]
+        fn nonce_count_rejects_non_lhex(value in "[0-9a-fA-F]{8}") {
+            prop_assume!(value.bytes().any(|b| b.is_ascii_uppercase()));
+            prop_assert!(NonceCount::from_str(&value).is_err());
+        }
+
+        #[test]
+        fn nonce_count_rejects_wrong_length(value in "[0-9a-f]{0,7}|[0-9a-f]{9,16}") {
+            prop_assert!(NonceCount::from_str(&value).is_err());
+        }
+
+        #[test]
+        fn nonce_count_rejects_anything_else(value in "\\PC*") {
+            let is_lhex = value.len() == 8 && value.bytes().all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b));
+            prop_assert_eq!(is_lhex, NonceCount::from_str(&value).is_ok());
+        }
+
+        #[test]
+        fn nonce_count_next_increments(count in 0..u32::MAX) {
+            prop_assert_eq!(Some(NonceCount(count + 1)), NonceCount(count).next());
+        }
+    }
 }
@@ -0,0 +1,132 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(feature = "cli")]
+
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Output, Stdio};
+
+const AUTHORIZATION: &str = "Authorization: Digest username=\"Mufasa\", \
+                             realm=\"testrealm@host.com\", \
+                             nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", \
+                             uri=\"/dir/index.html\", qop=auth, nc=00000001, \
+                             cnonce=\"0a4f113b\", response=\"6629fae49393a05397450978507c4ef1\", \
+                             opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"";
+
+fn debug(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guardhaus-debug"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Could not run guardhaus-debug");
+    // The tool may exit without reading its input, e.g. on a usage error.
+    if let Err(error) = child
+        .stdin
+        .take()
+        .expect("No stdin")
+        .write_all(stdin.as_bytes())
+    {
+        assert_eq!(
+            ErrorKind::BrokenPipe,
+            error.kind(),
+            "Could not write to stdin"
+        );
+    }
+    child
+        .wait_with_output()
+        .expect("Could not wait for guardhaus-debug")
+}
+
+#[test]
+fn test_decode_without_secret() {
+    let output = debug(&[AUTHORIZATION], "");
+    assert_eq!(Some(0), output.status.code());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("nonce:       dcd98b7102dd2f0e8b11d0f600bfb0c093\n"));
+    assert!(!stdout.contains("HA1"));
+}
+
+#[test]
+fn test_trace_with_password() {
+    let output = debug(&["-p", "Circle Of Life", AUTHORIZATION], "");
+    assert_eq!(Some(0), output.status.code());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("A1:          Mufasa:testrealm@host.com:Circle Of Life\n"));
+    assert!(stdout.contains("A2:          GET:/dir/index.html\n"));
+    assert!(stdout.contains("result:      match\n"));
+
+    let output = debug(&["-p", "Circle Of Life", "-m", "POST", AUTHORIZATION], "");
+    assert_eq!(Some(1), output.status.code());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("result:      MISMATCH\n"));
+}
+
+#[test]
+fn test_trace_with_ha1_and_response_auth_from_stdin() {
+    let stdin = format!(
+        "{}\nAuthentication-Info: rspauth=\"376602cfd2f4e8e5e78b948a85263e85\", qop=auth, \
+         nc=00000001, cnonce=\"0a4f113b\"\n",
+        AUTHORIZATION
+    );
+    let output = debug(&["--ha1", "939e7578ed9e3c518a452acee763bce9", "-"], &stdin);
+    assert_eq!(Some(0), output.status.code());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Response authentication\n"));
+    assert!(!stdout.contains("MISMATCH"));
+
+    let stdin = stdin.replace("376602cf", "00000000");
+    let output = debug(&["--ha1", "939e7578ed9e3c518a452acee763bce9", "-"], &stdin);
+    assert_eq!(Some(1), output.status.code());
+}
+
+#[test]
+fn test_challenges() {
+    let output = debug(
+        &[
+            "WWW-Authenticate: Digest realm=\"a\", nonce=\"b\", qop=\"auth\", algorithm=SHA-256, \
+           Digest realm=\"a\", nonce=\"b\"",
+        ],
+        "",
+    );
+    assert_eq!(Some(0), output.status.code());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("algorithm:   SHA-256\n"));
+    assert!(stdout.contains("algorithm:   MD5\n"));
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(Some(2), debug(&[], "").status.code());
+    assert_eq!(Some(2), debug(&["Digest username=\"a\""], "").status.code());
+    assert_eq!(
+        Some(2),
+        debug(&["Authorization: Digest username=\"a\""], "")
+            .status
+            .code()
+    );
+    assert_eq!(Some(2), debug(&["Date: today"], "").status.code());
+    assert_eq!(
+        Some(2),
+        debug(&["-p", "a", "--ha1", "b", AUTHORIZATION], "")
+            .status
+            .code()
+    );
+}
@@ -0,0 +1,289 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Sends requests through the `Digest` client integrations to an in-process server.
+
+#![cfg(any(feature = "tower", feature = "reqwest"))]
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use guardhaus::authentication_info::ResponseDigest;
+use guardhaus::authenticator::{AuthRequest, Authenticator};
+use guardhaus::digest::nonce::NonceGenerator;
+use guardhaus::digest::{Digest, DigestAuthenticator};
+use guardhaus::store::htdigest::{Htdigest, HtdigestRecord};
+use guardhaus::types::{HashAlgorithm, Qop};
+use headers::authorization::Credentials;
+use http::header::{
+    AUTHORIZATION, HeaderName, PROXY_AUTHENTICATE, PROXY_AUTHORIZATION, WWW_AUTHENTICATE,
+};
+use http::{HeaderValue, StatusCode};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const REALM: &str = "testrealm@host.com";
+
+#[derive(Clone)]
+struct Server {
+    authenticator: Arc<DigestAuthenticator<Htdigest>>,
+    requests: Arc<AtomicUsize>,
+    proxy: bool,
+    forge_rspauth: bool,
+}
+
+impl Server {
+    fn new(qop: Qop) -> Server {
+        let mut store = Htdigest::new();
+        store.insert(HtdigestRecord::new(
+            "Mufasa",
+            REALM,
+            "Circle Of Life".to_owned(),
+            &HashAlgorithm::Sha256,
+        ));
+        let authenticator = DigestAuthenticator::new(REALM, store)
+            .with_algorithms([HashAlgorithm::Sha256Session, HashAlgorithm::Md5])
+            .with_qop([qop])
+            .with_nonces(NonceGenerator::with_key([7; 32]));
+        Server {
+            authenticator: Arc::new(authenticator),
+            requests: Arc::default(),
+            proxy: false,
+            forge_rspauth: false,
+        }
+    }
+
+    fn requests(&self) -> usize {
+        self.requests.swap(0, Ordering::SeqCst)
+    }
+
+    fn router(&self) -> Router {
+        Router::new()
+            .route("/", get(|| async { "Hello, World!" }))
+            .route("/echo", post(|body: String| async move { body }))
+            .layer(middleware::from_fn_with_state(self.clone(), authenticate))
+    }
+}
+
+async fn authenticate(State(server): State<Server>, request: Request, next: Next) -> Response {
+    server.requests.fetch_add(1, Ordering::SeqCst);
+    let (status, challenge_header, authorization_header, info_header) = if server.proxy {
+        (
+            StatusCode::PROXY_AUTHENTICATION_REQUIRED,
+            PROXY_AUTHENTICATE,
+            PROXY_AUTHORIZATION,
+            HeaderName::from_static("proxy-authentication-info"),
+        )
+    } else {
+        (
+            StatusCode::UNAUTHORIZED,
+            WWW_AUTHENTICATE,
+            AUTHORIZATION,
+            HeaderName::from_static("authentication-info"),
+        )
+    };
+    let (parts, body) = request.into_parts();
+    let body = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .expect("Could not read body");
+    let authorization = parts.headers.get(&authorization_header).cloned();
+    let result = match authorization {
+        Some(ref value) => server
+            .authenticator
+            .authenticate(
+                value,
+                &AuthRequest::new(&parts.method, &parts.uri).with_body(&body),
+            )
+            .map_err(Some),
+        None => Err(None),
+    };
+    match result {
+        Ok(_) => {
+            let digest = Digest::decode(&authorization.expect("No credentials"))
+                .expect("Could not decode credentials");
+            let mut info = server
+                .authenticator
+                .authentication_info(&digest, b"")
+                .expect("No Authentication-Info");
+            if server.forge_rspauth {
+                info.digest = Some(ResponseDigest::ResponseAuth("0".repeat(64)));
+            }
+            let mut response = next.run(Request::from_parts(parts, Body::from(body))).await;
+            response.headers_mut().insert(
+                info_header,
+                HeaderValue::from_str(&info.to_string()).expect("Invalid header value"),
+            );
+            response
+        }
+        Err(error) => {
+            let mut response = status.into_response();
+            for challenge in server.authenticator.challenges(error.as_ref()) {
+                response.headers_mut().append(&challenge_header, challenge);
+            }
+            response
+        }
+    }
+}
+
+#[cfg(feature = "tower")]
+mod tower {
+    use super::Server;
+    use axum::body::Body;
+    use bytes::Bytes;
+    use guardhaus::digest::{DigestAuthLayer, DigestClientError};
+    use guardhaus::types::Qop;
+    use http::{Request, Response, StatusCode};
+    use http_body_util::{BodyExt, Full};
+    use std::future::poll_fn;
+    use tower_layer::Layer;
+    use tower_service::Service;
+
+    async fn send<S>(service: &mut S, request: Request<Full<Bytes>>) -> (StatusCode, String)
+    where
+        S: Service<Request<Full<Bytes>>, Response = Response<Body>, Error = DigestClientError>,
+    {
+        poll_fn(|cx| service.poll_ready(cx))
+            .await
+            .expect("Service is not ready");
+        let response = service.call(request).await.expect("Request failed");
+        let status = response.status();
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .expect("Could not read body")
+            .to_bytes();
+        (status, String::from_utf8_lossy(&body).into_owned())
+    }
+
+    fn get(uri: &str) -> Request<Full<Bytes>> {
+        Request::get(uri)
+            .body(Full::default())
+            .expect("Could not build request")
+    }
+
+    fn post(uri: &str, body: &'static str) -> Request<Full<Bytes>> {
+        Request::post(uri)
+            .body(Full::new(Bytes::from_static(body.as_bytes())))
+            .expect("Could not build request")
+    }
+
+    #[tokio::test]
+    async fn test_retry_and_preemptive_authorization() {
+        for qop in [Qop::Auth, Qop::AuthInt] {
+            let server = Server::new(qop);
+            let mut service =
+                DigestAuthLayer::new("Mufasa", "Circle Of Life").layer(server.router());
+
+            let response = send(&mut service, get("http://www.example.com/")).await;
+            assert_eq!((StatusCode::OK, "Hello, World!".to_owned()), response);
+            assert_eq!(2, server.requests());
+
+            let response = send(&mut service, post("http://www.example.com/echo", "roar")).await;
+            assert_eq!((StatusCode::OK, "roar".to_owned()), response);
+            assert_eq!(1, server.requests());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replayed_body() {
+        let server = Server::new(Qop::AuthInt);
+        let mut service = DigestAuthLayer::new("Mufasa", "Circle Of Life").layer(server.router());
+        let response = send(&mut service, post("http://www.example.com/echo", "roar")).await;
+        assert_eq!((StatusCode::OK, "roar".to_owned()), response);
+        assert_eq!(2, server.requests());
+    }
+
+    #[tokio::test]
+    async fn test_wrong_password() {
+        let server = Server::new(Qop::Auth);
+        let mut service = DigestAuthLayer::new("Mufasa", "Circle of Death").layer(server.router());
+        let (status, _) = send(&mut service, get("http://www.example.com/")).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, status);
+        assert_eq!(2, server.requests());
+
+        let (status, _) = send(&mut service, get("http://www.example.com/")).await;
+        assert_eq!(StatusCode::UNAUTHORIZED, status);
+        assert_eq!(1, server.requests());
+    }
+
+    #[tokio::test]
+    async fn test_forged_rspauth() {
+        let mut server = Server::new(Qop::Auth);
+        server.forge_rspauth = true;
+        let mut service = DigestAuthLayer::new("Mufasa", "Circle Of Life").layer(server.router());
+        poll_fn(|cx| Service::<Request<Full<Bytes>>>::poll_ready(&mut service, cx))
+            .await
+            .expect("Service is not ready");
+        let error = service
+            .call(get("http://www.example.com/"))
+            .await
+            .expect_err("rspauth should be rejected");
+        assert!(matches!(error, DigestClientError::InvalidResponseAuth));
+    }
+
+    #[tokio::test]
+    async fn test_proxy_authentication() {
+        let mut server = Server::new(Qop::Auth);
+        server.proxy = true;
+        let mut service = DigestAuthLayer::new("Mufasa", "Circle Of Life").layer(server.router());
+        let response = send(&mut service, get("http://www.example.com/")).await;
+        assert_eq!((StatusCode::OK, "Hello, World!".to_owned()), response);
+        assert_eq!(2, server.requests());
+    }
+}
+
+#[cfg(feature = "reqwest")]
+mod reqwest {
+    use super::Server;
+    use guardhaus::digest::DigestAuthMiddleware;
+    use guardhaus::types::Qop;
+    use http::StatusCode;
+
+    #[tokio::test]
+    async fn test_middleware() {
+        let server = Server::new(Qop::Auth);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Could not bind");
+        let address = listener.local_addr().expect("No local address");
+        let router = server.router();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+            .with(DigestAuthMiddleware::new("Mufasa", "Circle Of Life"))
+            .build();
+        let url = format!("http://{}/echo", address);
+        for expected_requests in [2, 1] {
+            let response = client
+                .post(&url)
+                .body("roar")
+                .send()
+                .await
+                .expect("Request failed");
+            assert_eq!(StatusCode::OK, response.status());
+            assert_eq!("roar", response.text().await.expect("Could not read body"));
+            assert_eq!(expected_requests, server.requests());
+        }
+    }
+}
@@ -0,0 +1,214 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+//! Drives sequences of requests through a `Digest` client and server, to check that the server
+//! issues `nextnonce` according to its policy and that the client switches to it.
+
+use guardhaus::authentication_info::AuthenticationInfo;
+use guardhaus::authenticator::{AuthRequest, Authenticator};
+use guardhaus::challenge::WwwAuthenticate;
+use guardhaus::digest::nonce::{NonceCountTracker, NonceGenerator};
+use guardhaus::digest::{Digest, DigestAuthenticator, DigestChallenge, DigestCredentialCache};
+use guardhaus::store::htdigest::{Htdigest, HtdigestRecord};
+use guardhaus::types::{HashAlgorithm, NonceCount};
+use headers::authorization::Credentials;
+use headers::{Authorization, HeaderMapExt};
+use http::header::{AUTHORIZATION, WWW_AUTHENTICATE};
+use http::{HeaderMap, Method, StatusCode, Uri};
+use std::time::Duration;
+
+const REALM: &str = "testrealm@host.com";
+
+struct Server {
+    authenticator: DigestAuthenticator<Htdigest>,
+}
+
+impl Server {
+    fn new(nonces: NonceCountTracker) -> Server {
+        let mut store = Htdigest::new();
+        store.insert(HtdigestRecord::new(
+            "Mufasa",
+            REALM,
+            "Circle Of Life".to_owned(),
+            &HashAlgorithm::Md5,
+        ));
+        Server {
+            authenticator: DigestAuthenticator::new(REALM, store).with_nonce_tracker(nonces),
+        }
+    }
+
+    fn handle(&self, method: &Method, uri: &Uri, headers: &HeaderMap) -> (StatusCode, HeaderMap) {
+        let mut response = HeaderMap::new();
+        let result = match headers.get(AUTHORIZATION) {
+            Some(value) => self
+                .authenticator
+                .authenticate(value, &AuthRequest::new(method, uri))
+                .map_err(Some),
+            None => Err(None),
+        };
+        match result {
+            Ok(_) => {
+                let digest = headers
+                    .typed_get::<Authorization<Digest>>()
+                    .expect("No credentials")
+                    .0;
+                let info = self
+                    .authenticator
+                    .authentication_info(&digest, b"")
+                    .expect("No Authentication-Info");
+                response.typed_insert(info);
+                (StatusCode::OK, response)
+            }
+            Err(error) => {
+                for challenge in self.authenticator.challenges(error.as_ref()) {
+                    response.append(WWW_AUTHENTICATE, challenge);
+                }
+                (StatusCode::UNAUTHORIZED, response)
+            }
+        }
+    }
+}
+
+/// The nonce and nonce count used for a successful request, and whether it needed a challenge.
+#[derive(Debug, PartialEq)]
+struct Exchange {
+    nonce: String,
+    nonce_count: NonceCount,
+    challenged: bool,
+}
+
+struct Client {
+    cache: DigestCredentialCache,
+}
+
+impl Client {
+    fn new() -> Client {
+        Client {
+            cache: DigestCredentialCache::new(),
+        }
+    }
+
+    fn attempt(&mut self, server: &Server, uri: &Uri) -> (StatusCode, HeaderMap, Option<Digest>) {
+        let mut request = HeaderMap::new();
+        let digest = self
+            .cache
+            .authorization(Method::GET, uri, b"")
+            .map(|digest| digest.expect("Could not compute response"));
+        if let Some(ref digest) = digest {
+            request.insert(AUTHORIZATION, digest.encode());
+        }
+        let target = Uri::try_from(uri.path()).expect("Invalid URI");
+        let (status, response) = server.handle(&Method::GET, &target, &request);
+        (status, response, digest)
+    }
+
+    fn get(&mut self, server: &Server, uri: &Uri) -> Exchange {
+        let (mut status, mut response, mut digest) = self.attempt(server, uri);
+        let challenged = status == StatusCode::UNAUTHORIZED;
+        if challenged {
+            let challenge =
+                WwwAuthenticate::<DigestChallenge>::decode_all(response.get_all(WWW_AUTHENTICATE))
+                    .expect("Could not decode challenges")
+                    .remove(0);
+            if !self.cache.handle_challenge(uri, challenge.clone()) {
+                self.cache
+                    .set_credentials(uri, &challenge.realm, "Mufasa", "Circle Of Life");
+            }
+            (status, response, digest) = self.attempt(server, uri);
+        }
+        assert_eq!(StatusCode::OK, status);
+
+        let info = response
+            .typed_get::<AuthenticationInfo>()
+            .expect("No Authentication-Info");
+        self.cache.handle_authentication_info(uri, &info);
+        let digest = digest.expect("No credentials were sent");
+        Exchange {
+            nonce: digest.nonce,
+            nonce_count: digest.nonce_count.expect("No nonce count"),
+            challenged,
+        }
+    }
+}
+
+#[test]
+fn test_next_nonce_after_uses() {
+    let server = Server::new(
+        NonceCountTracker::new(NonceGenerator::with_key([7; 32])).with_next_nonce_after_uses(3),
+    );
+    let mut client = Client::new();
+    let uri = Uri::from_static("http://www.example.com/dir/index.html");
+
+    let exchanges: Vec<Exchange> = (0..7).map(|_| client.get(&server, &uri)).collect();
+    assert!(exchanges[0].challenged);
+    assert!(exchanges[1..].iter().all(|exchange| !exchange.challenged));
+
+    let counts: Vec<u32> = exchanges
+        .iter()
+        .map(|exchange| exchange.nonce_count.0)
+        .collect();
+    assert_eq!(vec![1, 2, 3, 1, 2, 3, 1], counts);
+    assert_eq!(exchanges[0].nonce, exchanges[2].nonce);
+    assert_ne!(exchanges[2].nonce, exchanges[3].nonce);
+    assert_eq!(exchanges[3].nonce, exchanges[5].nonce);
+    assert_ne!(exchanges[5].nonce, exchanges[6].nonce);
+}
+
+#[test]
+fn test_next_nonce_after_age() {
+    let server = Server::new(
+        NonceCountTracker::new(NonceGenerator::with_key([7; 32]))
+            .with_next_nonce_after(Duration::ZERO),
+    );
+    let mut client = Client::new();
+    let uri = Uri::from_static("http://www.example.com/");
+
+    let exchanges: Vec<Exchange> = (0..3).map(|_| client.get(&server, &uri)).collect();
+    assert!(
+        exchanges
+            .iter()
+            .skip(1)
+            .all(|exchange| !exchange.challenged)
+    );
+    assert!(
+        exchanges
+            .iter()
+            .all(|exchange| exchange.nonce_count == NonceCount(1))
+    );
+    assert_ne!(exchanges[0].nonce, exchanges[1].nonce);
+    assert_ne!(exchanges[1].nonce, exchanges[2].nonce);
+}
+
+#[test]
+fn test_stale_nonce_without_next_nonce() {
+    let server = Server::new(NonceCountTracker::new(
+        NonceGenerator::with_key([7; 32]).with_lifetime(Duration::from_millis(50)),
+    ));
+    let mut client = Client::new();
+    let uri = Uri::from_static("http://www.example.com/");
+
+    let first = client.get(&server, &uri);
+    assert!(first.challenged);
+    std::thread::sleep(Duration::from_millis(60));
+    let second = client.get(&server, &uri);
+    assert!(second.challenged);
+    assert_ne!(first.nonce, second.nonce);
+    assert_eq!(NonceCount(1), second.nonce_count);
+}
@@ -0,0 +1,213 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+fn htdigest(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_guardhaus-htdigest"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Could not run guardhaus-htdigest");
+    // The tool may exit without reading its input, e.g. on a usage error.
+    if let Err(error) = child
+        .stdin
+        .take()
+        .expect("No stdin")
+        .write_all(stdin.as_bytes())
+    {
+        assert_eq!(
+            ErrorKind::BrokenPipe,
+            error.kind(),
+            "Could not write to stdin"
+        );
+    }
+    child
+        .wait_with_output()
+        .expect("Could not wait for guardhaus-htdigest")
+}
+
+fn path_str(path: &Path) -> &str {
+    path.to_str().expect("Non-UTF-8 path")
+}
+
+#[test]
+fn test_create_add_update_delete() {
+    let dir = tempfile::tempdir().expect("Could not create temporary directory");
+    let path = dir.path().join("htdigest");
+    let file = path_str(&path);
+    let realm = "testrealm@host.com";
+
+    let output = htdigest(&["-i", "create", file, realm, "Mufasa"], "Circle Of Life\n");
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!(
+        "Mufasa:testrealm@host.com:939e7578ed9e3c518a452acee763bce9\n",
+        fs::read_to_string(&path).expect("Could not read file")
+    );
+    let output = htdigest(&["-i", "create", file, realm, "Mufasa"], "Circle Of Life\n");
+    assert_eq!(Some(1), output.status.code());
+
+    let output = htdigest(
+        &["--stdin", "-a", "SHA-256", "add", file, realm, "Simba"],
+        "Hakuna Matata\r\n",
+    );
+    assert_eq!(Some(0), output.status.code());
+    let output = htdigest(&["-i", "add", file, realm, "Simba"], "Hakuna Matata\n");
+    assert_eq!(Some(8), output.status.code());
+
+    let output = htdigest(&["-i", "update", file, realm, "Mufasa"], "Pride Rock\n");
+    assert_eq!(Some(0), output.status.code());
+    let output = htdigest(&["-i", "update", file, realm, "Scar"], "Pride Rock\n");
+    assert_eq!(Some(8), output.status.code());
+
+    let output = htdigest(&["list", file], "");
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!(
+        "Mufasa:testrealm@host.com\nSimba:testrealm@host.com\n",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    let output = htdigest(&["list", file, "other"], "");
+    assert_eq!("", String::from_utf8_lossy(&output.stdout));
+
+    let output = htdigest(&["delete", file, realm, "Mufasa"], "");
+    assert_eq!(Some(0), output.status.code());
+    let output = htdigest(&["delete", file, realm, "Mufasa"], "");
+    assert_eq!(Some(8), output.status.code());
+    assert!(!dir.path().join("htdigest.lock").exists());
+}
+
+#[test]
+fn test_verify() {
+    let dir = tempfile::tempdir().expect("Could not create temporary directory");
+    let path = dir.path().join("htdigest");
+    let file = path_str(&path);
+    let realm = "testrealm@host.com";
+    htdigest(
+        &["-i", "-a", "SHA-512-256", "create", file, realm, "Mufasa"],
+        "Circle Of Life\n",
+    );
+
+    let output = htdigest(&["-i", "verify", file, realm, "Mufasa"], "Circle Of Life\n");
+    assert_eq!(Some(0), output.status.code());
+    let output = htdigest(&["-i", "verify", file, realm, "Mufasa"], "Circle of Life\n");
+    assert_eq!(Some(3), output.status.code());
+    let output = htdigest(
+        &["-i", "-a", "SHA-256", "verify", file, realm, "Mufasa"],
+        "Circle Of Life\n",
+    );
+    assert_eq!(Some(3), output.status.code());
+    let output = htdigest(&["-i", "verify", file, realm, "Scar"], "Circle Of Life\n");
+    assert_eq!(Some(8), output.status.code());
+}
+
+#[test]
+fn test_errors() {
+    let dir = tempfile::tempdir().expect("Could not create temporary directory");
+    let path = dir.path().join("htdigest");
+    let file = path_str(&path);
+
+    assert_eq!(Some(2), htdigest(&[], "").status.code());
+    assert_eq!(
+        Some(2),
+        htdigest(&["frobnicate", file, "a", "b"], "").status.code()
+    );
+    assert_eq!(
+        Some(2),
+        htdigest(&["-a", "SHA-256-sess", "create", file, "a", "b"], "")
+            .status
+            .code()
+    );
+    assert_eq!(
+        Some(6),
+        htdigest(&["-i", "create", file, "a:b", "c"], "x\n")
+            .status
+            .code()
+    );
+    assert_eq!(Some(1), htdigest(&["list", file], "").status.code());
+
+    fs::write(&path, "Mufasa\n").expect("Could not write file");
+    assert_eq!(Some(7), htdigest(&["list", file], "").status.code());
+
+    fs::write(dir.path().join("htdigest.lock"), "").expect("Could not write lock file");
+    assert_eq!(
+        Some(1),
+        htdigest(&["delete", file, "a", "Mufasa"], "").status.code()
+    );
+    assert_eq!(
+        "Mufasa\n",
+        fs::read_to_string(&path).expect("Could not read file")
+    );
+}
+
+#[test]
+fn test_migrate() {
+    let dir = tempfile::tempdir().expect("Could not create temporary directory");
+    let path = dir.path().join("htdigest");
+    let new_path = dir.path().join("multi");
+    let (file, new_file) = (path_str(&path), path_str(&new_path));
+    htdigest(
+        &["-i", "create", file, "testrealm@host.com", "Mufasa"],
+        "Circle Of Life\n",
+    );
+
+    let output = htdigest(&["-a", "SHA-256", "migrate", file, new_file], "");
+    assert_eq!(Some(7), output.status.code());
+    assert!(!new_path.exists());
+
+    let output = htdigest(&["--userhash", "migrate", file, new_file], "");
+    assert_eq!(Some(0), output.status.code());
+    assert_eq!(
+        "Mufasa:testrealm@host.com:MD5=939e7578ed9e3c518a452acee763bce9:\
+         MD5=74f54fe2c8045a5ffda7d02fd97f1716\n",
+        fs::read_to_string(&new_path).expect("Could not read file")
+    );
+    let output = htdigest(&["migrate", file, new_file], "");
+    assert_eq!(Some(1), output.status.code());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_keeps_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().expect("Could not create temporary directory");
+    let path = dir.path().join("htdigest");
+    let file = path_str(&path);
+    let realm = "testrealm@host.com";
+
+    let output = htdigest(&["-i", "create", file, realm, "Mufasa"], "Circle Of Life\n");
+    assert_eq!(Some(0), output.status.code());
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+        .expect("Could not set permissions");
+    let output = htdigest(&["-i", "add", file, realm, "Simba"], "Hakuna Matata\n");
+    assert_eq!(Some(0), output.status.code());
+    let mode = fs::metadata(&path)
+        .expect("Could not read metadata")
+        .permissions()
+        .mode();
+    assert_eq!(0o600, mode & 0o777);
+}
@@ -0,0 +1,191 @@
+// Copyright (c) 2026 Mark Lee
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT.  IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN
+// THE SOFTWARE.
+
+#![cfg(feature = "serde")]
+
+//! Round-trips the `Digest` and `Authentication-Info` header types through JSON.
+
+use guardhaus::authentication_info::AuthenticationInfo;
+use guardhaus::digest::{Digest, Username};
+use guardhaus::types::{HashAlgorithm, NonceCount, Qop};
+use headers::HeaderValue;
+use headers::authorization::Credentials;
+use serde_json::json;
+
+fn digest(value: &'static str) -> Digest {
+    Digest::decode(&HeaderValue::from_static(value)).expect("Could not decode Digest header")
+}
+
+#[test]
+fn test_digest_round_trip() {
+    let digest = digest(
+        "Digest username*=UTF-8''J%C3%A4s%C3%B8n%20Doe, realm=\"api@example.org\", \
+         uri=\"/doe.json\", algorithm=SHA-512-256-sess, \
+         nonce=\"5TsQWLVdgBdmrQ0XsxbDODV+57QdFR34I9HAbC/RVvkK\", nc=0000000a, \
+         cnonce=\"NTg6RKcb9boFIAS3KrFK9BGeh+iDa/sm6jUMp2wds69v\", qop=auth-int, \
+         response=\"3798d4131c277846293534c3edc11bd8a5e4cdcbff78b05db9d95eeb1cec68a5\", \
+         opaque=\"HRPCssKJSGjCrkzDg8OhwpzCiGPChXYjwrI2QmXDnsOS\", charset=UTF-8",
+    );
+    let value = serde_json::to_value(&digest).expect("Could not serialize");
+    assert_eq!(
+        json!({
+            "username*": "UTF-8''J%C3%A4s%C3%B8n%20Doe",
+            "realm": "api@example.org",
+            "nonce": "5TsQWLVdgBdmrQ0XsxbDODV+57QdFR34I9HAbC/RVvkK",
+            "nc": "0000000a",
+            "response": "3798d4131c277846293534c3edc11bd8a5e4cdcbff78b05db9d95eeb1cec68a5",
+            "uri": "/doe.json",
+            "algorithm": "SHA-512-256-sess",
+            "qop": "auth-int",
+            "cnonce": "NTg6RKcb9boFIAS3KrFK9BGeh+iDa/sm6jUMp2wds69v",
+            "opaque": "HRPCssKJSGjCrkzDg8OhwpzCiGPChXYjwrI2QmXDnsOS",
+            "charset": "UTF-8",
+            "userhash": false,
+        }),
+        value
+    );
+    assert_eq!(
+        digest,
+        serde_json::from_value(value).expect("Could not deserialize")
+    );
+}
+
+#[test]
+fn test_digest_without_optional_parameters() {
+    let digest: Digest = serde_json::from_value(json!({
+        "username": "Mufasa",
+        "realm": "testrealm@host.com",
+        "nonce": "dcd98b7102dd2f0e8b11d0f600bfb0c093",
+        "response": "1949323746fe6a43ef61f9606e7febea",
+        "uri": "/dir/index.html",
+        "algorithm": "MD5",
+    }))
+    .expect("Could not deserialize");
+    assert_eq!(Username::Plain("Mufasa".to_owned()), digest.username);
+
+    let mut digest = digest;
+    digest.username = Username::Plain("UTF-8''Mufasa".to_owned());
+    let value = serde_json::to_value(&digest).expect("Could not serialize");
+    assert_eq!(json!("UTF-8''Mufasa"), value["username"]);
+    assert_eq!(
+        digest,
+        serde_json::from_value(value).expect("Could not deserialize")
+    );
+    assert_eq!(None, digest.nonce_count);
+    assert_eq!(None, digest.qop);
+    assert!(!digest.userhash);
+    let value = serde_json::to_value(&digest).expect("Could not serialize");
+    assert_eq!(None, value.get("nc"));
+    assert_eq!(None, value.get("cnonce"));
+}
+
+#[test]
+fn test_redacted_digest() {
+    let digest = digest(
+        "Digest username=\"Mufasa\", realm=\"testrealm@host.com\", \
+         nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", uri=\"/dir/index.html\", qop=auth, \
+         nc=00000001, cnonce=\"0a4f113b\", response=\"6629fae49393a05397450978507c4ef1\"",
+    );
+    let value = serde_json::to_value(digest.redacted()).expect("Could not serialize");
+    assert_eq!(json!("[redacted]"), value["response"]);
+    assert_eq!(json!("testrealm@host.com"), value["realm"]);
+    assert!(
+        !value
+            .to_string()
+            .contains("6629fae49393a05397450978507c4ef1")
+    );
+}
+
+#[test]
+fn test_authentication_info_round_trip() {
+    let info: AuthenticationInfo = "rspauth=\"6629fae49393a05397450978507c4ef1\", qop=auth, \
+                                    nc=00000001, cnonce=\"0a4f113b\", nextnonce=\"abcd\""
+        .parse()
+        .expect("Could not parse Authentication-Info");
+    let value = serde_json::to_value(&info).expect("Could not serialize");
+    assert_eq!(
+        json!({
+            "rspauth": "6629fae49393a05397450978507c4ef1",
+            "nextnonce": "abcd",
+            "qop": "auth",
+            "cnonce": "0a4f113b",
+            "nc": "00000001",
+        }),
+        value
+    );
+    assert_eq!(
+        info,
+        serde_json::from_value(value).expect("Could not deserialize")
+    );
+
+    let info: AuthenticationInfo = "digest=\"abcd\"".parse().expect("Could not parse");
+    let value = serde_json::to_value(&info).expect("Could not serialize");
+    assert_eq!(json!({"digest": "abcd"}), value);
+    assert_eq!(
+        info,
+        serde_json::from_value(value).expect("Could not deserialize")
+    );
+
+    let info: AuthenticationInfo =
+        serde_json::from_value(json!({"nextnonce": "abcd"})).expect("Could not deserialize");
+    assert_eq!(None, info.digest);
+}
+
+#[test]
+fn test_string_forms() {
+    assert_eq!(
+        json!("SHA-256-sess"),
+        serde_json::to_value(HashAlgorithm::Sha256Session).expect("Could not serialize")
+    );
+    assert_eq!(
+        json!("00000001"),
+        serde_json::to_value(NonceCount(1)).expect("Could not serialize")
+    );
+    assert_eq!(
+        json!("auth-int"),
+        serde_json::to_value(Qop::AuthInt).expect("Could not serialize")
+    );
+    let digest_with = |username: &str, charset: &str| {
+        serde_json::from_value::<Digest>(json!({
+            "username*": username,
+            "realm": "testrealm@host.com",
+            "nonce": "dcd98b7102dd2f0e8b11d0f600bfb0c093",
+            "response": "1949323746fe6a43ef61f9606e7febea",
+            "uri": "/dir/index.html",
+            "algorithm": "MD5",
+            "charset": charset,
+        }))
+    };
+    let digest =
+        digest_with("iso-8859-1'en'%A3%20rates", "X-CUSTOM").expect("Could not deserialize");
+    assert_eq!(
+        Some("X-CUSTOM".to_owned()),
+        digest.charset.map(|charset| charset.to_string())
+    );
+    match digest.username {
+        Username::Encoded(value) => assert_eq!("ISO-8859-1", value.charset.to_string()),
+        Username::Plain(_) => panic!("Expected an encoded username"),
+    }
+
+    assert!(serde_json::from_value::<HashAlgorithm>(json!("SHA-1")).is_err());
+    assert!(serde_json::from_value::<NonceCount>(json!("1")).is_err());
+    assert!(serde_json::from_value::<Qop>(json!("auth-conf")).is_err());
+    assert!(digest_with("no quotes", "UTF-8").is_err());
+}